@@ -27,6 +27,7 @@ pub enum AssetProblem {
     MapParallaxTooBig { width: u32, height: u32, para_width: u32, para_height: u32 },
     MapInvalidTile { tile_x: u32, tile_y: u32, tile: u8, layer: MapLayer },
     MapTransparentTile { first_tile_x: u32, first_tile_y: u32, num_tiles: u32 },
+    MapUnknownEffect { first_tile_x: u32, first_tile_y: u32, value: u8, num_tiles: u32 },
     SpriteTooBig { num_frames: u32 },
     PalSpriteTooBig { num_frames: u32 },
     PalSpriteColorOutOfPalette { frame_num: u32, num_pixels: u64 },
@@ -94,6 +95,16 @@ impl AssetProblem {
                 ));
             }
 
+            AssetProblem::MapUnknownEffect { first_tile_x, first_tile_y, value, num_tiles } => {
+                ui.label(format!(
+                    "  -> map has unknown effect type {} at ({}, {}), total of {} tile(s) with unknown effect types",
+                    value,
+                    first_tile_x,
+                    first_tile_y,
+                    num_tiles
+                ));
+            }
+
             AssetProblem::SpriteTooBig { num_frames } => {
                 ui.label(format!("  -> sprite has too many frames: {} (max is 255)", num_frames));
             }
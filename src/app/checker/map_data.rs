@@ -146,6 +146,34 @@ fn check_map_tiles(map_data: &MapData, tileset: &Tileset, problems: &mut Vec<Ass
     }
 }
 
+fn check_map_effects(map_data: &MapData, num_fx_types: usize, problems: &mut Vec<AssetProblem>) {
+    // without effect types, the effects layer values are not checked
+    if num_fx_types == 0 { return; }
+
+    let mut num_bad_tiles = 0;
+    let mut first_bad_tile = None;
+    for y in 0..map_data.height {
+        for x in 0..map_data.width {
+            let value = map_data.fx_tiles[(y*map_data.width + x) as usize];
+            if value != MapData::NO_TILE && value as usize >= num_fx_types {
+                if first_bad_tile.is_none() {
+                    first_bad_tile = Some((x, y, value));
+                }
+                num_bad_tiles += 1;
+            }
+        }
+    }
+
+    if let Some((first_tile_x, first_tile_y, value)) = first_bad_tile {
+        problems.push(AssetProblem::MapUnknownEffect {
+            first_tile_x,
+            first_tile_y,
+            value,
+            num_tiles: num_bad_tiles,
+        });
+    }
+}
+
 fn check_map_size(map_data: &MapData, problems: &mut Vec<AssetProblem>) {
     if map_data.para_width != 0 && map_data.height != 0 &&
         ((map_data.para_width * Tileset::TILE_SIZE) < SCREEN_WIDTH ||
@@ -174,6 +202,7 @@ pub fn check_maps(asset_problems: &mut BTreeMap<DataAssetId, Vec<AssetProblem>>,
         if let Some(tileset) = store.assets.tilesets.get(&map_data.tileset_id) {
             check_map_size(map_data, &mut map_problems);
             check_map_tiles(map_data, tileset, &mut map_problems);
+            check_map_effects(map_data, store.map_fx_types.len(), &mut map_problems);
             let tileset_transp = tileset_transp_map.entry(map_data.tileset_id).or_insert_with(|| build_tileset_transparency(tileset));
             check_map_transparency(map_data, tileset_transp, &mut map_problems);
         } else {
@@ -0,0 +1,71 @@
+use super::{
+    SysDialogs,
+    DialogResult,
+    AppWindowTracker,
+    MapLayer,
+    create_dialog_window,
+};
+
+pub struct ImportLevelDialog {
+    id: egui::Id,
+    open: bool,
+    pub layer: MapLayer,
+    pub report_flipped: bool,
+}
+
+impl ImportLevelDialog {
+    const WINDOW_WIDTH: f32 = 350.0;
+
+    pub fn new() -> Self {
+        ImportLevelDialog {
+            id: egui::Id::new("dlg_import_level"),
+            open: false,
+            layer: MapLayer::Foreground,
+            report_flipped: false,
+        }
+    }
+
+    pub fn set_open(&mut self, wt: &mut AppWindowTracker) {
+        self.open = true;
+        wt.set_dialog_open(self.id, self.open);
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, wt: &mut AppWindowTracker, sys_dialogs: &SysDialogs) -> DialogResult {
+        if ! self.open { return DialogResult::None; }
+
+        let resp = create_dialog_window(sys_dialogs, ui, self.id, Self::WINDOW_WIDTH, "Import Level Image", |ui| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                    ui.label("Create a tileset with the distinct tiles of an image and a map using them.");
+                    ui.label("A map can use up to 255 tiles; if the image has more, only the first 255 are imported.");
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        ui.label("Map layer:");
+                        ui.radio_value(&mut self.layer, MapLayer::Foreground, "Foreground");
+                        ui.radio_value(&mut self.layer, MapLayer::Background, "Background");
+                    });
+                    ui.checkbox(&mut self.report_flipped, "Report tiles that are flipped copies of other tiles");
+                });
+            });
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                    DialogResult::No
+                } else if ui.button("Open File").clicked() {
+                    ui.close();
+                    DialogResult::Yes
+                } else {
+                    DialogResult::None
+                }
+            }).inner
+        });
+        if resp.should_close() {
+            self.open = false;
+            wt.set_dialog_open(self.id, self.open);
+            if resp.inner == DialogResult::None {
+                return DialogResult::Cancel;
+            }
+        }
+        resp.inner
+    }
+}
@@ -3,15 +3,18 @@ mod message_box;
 mod confirmation;
 mod colorset;
 mod text_input;
+mod import_level;
 
 use about::{*};
 use message_box::{*};
 use confirmation::{*};
 use colorset::{*};
 use text_input::{*};
+use import_level::{*};
 
 use crate::misc::IMAGES;
 
+use super::editors::MapLayer;
 use super::{
     SysDialogs,
     AppSettings,
@@ -32,6 +35,7 @@ pub struct AppDialogs {
     confirmation: ConfirmationDialog,
     colorset: ColorsetEditorDialog,
     text_input: TextInputDialog,
+    import_level: ImportLevelDialog,
 }
 
 impl AppDialogs {
@@ -42,6 +46,7 @@ impl AppDialogs {
             confirmation: ConfirmationDialog::new(),
             colorset: ColorsetEditorDialog::new(),
             text_input: TextInputDialog::new(),
+            import_level: ImportLevelDialog::new(),
         }
     }
 
@@ -76,6 +81,10 @@ impl AppDialogs {
         self.about.set_open(wt);
     }
 
+    pub fn open_import_level_dialog(&mut self, wt: &mut AppWindowTracker) {
+        self.import_level.set_open(wt);
+    }
+
     pub fn open_colorset_dialog(&mut self, wt: &mut AppWindowTracker, colorset: usize) {
         self.colorset.set_open(wt, colorset);
     }
@@ -98,6 +107,14 @@ impl AppDialogs {
     pub fn get_text_input_dialog_input(&mut self) -> String {
         self.text_input.get_input()
     }
+
+    pub fn show_import_level_dialog(&mut self, ui: &mut egui::Ui, wt: &mut AppWindowTracker, sys_dialogs: &SysDialogs) -> DialogResult {
+        self.import_level.show(ui, wt, sys_dialogs)
+    }
+
+    pub fn get_import_level_options(&self) -> (MapLayer, bool) {
+        (self.import_level.layer, self.import_level.report_flipped)
+    }
 }
 
 pub fn create_dialog_window<T>(
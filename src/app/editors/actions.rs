@@ -10,6 +10,13 @@ use super::{
     fix_after_sprite_frames_removed,
     fix_after_pal_sprite_frames_added,
     fix_after_pal_sprite_frames_removed,
+    remap_image_asset_colors,
+    merge_tileset_tiles,
+    undo_tileset_tiles_merge,
+    reorder_tileset_tiles,
+    offset_map_in_rooms,
+    set_map_room_placements,
+    MapRoomPlacement,
     WindowContext,
     EditorStore,
 };
@@ -21,6 +28,13 @@ pub enum EditorAction {
     SpriteFramesRemoved { sprite_id: DataAssetId, hole_start: u32, hole_size: u32, num_frames_after_hole: u32 },
     PalSpriteFramesAdded { pal_sprite_id: DataAssetId, hole_start: u32, hole_size: u32, num_frames_after_hole: u32 },
     PalSpriteFramesRemoved { pal_sprite_id: DataAssetId, hole_start: u32, hole_size: u32, num_frames_after_hole: u32 },
+    RemapColors { asset_ids: Vec<DataAssetId>, frame: Option<u32>, color_map: Box<[u8; 256]> },
+    MergeTilesetTiles { tileset_id: DataAssetId, merges: Vec<(u8, u8)> },
+    UndoMergeTilesetTiles { tileset_id: DataAssetId },
+    ReorderTilesetTiles { tileset_id: DataAssetId, order: Vec<u8> },
+    OffsetMapInRooms { map_id: DataAssetId, offset: (i32, i32) },
+    RestoreMapRoomPlacements { map_id: DataAssetId, placements: Vec<MapRoomPlacement>, from_undo: bool },
+    ImageAssetRenamed,
 }
 
 impl EditorAction {
@@ -49,6 +63,44 @@ impl EditorAction {
             EditorAction::PalSpriteFramesRemoved { pal_sprite_id, hole_start, hole_size, num_frames_after_hole } => {
                 fix_after_pal_sprite_frames_removed(wc, store, editors, pal_sprite_id, hole_start, hole_size, num_frames_after_hole);
             }
+
+            EditorAction::RemapColors { asset_ids, frame, color_map } => {
+                remap_image_asset_colors(wc, store, editors, &asset_ids, frame, &color_map);
+            }
+
+            EditorAction::MergeTilesetTiles { tileset_id, merges } => {
+                merge_tileset_tiles(wc, store, editors, tileset_id, &merges);
+            }
+
+            EditorAction::UndoMergeTilesetTiles { tileset_id } => {
+                undo_tileset_tiles_merge(wc, store, editors, tileset_id);
+            }
+
+            EditorAction::ReorderTilesetTiles { tileset_id, order } => {
+                reorder_tileset_tiles(wc, store, editors, tileset_id, &order);
+            }
+
+            EditorAction::OffsetMapInRooms { map_id, offset } => {
+                let old_placements = offset_map_in_rooms(wc, store, map_id, offset);
+                if ! old_placements.is_empty() && let Some(editor) = editors.maps.get_mut(&map_id) {
+                    editor.set_undo_room_placements(old_placements);
+                }
+            }
+
+            EditorAction::RestoreMapRoomPlacements { map_id, placements, from_undo } => {
+                let old_placements = set_map_room_placements(store, map_id, &placements);
+                if let Some(editor) = editors.maps.get_mut(&map_id) {
+                    if from_undo {
+                        editor.set_redo_room_placements(old_placements);
+                    } else {
+                        editor.set_undo_room_placements(old_placements);
+                    }
+                }
+            }
+
+            EditorAction::ImageAssetRenamed => {
+                editors.set_image_asset_names_dirty();
+            }
         }
     }
 }
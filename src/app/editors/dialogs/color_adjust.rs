@@ -0,0 +1,157 @@
+use crate::data_asset::DataAssetId;
+use crate::image::{
+    ImageCollection,
+    ColorAdjustment,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+};
+use super::draw_color_map_preview;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AdjustScope {
+    Selection,
+    CurrentImage,
+    AllImages,
+}
+
+impl AdjustScope {
+    pub fn text(&self) -> &'static str {
+        match self {
+            AdjustScope::Selection => "selection",
+            AdjustScope::CurrentImage => "current image",
+            AdjustScope::AllImages => "all images of this asset",
+        }
+    }
+}
+
+pub struct ColorAdjustDialog {
+    pub open: bool,
+    pub scope: AdjustScope,
+    pub color_map: [u8; 256],
+    window_id: egui::Id,
+    grid_id: egui::Id,
+    scope_combo_id: egui::Id,
+    selected_image: u32,
+    has_selection: bool,
+    adjustment: ColorAdjustment,
+}
+
+impl ColorAdjustDialog {
+    const CHANNEL_NAMES: [&str; 3] = ["Red", "Green", "Blue"];
+
+    pub fn new(id: DataAssetId) -> Self {
+        ColorAdjustDialog {
+            window_id: egui::Id::new(format!("editor_{}_color_adjust", id)),
+            grid_id: egui::Id::new(format!("editor_{}_color_adjust_grid", id)),
+            scope_combo_id: egui::Id::new(format!("editor_{}_color_adjust_scope_combo", id)),
+            open: false,
+            scope: AdjustScope::CurrentImage,
+            color_map: std::array::from_fn(|i| i as u8),
+            selected_image: 0,
+            has_selection: false,
+            adjustment: ColorAdjustment::new(),
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, selected_image: u32, has_selection: bool) {
+        self.selected_image = selected_image;
+        self.has_selection = has_selection;
+        self.scope = if has_selection { AdjustScope::Selection } else { AdjustScope::CurrentImage };
+        self.adjustment = ColorAdjustment::new();
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn show_steps(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for (i, name) in Self::CHANNEL_NAMES.iter().enumerate() {
+                ui.label(format!("{}:", name));
+                ui.add(egui::DragValue::new(&mut self.adjustment.steps[i]).range(-7..=7));
+                ui.add_space(4.0);
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Darken").on_hover_text("Darken all channels by one step").clicked() {
+                self.adjustment.steps = self.adjustment.steps.map(|s| (s - 1).max(-7));
+            }
+            if ui.button("Lighten").on_hover_text("Lighten all channels by one step").clicked() {
+                self.adjustment.steps = self.adjustment.steps.map(|s| (s + 1).min(7));
+            }
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, image: &impl ImageCollection) -> bool {
+        if ! self.open { return false; }
+
+        let mut confirmed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 450.0, "Adjust Colors", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                egui::Grid::new(self.grid_id)
+                    .num_columns(2)
+                    .spacing([8.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("Apply to:");
+                        egui::ComboBox::from_id_salt(self.scope_combo_id)
+                            .selected_text(self.scope.text())
+                            .width(200.0)
+                            .show_ui(ui, |ui| {
+                                for scope in [AdjustScope::Selection, AdjustScope::CurrentImage, AdjustScope::AllImages] {
+                                    if scope == AdjustScope::Selection && ! self.has_selection { continue; }
+                                    ui.selectable_value(&mut self.scope, scope, scope.text());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Brightness:");
+                        ui.add(egui::Slider::new(&mut self.adjustment.brightness, -1.0..=1.0));
+                        ui.end_row();
+
+                        ui.label("Contrast:");
+                        ui.add(egui::Slider::new(&mut self.adjustment.contrast, -1.0..=1.0));
+                        ui.end_row();
+
+                        ui.label("Hue:");
+                        ui.add(egui::Slider::new(&mut self.adjustment.hue, -180.0..=180.0).step_by(1.0).suffix("°"));
+                        ui.end_row();
+
+                        ui.label("Saturation:");
+                        ui.add(egui::Slider::new(&mut self.adjustment.saturation, -1.0..=1.0));
+                        ui.end_row();
+
+                        ui.label("Steps:");
+                        ui.vertical(|ui| {
+                            self.show_steps(ui);
+                        });
+                        ui.end_row();
+                    });
+
+                ui.add_space(8.0);
+                ui.label("Preview (current image):");
+                let color_map = self.adjustment.color_map(wc.vga_bits_per_pixel);
+                ui.horizontal(|ui| {
+                    draw_color_map_preview(ui, wc, image, self.selected_image, &std::array::from_fn(|i| i as u8));
+                    ui.label(egui::RichText::new("\u{2192}").monospace());
+                    draw_color_map_preview(ui, wc, image, self.selected_image, &color_map);
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    self.color_map = self.adjustment.color_map(wc.vga_bits_per_pixel);
+                    confirmed = true;
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+        confirmed
+    }
+}
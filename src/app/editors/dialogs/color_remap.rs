@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+
+use crate::data_asset::DataAssetId;
+use crate::image::{
+    colors,
+    ImageCollection,
+};
+use crate::image::colors::{
+    color_to_rgb,
+    color_to_rgb_contrast,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    EditorAction,
+    ImageAssetName,
+    ColorPickerPopupWidget,
+};
+
+const CLOSE_PICKER_ON_CLICK: bool = true;
+const COLOR_BUTTON_SIZE: egui::Vec2 = egui::Vec2::new(32.0, 18.0);
+const PREVIEW_SIZE: f32 = 128.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RemapScope {
+    CurrentImage,
+    AllImages,
+    SelectedAssets,
+}
+
+impl RemapScope {
+    pub fn text(&self) -> &'static str {
+        match self {
+            RemapScope::CurrentImage => "current image",
+            RemapScope::AllImages => "all images of this asset",
+            RemapScope::SelectedAssets => "all images of selected assets",
+        }
+    }
+}
+
+/**
+ * Draw an image item with its colors replaced according to the color map.
+ */
+pub fn draw_color_map_preview(ui: &mut egui::Ui, wc: &WindowContext, image: &impl ImageCollection, item: u32, color_map: &[u8; 256]) {
+    let image_size = image.get_item_size();
+    let zoom = (PREVIEW_SIZE / image_size.x.max(image_size.y)).max(1.0);
+    let (response, painter) = ui.allocate_painter(image_size * zoom, egui::Sense::hover());
+    painter.rect_filled(response.rect, egui::CornerRadius::ZERO, wc.settings.image_bg_color);
+    if item >= image.num_items() { return; }
+    let width = image.width() as usize;
+    for (i, &pixel) in image.item_data(item).iter().enumerate() {
+        let color = color_map[pixel as usize];
+        if color == colors::TRANSPARENT { continue; }
+        let pos = egui::Vec2::new((i % width) as f32, (i / width) as f32) * zoom;
+        let rect = egui::Rect::from_min_size(response.rect.min + pos, egui::Vec2::splat(zoom));
+        painter.rect_filled(rect, egui::CornerRadius::ZERO, color_to_rgb(color));
+    }
+}
+
+pub struct ColorRemapDialog {
+    pub open: bool,
+    asset_id: DataAssetId,
+    window_id: egui::Id,
+    grid_id: egui::Id,
+    scope_combo_id: egui::Id,
+    selected_image: u32,
+    scope: RemapScope,
+    mapping: Vec<(u8, u8)>,
+    selected_assets: HashSet<DataAssetId>,
+    edit_color: Option<(usize, bool)>,
+    popup: ColorPickerPopupWidget,
+}
+
+impl ColorRemapDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        ColorRemapDialog {
+            window_id: egui::Id::new(format!("editor_{}_color_remap", id)),
+            grid_id: egui::Id::new(format!("editor_{}_color_remap_grid", id)),
+            scope_combo_id: egui::Id::new(format!("editor_{}_color_remap_scope_combo", id)),
+            popup: ColorPickerPopupWidget::new(egui::Id::new(format!("editor_{}_color_remap_popup", id)), CLOSE_PICKER_ON_CLICK),
+            asset_id: id,
+            open: false,
+            selected_image: 0,
+            scope: RemapScope::CurrentImage,
+            mapping: Vec::new(),
+            selected_assets: HashSet::new(),
+            edit_color: None,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, selected_image: u32, from_color: u8, to_color: u8) {
+        self.selected_image = selected_image;
+        self.scope = RemapScope::CurrentImage;
+        self.mapping.clear();
+        self.mapping.push((from_color, to_color));
+        self.selected_assets.clear();
+        self.selected_assets.insert(self.asset_id);
+        self.edit_color = None;
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn color_map(&self) -> [u8; 256] {
+        let mut color_map = std::array::from_fn(|i| i as u8);
+        for &(from, to) in &self.mapping {
+            color_map[from as usize] = to;
+        }
+        color_map
+    }
+
+    fn confirm(&mut self, wc: &mut WindowContext) -> bool {
+        let (asset_ids, frame) = match self.scope {
+            RemapScope::CurrentImage => (vec![self.asset_id], Some(self.selected_image)),
+            RemapScope::AllImages => (vec![self.asset_id], None),
+            RemapScope::SelectedAssets => (self.selected_assets.iter().copied().collect(), None),
+        };
+        if asset_ids.is_empty() {
+            wc.open_message_box("No Assets Selected", "Select at least one asset to remap its colors.");
+            return false;
+        }
+        wc.add_editor_action(EditorAction::RemapColors { asset_ids, frame, color_map: Box::new(self.color_map()) });
+        true
+    }
+
+    fn color_button(ui: &mut egui::Ui, color: u8) -> egui::Response {
+        let (response, painter) = ui.allocate_painter(COLOR_BUTTON_SIZE, egui::Sense::click());
+        painter.rect_filled(response.rect, egui::CornerRadius::ZERO, color_to_rgb(color));
+        let stroke = egui::Stroke::new(1.0, color_to_rgb_contrast(color));
+        painter.rect_stroke(response.rect, egui::CornerRadius::ZERO, stroke, egui::StrokeKind::Inside);
+        response
+    }
+
+    fn show_mapping(&mut self, ui: &mut egui::Ui, wc: &WindowContext) {
+        let mut remove_row = None;
+        egui::Grid::new(self.grid_id)
+            .num_columns(4)
+            .spacing([8.0, 4.0])
+            .show(ui, |ui| {
+                for (row, (from, to)) in self.mapping.iter_mut().enumerate() {
+                    for (color, is_to) in [(from, false), (to, true)] {
+                        let response = Self::color_button(ui, *color);
+                        if response.clicked() {
+                            self.edit_color = Some((row, is_to));
+                        }
+                        if self.edit_color == Some((row, is_to)) {
+                            self.popup.show(&response, wc.settings, color);
+                            if self.popup.close {
+                                self.edit_color = None;
+                            }
+                        }
+                        if ! is_to {
+                            ui.label(egui::RichText::new("\u{2192}").monospace());
+                        }
+                    }
+                    if ui.button("\u{2796}").on_hover_text("Remove").clicked() {
+                        remove_row = Some(row);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(row) = remove_row {
+            self.mapping.remove(row);
+            self.edit_color = None;
+        }
+        if ui.button("\u{2795} Add").clicked() {
+            let last = self.mapping.last().copied().unwrap_or((0, 0));
+            self.mapping.push(last);
+        }
+    }
+
+    fn show_asset_list(&mut self, ui: &mut egui::Ui, image_assets: &[ImageAssetName]) {
+        egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+            for asset in image_assets {
+                let mut selected = self.selected_assets.contains(&asset.id);
+                if ui.checkbox(&mut selected, format!("{} ({})", asset.name, asset.asset_type.name())).changed() {
+                    if selected {
+                        self.selected_assets.insert(asset.id);
+                    } else {
+                        self.selected_assets.remove(&asset.id);
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, image: &impl ImageCollection, image_assets: &[ImageAssetName]) {
+        if ! self.open { return; }
+
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 450.0, "Remap Colors", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Apply to:");
+                    egui::ComboBox::from_id_salt(self.scope_combo_id)
+                        .selected_text(self.scope.text())
+                        .width(200.0)
+                        .show_ui(ui, |ui| {
+                            for scope in [RemapScope::CurrentImage, RemapScope::AllImages, RemapScope::SelectedAssets] {
+                                ui.selectable_value(&mut self.scope, scope, scope.text());
+                            }
+                        });
+                });
+                if self.scope == RemapScope::SelectedAssets {
+                    ui.add_space(4.0);
+                    self.show_asset_list(ui, image_assets);
+                }
+
+                ui.add_space(8.0);
+                ui.label("Colors:");
+                self.show_mapping(ui, wc);
+
+                ui.add_space(8.0);
+                ui.label("Preview (current image):");
+                let color_map = self.color_map();
+                ui.horizontal(|ui| {
+                    draw_color_map_preview(ui, wc, image, self.selected_image, &std::array::from_fn(|i| i as u8));
+                    ui.label(egui::RichText::new("\u{2192}").monospace());
+                    draw_color_map_preview(ui, wc, image, self.selected_image, &color_map);
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() && self.confirm(wc) {
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::data_asset::DataAssetId;
+use crate::image::ImageTransform;
+use crate::image::colors::{
+    color_to_rgb,
+    color_to_rgb_contrast,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    ColorPickerPopupWidget,
+};
+
+const CLOSE_PICKER_ON_CLICK: bool = true;
+const COLOR_BUTTON_SIZE: egui::Vec2 = egui::Vec2::new(32.0, 18.0);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TransformKind {
+    Scale,
+    Rotate,
+    Skew,
+    Outline,
+    DropShadow,
+}
+
+impl TransformKind {
+    const ALL: &[TransformKind] = &[
+        TransformKind::Scale,
+        TransformKind::Rotate,
+        TransformKind::Skew,
+        TransformKind::Outline,
+        TransformKind::DropShadow,
+    ];
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            TransformKind::Scale => "Scale",
+            TransformKind::Rotate => "Rotate",
+            TransformKind::Skew => "Skew",
+            TransformKind::Outline => "Outline",
+            TransformKind::DropShadow => "Drop shadow",
+        }
+    }
+}
+
+pub struct ImageTransformDialog {
+    pub open: bool,
+    pub transform: ImageTransform,
+    window_id: egui::Id,
+    grid_id: egui::Id,
+    kind_combo_id: egui::Id,
+    kind: TransformKind,
+    scale_x: f32,
+    scale_y: f32,
+    keep_aspect: bool,
+    angle: f32,
+    skew_x: f32,
+    skew_y: f32,
+    color: u8,
+    popup: ColorPickerPopupWidget,
+    popup_open: bool,
+}
+
+impl ImageTransformDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        ImageTransformDialog {
+            window_id: egui::Id::new(format!("editor_{}_image_transform", id)),
+            grid_id: egui::Id::new(format!("editor_{}_image_transform_grid", id)),
+            kind_combo_id: egui::Id::new(format!("editor_{}_image_transform_kind_combo", id)),
+            popup: ColorPickerPopupWidget::new(egui::Id::new(format!("editor_{}_image_transform_popup", id)), CLOSE_PICKER_ON_CLICK),
+            open: false,
+            transform: ImageTransform::Scale(1.0, 1.0),
+            kind: TransformKind::Scale,
+            scale_x: 2.0,
+            scale_y: 2.0,
+            keep_aspect: true,
+            angle: 45.0,
+            skew_x: 0.0,
+            skew_y: 0.0,
+            color: 0,
+            popup_open: false,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, color: u8) {
+        self.color = color;
+        self.popup_open = false;
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn confirm(&mut self) -> bool {
+        self.transform = match self.kind {
+            TransformKind::Scale => ImageTransform::Scale(self.scale_x, self.scale_y),
+            TransformKind::Rotate => ImageTransform::Rotate(self.angle),
+            TransformKind::Skew => ImageTransform::Skew(self.skew_x, self.skew_y),
+            TransformKind::Outline => ImageTransform::Outline(self.color),
+            TransformKind::DropShadow => ImageTransform::DropShadow(self.color),
+        };
+        true
+    }
+
+    fn show_color(&mut self, ui: &mut egui::Ui, wc: &WindowContext) {
+        let (response, painter) = ui.allocate_painter(COLOR_BUTTON_SIZE, egui::Sense::click());
+        painter.rect_filled(response.rect, egui::CornerRadius::ZERO, color_to_rgb(self.color));
+        let stroke = egui::Stroke::new(1.0, color_to_rgb_contrast(self.color));
+        painter.rect_stroke(response.rect, egui::CornerRadius::ZERO, stroke, egui::StrokeKind::Inside);
+        if response.clicked() {
+            self.popup_open = true;
+        }
+        if self.popup_open {
+            self.popup.show(&response, wc.settings, &mut self.color);
+            if self.popup.close {
+                self.popup_open = false;
+            }
+        }
+    }
+
+    fn show_options(&mut self, ui: &mut egui::Ui, wc: &WindowContext) {
+        match self.kind {
+            TransformKind::Scale => {
+                ui.label("Horizontal:");
+                if ui.add(egui::DragValue::new(&mut self.scale_x).speed(0.05).range(0.1..=16.0).prefix("\u{00d7}")).changed() &&
+                    self.keep_aspect {
+                    self.scale_y = self.scale_x;
+                }
+                ui.end_row();
+
+                ui.label("Vertical:");
+                if ui.add(egui::DragValue::new(&mut self.scale_y).speed(0.05).range(0.1..=16.0).prefix("\u{00d7}")).changed() &&
+                    self.keep_aspect {
+                    self.scale_x = self.scale_y;
+                }
+                ui.end_row();
+
+                ui.label("");
+                if ui.checkbox(&mut self.keep_aspect, "Keep aspect ratio").changed() && self.keep_aspect {
+                    self.scale_y = self.scale_x;
+                }
+                ui.end_row();
+            }
+
+            TransformKind::Rotate => {
+                ui.label("Angle:");
+                ui.add(egui::Slider::new(&mut self.angle, -180.0..=180.0).step_by(1.0).suffix("°"));
+                ui.end_row();
+            }
+
+            TransformKind::Skew => {
+                ui.label("Horizontal:");
+                ui.add(egui::Slider::new(&mut self.skew_x, -60.0..=60.0).step_by(1.0).suffix("°"));
+                ui.end_row();
+
+                ui.label("Vertical:");
+                ui.add(egui::Slider::new(&mut self.skew_y, -60.0..=60.0).step_by(1.0).suffix("°"));
+                ui.end_row();
+            }
+
+            TransformKind::Outline | TransformKind::DropShadow => {
+                ui.label("Color:");
+                self.show_color(ui, wc);
+                ui.end_row();
+            }
+        }
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext) -> bool {
+        if ! self.open { return false; }
+
+        let mut confirmed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 350.0, "Transform Image", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                egui::Grid::new(self.grid_id)
+                    .num_columns(2)
+                    .spacing([8.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("Transform:");
+                        egui::ComboBox::from_id_salt(self.kind_combo_id)
+                            .selected_text(self.kind.text())
+                            .width(150.0)
+                            .show_ui(ui, |ui| {
+                                for &kind in TransformKind::ALL {
+                                    ui.selectable_value(&mut self.kind, kind, kind.text());
+                                }
+                            });
+                        ui.end_row();
+
+                        self.show_options(ui, wc);
+                    });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() && self.confirm() {
+                    confirmed = true;
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+        confirmed
+    }
+}
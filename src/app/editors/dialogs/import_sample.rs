@@ -0,0 +1,201 @@
+use crate::sound::SoundPlayer;
+use crate::data_asset::DataAssetId;
+use crate::misc::wav_utils::{
+    WavFile,
+    WavMixdown,
+    WavImportOptions,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+};
+
+pub struct ImportSampleDialog {
+    pub open: bool,
+    pub samples: Vec<i16>,
+    pub bits_per_sample: u16,
+    pub sample_rate: u32,
+    pub loop_start: u32,
+    pub loop_len: u32,
+    window_id: egui::Id,
+    grid_id: egui::Id,
+    mixdown_combo_id: egui::Id,
+    filename: String,
+    wav_file: Option<WavFile>,
+    mixdown: WavMixdown,
+    resample: bool,
+    resample_rate: u32,
+    quantize_8bit: bool,
+    dither: bool,
+}
+
+impl ImportSampleDialog {
+    pub const MIN_SAMPLE_RATE: u32 = 8000;
+    pub const MAX_SAMPLE_RATE: u32 = 44100;
+
+    pub fn new(id: DataAssetId) -> Self {
+        ImportSampleDialog {
+            window_id: egui::Id::new(format!("editor_{}_import_sample_options", id)),
+            grid_id: egui::Id::new(format!("editor_{}_import_sample_grid", id)),
+            mixdown_combo_id: egui::Id::new(format!("editor_{}_import_sample_mixdown_combo", id)),
+            open: false,
+            samples: Vec::new(),
+            bits_per_sample: 16,
+            sample_rate: 0,
+            loop_start: 0,
+            loop_len: 0,
+            filename: String::new(),
+            wav_file: None,
+            mixdown: WavMixdown::Mid,
+            resample: true,
+            resample_rate: 11025,
+            quantize_8bit: false,
+            dither: true,
+        }
+    }
+
+    /**
+     * Playback frequency for the imported samples (the file's sample rate
+     * is kept as is when not resampling).
+     */
+    pub fn play_freq(&self) -> f32 {
+        if self.resample {
+            self.sample_rate.clamp(Self::MIN_SAMPLE_RATE, Self::MAX_SAMPLE_RATE) as f32
+        } else {
+            self.sample_rate as f32
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, wav_file: WavFile, filename: &str, playback_rate: u32) {
+        self.filename.clear();
+        self.filename.push_str(filename);
+        let playback_rate = playback_rate.clamp(Self::MIN_SAMPLE_RATE, Self::MAX_SAMPLE_RATE);
+        self.resample = wav_file.sample_rate != playback_rate;
+        self.resample_rate = playback_rate;
+        self.quantize_8bit = false;
+        self.wav_file = Some(wav_file);
+        self.samples.clear();
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn options(&self) -> WavImportOptions {
+        WavImportOptions {
+            mixdown: self.mixdown,
+            resample_rate: if self.resample { Some(self.resample_rate) } else { None },
+            quantize_8bit: self.quantize_8bit,
+            dither: self.dither,
+        }
+    }
+
+    fn convert(&mut self) {
+        if let Some(wav_file) = &self.wav_file {
+            (self.samples, self.bits_per_sample) = wav_file.convert(&self.options());
+            self.sample_rate = if self.resample { self.resample_rate } else { wav_file.sample_rate };
+
+            // scale loop to the converted length
+            let source_len = wav_file.channels.first().map_or(0, |ch| ch.len()) as u64;
+            let len = self.samples.len() as u64;
+            if wav_file.loop_len > 0 && source_len > 0 {
+                let loop_end = (wav_file.loop_start + wav_file.loop_len) as u64 * len / source_len;
+                self.loop_start = (wav_file.loop_start as u64 * len / source_len) as u32;
+                self.loop_len = (loop_end as u32).saturating_sub(self.loop_start);
+            } else {
+                self.loop_start = 0;
+                self.loop_len = 0;
+            }
+        }
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, sound_player: &mut SoundPlayer, play_volume: f32) -> bool {
+        if ! self.open { return false; }
+
+        let mut confirmed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 400.0, "Import Sample", |ui, _wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                let Some(wav_file) = &self.wav_file else { return; };
+                let num_channels = wav_file.channels.len();
+                let source_len = wav_file.channels.first().map_or(0, |ch| ch.len());
+                let source_format = format!("{} channel(s), {} Hz, {} bits, {} samples",
+                                            num_channels, wav_file.sample_rate, wav_file.bits_per_sample, source_len);
+                let source_loop = if wav_file.loop_len > 0 {
+                    Some(format!("{} to {}", wav_file.loop_start, wav_file.loop_start + wav_file.loop_len))
+                } else {
+                    None
+                };
+
+                egui::Grid::new(self.grid_id)
+                    .num_columns(2)
+                    .spacing([8.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("File:");
+                        ui.add(egui::Label::new(&self.filename).truncate());
+                        ui.end_row();
+
+                        ui.label("Format:");
+                        ui.label(source_format);
+                        ui.end_row();
+
+                        if let Some(source_loop) = source_loop {
+                            ui.label("Loop:");
+                            ui.label(source_loop);
+                            ui.end_row();
+                        }
+
+                        ui.label("Channels:");
+                        ui.add_enabled_ui(num_channels > 1, |ui| {
+                            egui::ComboBox::from_id_salt(self.mixdown_combo_id)
+                                .selected_text(self.mixdown.text())
+                                .width(100.0)
+                                .show_ui(ui, |ui| {
+                                    for mixdown in WavMixdown::ALL {
+                                        ui.selectable_value(&mut self.mixdown, *mixdown, mixdown.text());
+                                    }
+                                });
+                        });
+                        ui.end_row();
+
+                        ui.label("Resample:");
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.resample, "to");
+                            ui.add_enabled(self.resample, egui::DragValue::new(&mut self.resample_rate).speed(25.0).range(Self::MIN_SAMPLE_RATE..=Self::MAX_SAMPLE_RATE));
+                            ui.label("Hz");
+                        });
+                        ui.end_row();
+
+                        ui.label("Quantize:");
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.quantize_8bit, "8 bits");
+                            ui.add_enabled(self.quantize_8bit, egui::Checkbox::new(&mut self.dither, "dither"));
+                        });
+                        ui.end_row();
+                    });
+
+                if sound_player.is_available() {
+                    ui.add_space(8.0);
+                    if ui.button("▶ Preview ").clicked() {
+                        self.convert();
+                        sound_player.play_s16(&self.samples, self.sample_rate as f32, play_volume);
+                    }
+                }
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    self.convert();
+                    confirmed = true;
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            self.wav_file = None;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+        confirmed
+    }
+}
@@ -1,5 +1,13 @@
 mod confirmation;
+mod color_remap;
+mod color_adjust;
 mod create_colorset;
+mod import_sample;
+mod image_transform;
 
 pub use confirmation::{*};
+pub use color_remap::{*};
+pub use color_adjust::{*};
 pub use create_colorset::{*};
+pub use import_sample::{*};
+pub use image_transform::{*};
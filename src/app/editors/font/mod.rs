@@ -19,9 +19,13 @@ use super::{
     AssetEditorBase,
     WindowContext,
     SysDialogResponse,
+    ImageAssetName,
 };
+use super::dialogs::ColorRemapDialog;
 use super::widgets::{
     ImageEditorWidget,
+    ImageDrawingTool,
+    ImageBrushMenuAction,
     FontViewWidget,
     FontPainter,
 };
@@ -69,7 +73,7 @@ impl FontEditor {
         FontEditor {
             base: AssetEditorBase::new(id, open),
             editor: Editor::new(id),
-            dialogs: Dialogs::new(),
+            dialogs: Dialogs::new(id),
         }
     }
 
@@ -92,8 +96,20 @@ impl FontEditor {
         });
     }
 
-    pub fn show(&mut self, wc: &mut WindowContext, font: &mut Font) {
-        self.dialogs.show(wc, &mut self.editor, font);
+    pub fn remap_colors(&mut self, wc: &mut WindowContext, font: &mut Font, frame: Option<u32>, color_map: &[u8; 256]) {
+        // fonts only have foreground and background colors
+        let color_map = std::array::from_fn(|i| if color_map[i] == Font::FG_COLOR { Font::FG_COLOR } else { Font::BG_COLOR });
+        let frames = match frame {
+            Some(frame) => frame..frame+1,
+            None => 0..Font::NUM_CHARS,
+        };
+        if self.editor.image_editor.remap_colors(font, frames, &color_map) {
+            ImageEditorWidget::<Font>::update_texture(wc, font);
+        }
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, font: &mut Font, image_assets: &[ImageAssetName]) {
+        self.dialogs.show(wc, &mut self.editor, font, image_assets);
 
         self.base.show_window(wc, font, [400.0, 350.0], [400.0, 400.0], |ui, wc, font, base| {
             Self::show_footer(ui, wc, font, base);
@@ -105,17 +121,19 @@ impl FontEditor {
 struct Dialogs {
     properties_dialog: PropertiesDialog,
     import_dialog: ImportDialog,
+    color_remap_dialog: ColorRemapDialog,
 }
 
 impl Dialogs {
-    pub fn new() -> Self {
+    pub fn new(id: DataAssetId) -> Self {
         Dialogs {
             properties_dialog: PropertiesDialog::new(),
             import_dialog: ImportDialog::new(),
+            color_remap_dialog: ColorRemapDialog::new(id),
         }
     }
 
-    fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, font: &mut Font) {
+    fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, font: &mut Font, image_assets: &[ImageAssetName]) {
         if self.properties_dialog.open && self.properties_dialog.show(wc, font) {
             editor.image_editor.set_undo_target(font);
             editor.image_editor.set_image_changed();
@@ -124,6 +142,9 @@ impl Dialogs {
             editor.image_editor.set_undo_target(font);
             editor.image_editor.set_image_changed();
         }
+        if self.color_remap_dialog.open {
+            self.color_remap_dialog.show(wc, font, image_assets);
+        }
     }
 }
 
@@ -192,6 +213,9 @@ impl Editor {
                         }
                         self.image_editor.set_image_changed();
                     }
+                    if ui.add(menu_item_no_image(" Remap colors...")).clicked() {
+                        dialogs.color_remap_dialog.set_open(wc, self.image_editor.get_selected_image(), Font::FG_COLOR, Font::BG_COLOR);
+                    }
 
                     ui.separator();
 
@@ -212,7 +236,8 @@ impl Editor {
         });
     }
 
-    fn show_toolbar(&mut self, ui: &mut egui::Ui, _wc: &mut WindowContext, font: &mut Font) {
+    fn show_toolbar(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, font: &mut Font) {
+        self.image_editor.reference.update(wc, format!("font/{}", font.asset.name));
         egui::Panel::top(format!("editor_panel_{}_toolbar", self.asset_id)).show(ui, |ui| {
             ui.add_space(2.0);
             ui.horizontal(|ui| {
@@ -258,6 +283,39 @@ impl Editor {
                     self.shift_image(font, 1, 0);
                 }
 
+                ui.add_space(4.0);
+                ui.separator();
+                ui.add_space(4.0);
+
+                ui.label("Tool:");
+                if ui.add(egui::Button::image(IMAGES.pen)
+                          .selected(self.image_editor.get_tool() == ImageDrawingTool::Pencil)
+                          .frame_when_inactive(self.image_editor.get_tool() == ImageDrawingTool::Pencil)).on_hover_text("Pencil").clicked() {
+                    self.image_editor.set_tool(ImageDrawingTool::Pencil);
+                }
+                if ui.add(egui::Button::image(IMAGES.fill)
+                          .selected(self.image_editor.get_tool() == ImageDrawingTool::Fill)
+                          .frame_when_inactive(self.image_editor.get_tool() == ImageDrawingTool::Fill)).on_hover_text("Fill").clicked() {
+                    self.image_editor.set_tool(ImageDrawingTool::Fill);
+                }
+                for &tool in ImageDrawingTool::SHAPES {
+                    if ui.add(egui::Button::new(tool.shape_icon())
+                              .selected(self.image_editor.get_tool() == tool)
+                              .frame_when_inactive(self.image_editor.get_tool() == tool)).on_hover_text(tool.shape_name()).clicked() {
+                        self.image_editor.set_tool(tool);
+                    }
+                }
+
+                ui.add_space(5.0);
+                let can_capture = ! self.image_editor.selection.is_empty();
+                if let ImageBrushMenuAction::Capture = self.image_editor.brush.show_menu(ui, can_capture) {
+                    self.image_editor.capture_brush(font);
+                }
+                ui.add_space(5.0);
+                self.image_editor.symmetry.show_menu(ui);
+                ui.add_space(5.0);
+                self.image_editor.reference.show_menu(ui, wc);
+
                 ui.with_layout(egui::Layout::default().with_cross_align(egui::Align::RIGHT), |ui| {
                     ui.horizontal(|ui| {
                         let spacing = ui.spacing().item_spacing;
@@ -4,6 +4,7 @@ use crate::data_asset::Font;
 use super::super::{
     AssetEditorBase,
     WindowContext,
+    EditorAction,
 };
 
 pub struct PropertiesDialog {
@@ -38,7 +39,10 @@ impl PropertiesDialog {
         wc.set_dialog_open(Self::id(), self.open);
     }
 
-    fn confirm(&mut self, font: &mut Font) {
+    fn confirm(&mut self, wc: &mut WindowContext, font: &mut Font) {
+        if font.asset.name != self.name {
+            wc.add_editor_action(EditorAction::ImageAssetRenamed);
+        }
         font.asset.name.clear();
         font.asset.name.push_str(&self.name);
 
@@ -49,7 +53,7 @@ impl PropertiesDialog {
     }
 
     pub fn show(&mut self, wc: &mut WindowContext, font: &mut Font) -> bool {
-        if AssetEditorBase::show_dialog_window(wc, Self::id(), 300.0, "Font Properties", |ui, _wc| {
+        if AssetEditorBase::show_dialog_window(wc, Self::id(), 300.0, "Font Properties", |ui, wc| {
             egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
                 egui::Grid::new(format!("editor_panel_{}_prop_grid", font.asset.id))
                     .num_columns(2)
@@ -74,7 +78,7 @@ impl PropertiesDialog {
                     ui.close();
                 }
                 if ui.button("Ok").clicked() {
-                    self.confirm(font);
+                    self.confirm(wc, font);
                     ui.close();
                 }
             });
@@ -1,4 +1,8 @@
 mod properties;
+mod stamp;
+mod resize;
+mod shift;
+mod parallax_preview;
 
 use crate::image::{
     ImageCollection,
@@ -6,6 +10,7 @@ use crate::image::{
 };
 use crate::data_asset::{
     MapData,
+    MapEffectType,
     Tileset,
     AssetIdList,
     AssetList,
@@ -22,16 +27,28 @@ use super::{
     WindowContext,
     MapLayer,
     MapTileFixer,
+    EditorAction,
+    MapRoomPlacement,
+    resize_map_anchored,
+    shift_map,
 };
 use super::widgets::{
     MapEditorWidget,
     MapDisplay,
     MapTool,
     ImagePickerWidget,
+    map_fx_type_color,
+};
+use super::super::{
+    menu_item,
+    menu_item_no_image,
 };
-use super::super::menu_item;
 
 use properties::PropertiesDialog;
+use stamp::StampDialog;
+use resize::ResizeDialog;
+use shift::ShiftDialog;
+use parallax_preview::ParallaxPreviewDialog;
 
 const ZOOM_OPTIONS: &[f32] = &[ 0.5, 0.75, 1.0, 1.5, 2.0, 2.5, 3.0, 4.0 ];
 
@@ -46,7 +63,7 @@ impl MapDataEditor {
         MapDataEditor {
             base: AssetEditorBase::new(id, open),
             editor: Editor::new(id),
-            dialogs: Dialogs::new(),
+            dialogs: Dialogs::new(id),
         }
     }
 
@@ -54,7 +71,8 @@ impl MapDataEditor {
         self.editor.map_editor.drop_selection(map_data);
     }
 
-    fn show_footer(ui: &mut egui::Ui, wc: &WindowContext, editor: &Editor, map_data: &MapData, base: &AssetEditorBase) {
+    fn show_footer(ui: &mut egui::Ui, wc: &WindowContext, editor: &Editor, map_data: &MapData, base: &AssetEditorBase,
+                   fx_types: &[MapEffectType]) {
         let margin = egui::Margin { left: 5, right: 5, top: 4, bottom: 0 };
         let bottom_frame = egui::Frame::NONE.inner_margin(margin).fill(base.footer_bg_color(wc, map_data.asset.id));
         let dirty = if base.is_dirty() { " (modified)" } else { "" };
@@ -82,6 +100,14 @@ impl MapDataEditor {
 
                         ui.label(format!("({}, {})", editor.map_editor.hover_pos.x.floor(), editor.map_editor.hover_pos.y.floor()));
 
+                        if editor.map_editor.edit_layer == MapLayer::Effects && ! fx_types.is_empty() &&
+                            let Some(value) = editor.get_hover_fx_value(map_data) {
+                                match fx_types.get(value as usize) {
+                                    Some(fx_type) => ui.label(&fx_type.name),
+                                    None => ui.label(format!("unknown effect {}", value)),
+                                };
+                            }
+
                         ui.spacing_mut().item_spacing = spacing;
                     });
                 });
@@ -89,7 +115,16 @@ impl MapDataEditor {
         });
     }
 
-    pub fn show(&mut self, wc: &mut WindowContext, map_data: &mut MapData, tileset_ids: &AssetIdList, tilesets: &AssetList<Tileset>) {
+    pub fn set_undo_room_placements(&mut self, placements: Vec<MapRoomPlacement>) {
+        self.editor.map_editor.set_undo_room_placements(placements);
+    }
+
+    pub fn set_redo_room_placements(&mut self, placements: Vec<MapRoomPlacement>) {
+        self.editor.map_editor.set_redo_room_placements(placements);
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, map_data: &mut MapData, tileset_ids: &AssetIdList, tilesets: &AssetList<Tileset>,
+                fx_types: &[MapEffectType]) {
         self.dialogs.show(wc, &mut self.editor, map_data, tileset_ids, tilesets);
 
         let min_size = egui::Vec2::new(600.0, 200.0);
@@ -97,8 +132,8 @@ impl MapDataEditor {
         let def_size = def_size.min(wc.window_space.size() - egui::Vec2::splat(100.0)).max(min_size);
 
         self.base.show_window(wc, map_data, min_size, def_size, |ui, wc, map_data, base| {
-            Self::show_footer(ui, wc, &self.editor, map_data, base);
-            self.editor.show(ui, wc, &mut self.dialogs, map_data, tilesets);
+            Self::show_footer(ui, wc, &self.editor, map_data, base, fx_types);
+            self.editor.show(ui, wc, &mut self.dialogs, map_data, tilesets, fx_types);
         });
     }
 }
@@ -111,12 +146,20 @@ impl MapTileFixer for MapDataEditor {
 
 struct Dialogs {
     properties_dialog: Option<PropertiesDialog>,
+    stamp_dialog: StampDialog,
+    resize_dialog: ResizeDialog,
+    shift_dialog: ShiftDialog,
+    parallax_preview_dialog: ParallaxPreviewDialog,
 }
 
 impl Dialogs {
-    fn new() -> Self {
+    fn new(id: DataAssetId) -> Self {
         Dialogs {
             properties_dialog: None,
+            stamp_dialog: StampDialog::new(id),
+            resize_dialog: ResizeDialog::new(id),
+            shift_dialog: ShiftDialog::new(id),
+            parallax_preview_dialog: ParallaxPreviewDialog::new(id),
         }
     }
 
@@ -128,6 +171,30 @@ impl Dialogs {
                 editor.map_editor.set_undo_target(map_data);
             }
         }
+        if let Some(tileset) = tilesets.get(&map_data.tileset_id) && self.stamp_dialog.show(wc, tileset) {
+            editor.map_editor.stamp = self.stamp_dialog.stamp.clone();
+        }
+        if self.resize_dialog.show(wc) {
+            let dlg = &self.resize_dialog;
+            let (para_width, para_height) = dlg.parallax_size();
+            editor.map_editor.drop_selection(map_data);
+            editor.map_editor.set_undo_target(map_data);
+            let offset = resize_map_anchored(map_data, dlg.width, dlg.height, para_width, para_height, dlg.anchor, dlg.new_tile);
+            if offset != (0, 0) {
+                wc.add_editor_action(EditorAction::OffsetMapInRooms { map_id: map_data.asset.id, offset });
+            }
+        }
+        if self.shift_dialog.show(wc) {
+            let dlg = &self.shift_dialog;
+            editor.map_editor.drop_selection(map_data);
+            editor.map_editor.set_undo_target(map_data);
+            shift_map(map_data, dlg.layer, (dlg.dx, dlg.dy), dlg.wrap, dlg.new_tile);
+        }
+        if let Some(tileset) = tilesets.get(&map_data.tileset_id) && self.parallax_preview_dialog.show(wc, map_data, tileset) {
+            // leave the screen rectangle where the preview stopped
+            editor.map_editor.screen_display_pos = self.parallax_preview_dialog.camera_pos.floor().to_pos2();
+            editor.map_editor.display.set(MapDisplay::SCREEN);
+        }
     }
 }
 
@@ -146,7 +213,7 @@ impl Editor {
         Editor {
             asset_id,
             map_editor: MapEditorWidget::new(),
-            image_picker: ImagePickerWidget::new().use_as_palette(true),
+            image_picker: ImagePickerWidget::new().use_as_palette(true).use_range_selection(true),
             use_custom_grid_color: false,
             use_custom_bg_color: false,
             custom_grid_color: egui::Color32::RED,
@@ -166,6 +233,14 @@ impl Editor {
         }
     }
 
+    fn get_hover_fx_value(&self, map_data: &MapData) -> Option<u8> {
+        let x = self.map_editor.hover_pos.x.floor() as u32;
+        let y = self.map_editor.hover_pos.y.floor() as u32;
+        if x >= map_data.width || y >= map_data.height { return None; }
+        let value = map_data.fx_tiles[(y * map_data.width + x) as usize];
+        if value == MapData::NO_TILE { None } else { Some(value) }
+    }
+
     fn image_selection_to_tile(image_selection: Option<u32>) -> u8 {
         if let Some(image_selection) = image_selection {
             (image_selection & 0xff) as u8
@@ -184,13 +259,27 @@ impl Editor {
                         });
                         dlg.set_open(wc, map_data, Self::image_selection_to_tile(self.image_picker.get_selected_image_right()));
                     }
+                    if ui.add(menu_item_no_image(" Resize...")).clicked() {
+                        let new_tile = Self::image_selection_to_tile(self.image_picker.get_selected_image_right());
+                        dialogs.resize_dialog.set_open(wc, map_data, new_tile);
+                    }
+                    if ui.add(menu_item_no_image(" Shift...")).clicked() {
+                        let new_tile = Self::image_selection_to_tile(self.image_picker.get_selected_image_right());
+                        dialogs.shift_dialog.set_open(wc, new_tile);
+                    }
+                    ui.separator();
+                    let has_parallax = map_data.para_width != 0 && map_data.para_height != 0;
+                    if ui.add_enabled(has_parallax, menu_item(IMAGES.layer_parallax, " Parallax preview...")).clicked() {
+                        let camera_pos = self.map_editor.screen_display_pos.to_vec2();
+                        dialogs.parallax_preview_dialog.set_open(wc, map_data, camera_pos);
+                    }
                 });
                 ui.menu_button("Edit", |ui| {
                     if ui.add_enabled(self.map_editor.can_undo(), menu_item(IMAGES.undo, " Undo")).clicked() {
-                        self.map_editor.undo(map_data);
+                        self.map_editor.undo(wc, map_data);
                     }
                     if ui.add_enabled(self.map_editor.can_redo(), menu_item(IMAGES.redo, " Redo")).clicked() {
-                        self.map_editor.redo(map_data);
+                        self.map_editor.redo(wc, map_data);
                     }
 
                     ui.separator();
@@ -224,7 +313,8 @@ impl Editor {
         }
     }
 
-    fn show_display_toolbar(&mut self, ui: &mut egui::Ui, _wc: &mut WindowContext, map_data: &MapData) {
+    fn show_display_toolbar(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, map_data: &MapData) {
+        self.map_editor.reference.update(wc, format!("map/{}", map_data.asset.name));
         egui::Panel::top(format!("editor_panel_{}_display_toolbar", self.asset_id)).show(ui, |ui| {
             ui.add_space(2.0);
             ui.horizontal(|ui| {
@@ -270,6 +360,13 @@ impl Editor {
                         self.map_editor.display.toggle(MapDisplay::SCREEN);
                     }
 
+                if ui.add(egui::Button::new("Anim")
+                          .selected(self.map_editor.display.has_bits(MapDisplay::ANIMATION))
+                          .frame_when_inactive(self.map_editor.display.has_bits(MapDisplay::ANIMATION)))
+                    .on_hover_text("Play tile animations").clicked() {
+                        self.map_editor.display.toggle(MapDisplay::ANIMATION);
+                    }
+
                 ui.add_space(5.0);
                 ui.separator();
                 ui.add_space(5.0);
@@ -315,6 +412,11 @@ impl Editor {
                     self.map_editor.custom_bg_color = None;
                 }
 
+                ui.add_space(5.0);
+                ui.separator();
+                ui.add_space(5.0);
+                self.map_editor.reference.show_menu(ui, wc);
+
                 ui.with_layout(egui::Layout::default().with_cross_align(egui::Align::RIGHT), |ui| {
                     ui.horizontal(|ui| {
                         let cur_zoom_name = if let Some(zoom) = ZOOM_OPTIONS.iter().find(|&z| *z == self.map_editor.zoom) {
@@ -341,7 +443,8 @@ impl Editor {
         });
     }
 
-    fn show_edit_toolbar(&mut self, ui: &mut egui::Ui, _wc: &mut WindowContext, map_data: &mut MapData) {
+    fn show_edit_toolbar(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, dialogs: &mut Dialogs,
+                         map_data: &mut MapData, tileset: Option<&Tileset>) {
         egui::Panel::top(format!("editor_panel_{}_edit_toolbar", self.asset_id)).show(ui, |ui| {
             ui.add_space(2.0);
             ui.horizontal(|ui| {
@@ -428,17 +531,72 @@ impl Editor {
                         self.map_editor.set_tool(MapTool::SelectAllLayers);
                     }
 
+                if ui.add_enabled(tools_enabled, egui::Button::image(IMAGES.fill)
+                                  .selected(self.map_editor.tool == MapTool::FloodFill)
+                                  .frame_when_inactive(self.map_editor.tool == MapTool::FloodFill))
+                    .on_hover_text("Flood fill").clicked() {
+                        self.map_editor.set_tool(MapTool::FloodFill);
+                    }
+
+                if ui.add_enabled(tools_enabled, egui::Button::new("\u{25a3}")
+                                  .selected(self.map_editor.tool == MapTool::RectFill)
+                                  .frame_when_inactive(self.map_editor.tool == MapTool::RectFill))
+                    .on_hover_text("Fill rectangle").clicked() {
+                        self.map_editor.set_tool(MapTool::RectFill);
+                    }
+
+                if ui.add_enabled(tools_enabled, egui::Button::new("\u{229e}")
+                                  .selected(self.map_editor.tool == MapTool::Stamp)
+                                  .frame_when_inactive(self.map_editor.tool == MapTool::Stamp))
+                    .on_hover_text("Paint stamp").clicked() {
+                        self.map_editor.set_tool(MapTool::Stamp);
+                    }
+
+                let terrain_enabled = tools_enabled && self.map_editor.edit_layer != MapLayer::Effects;
+                if ui.add_enabled(terrain_enabled, egui::Button::new("\u{26f0}")
+                                  .selected(self.map_editor.tool == MapTool::Terrain)
+                                  .frame_when_inactive(self.map_editor.tool == MapTool::Terrain))
+                    .on_hover_text("Paint terrain").clicked() {
+                        self.map_editor.set_tool(MapTool::Terrain);
+                    }
+
                 ui.spacing_mut().item_spacing = spacing;
+
+                if self.map_editor.tool == MapTool::Stamp {
+                    ui.add_space(5.0);
+                    if ui.button("Stamp...").on_hover_text("Choose the stamp tiles").clicked() {
+                        let left_tile = Self::image_selection_to_tile(self.image_picker.get_selected_image());
+                        dialogs.stamp_dialog.set_open(wc, &self.map_editor.stamp, self.image_picker.get_selected_range(), left_tile);
+                    }
+                    ui.add_space(2.0);
+                    ui.label(self.map_editor.stamp.text());
+                }
+
+                if self.map_editor.tool == MapTool::Terrain && let Some(tileset) = tileset {
+                    ui.add_space(5.0);
+                    let selected_text = match tileset.terrains.get(self.map_editor.terrain_index) {
+                        Some(terrain) => terrain.name.as_str(),
+                        None => "(no terrains)",
+                    };
+                    egui::ComboBox::from_id_salt(format!("editor_{}_terrain_combo", self.asset_id))
+                        .selected_text(selected_text)
+                        .width(120.0)
+                        .show_ui(ui, |ui| {
+                            for (index, terrain) in tileset.terrains.iter().enumerate() {
+                                ui.selectable_value(&mut self.map_editor.terrain_index, index, &terrain.name);
+                            }
+                        }).response.on_hover_text("Terrain (edit terrains in the tileset editor)");
+                }
             });
             ui.add_space(0.0);  // don't remove this, it's necessary
         });
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, dialogs: &mut Dialogs,
-                map_data: &mut MapData, tilesets: &AssetList<Tileset>) {
+                map_data: &mut MapData, tilesets: &AssetList<Tileset>, fx_types: &[MapEffectType]) {
         self.show_menubar(ui, wc, dialogs, map_data);
         self.show_display_toolbar(ui, wc, map_data);
-        self.show_edit_toolbar(ui, wc, map_data);
+        self.show_edit_toolbar(ui, wc, dialogs, map_data, tilesets.get(&map_data.tileset_id));
 
         if let Some(tileset) = tilesets.get(&map_data.tileset_id) {
             // tile picker:
@@ -449,9 +607,17 @@ impl Editor {
                     MapLayer::Effects => {
                         let tiles = STATIC_IMAGES.fx_tiles();
                         let texture = tiles.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent);
+                        self.image_picker.item_labels.clear();
+                        self.image_picker.item_labels.extend(fx_types.iter().map(|fx_type| {
+                            (fx_type.name.clone(), map_fx_type_color(fx_type))
+                        }));
                         self.image_picker.show(ui, wc.settings, tiles, texture, egui::Color32::BLACK);
+                        if let Some(fx_type) = self.image_picker.get_selected_image().and_then(|value| fx_types.get(value as usize)) {
+                            ui.label(&fx_type.name);
+                        }
                     }
                     _ => {
+                        self.image_picker.item_labels.clear();
                         let bg_color = if self.use_custom_bg_color { self.custom_bg_color } else { wc.settings.map_bg_color };
                         let texture = tileset.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent);
                         self.image_picker.show(ui, wc.settings, tileset, texture, bg_color);
@@ -463,7 +629,7 @@ impl Editor {
 
             // body:
             egui::CentralPanel::default().show(ui, |ui| {
-                self.map_editor.show(ui, wc, map_data, tileset);
+                self.map_editor.show(ui, wc, map_data, tileset, fx_types);
                 if self.map_editor.left_draw_tile_changed {
                     self.map_editor.left_draw_tile_changed = false;
                     self.image_picker.set_selected_image(Self::tile_to_image_selection(self.map_editor.left_draw_tile));
@@ -0,0 +1,152 @@
+use crate::data_asset::{
+    DataAssetId,
+    MapData,
+    Tileset,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+};
+use super::super::widgets::MapViewWidget;
+
+const PREVIEW_ZOOM: f32 = 2.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PanDirection {
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl PanDirection {
+    pub fn text(&self) -> &'static str {
+        match self {
+            PanDirection::Horizontal => "Horizontal",
+            PanDirection::Vertical => "Vertical",
+            PanDirection::Both => "Both",
+        }
+    }
+}
+
+pub struct ParallaxPreviewDialog {
+    pub open: bool,
+    pub camera_pos: egui::Vec2,
+    window_id: egui::Id,
+    direction_combo_id: egui::Id,
+    playing: bool,
+    speed: f32,
+    direction: PanDirection,
+    pan_dir: egui::Vec2,
+}
+
+impl ParallaxPreviewDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        ParallaxPreviewDialog {
+            window_id: egui::Id::new(format!("editor_{}_parallax_preview", id)),
+            direction_combo_id: egui::Id::new(format!("editor_{}_parallax_preview_direction_combo", id)),
+            open: false,
+            camera_pos: egui::Vec2::ZERO,
+            playing: true,
+            speed: 120.0,
+            direction: PanDirection::Horizontal,
+            pan_dir: egui::Vec2::splat(1.0),
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, map_data: &MapData, camera_pos: egui::Vec2) {
+        self.camera_pos = camera_pos.clamp(egui::Vec2::ZERO, MapViewWidget::max_camera_pos(map_data));
+        self.pan_dir = egui::Vec2::splat(1.0);
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    /**
+     * Move the camera back and forth across the map.
+     */
+    fn pan_camera(&mut self, map_data: &MapData, dt: f32) {
+        let max_pos = MapViewWidget::max_camera_pos(map_data);
+        let axes = match self.direction {
+            PanDirection::Horizontal => egui::Vec2::new(1.0, 0.0),
+            PanDirection::Vertical => egui::Vec2::new(0.0, 1.0),
+            PanDirection::Both => egui::Vec2::splat(1.0),
+        };
+        let pos = self.camera_pos + self.speed * dt * axes * self.pan_dir;
+        if pos.x <= 0.0 { self.pan_dir.x = 1.0; }
+        if pos.x >= max_pos.x { self.pan_dir.x = -1.0; }
+        if pos.y <= 0.0 { self.pan_dir.y = 1.0; }
+        if pos.y >= max_pos.y { self.pan_dir.y = -1.0; }
+        self.camera_pos = pos.clamp(egui::Vec2::ZERO, max_pos);
+    }
+
+    fn show_controls(&mut self, ui: &mut egui::Ui, map_data: &MapData) {
+        let max_pos = MapViewWidget::max_camera_pos(map_data);
+        ui.horizontal(|ui| {
+            let text = if self.playing { "Pause" } else { "Play" };
+            if ui.add(egui::Button::new(text).min_size(egui::Vec2::new(60.0, 0.0))).clicked() {
+                self.playing = ! self.playing;
+            }
+            ui.add_space(8.0);
+            ui.label("Pan:");
+            egui::ComboBox::from_id_salt(self.direction_combo_id)
+                .selected_text(self.direction.text())
+                .width(100.0)
+                .show_ui(ui, |ui| {
+                    for direction in [PanDirection::Horizontal, PanDirection::Vertical, PanDirection::Both] {
+                        ui.selectable_value(&mut self.direction, direction, direction.text());
+                    }
+                });
+            ui.add_space(8.0);
+            ui.label("Speed:");
+            ui.add(egui::DragValue::new(&mut self.speed).range(10.0..=1000.0).speed(5.0).suffix(" px/s"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Camera:");
+            ui.add_enabled(max_pos.x > 0.0, egui::Slider::new(&mut self.camera_pos.x, 0.0..=max_pos.x).step_by(1.0).prefix("x: "));
+            ui.add_enabled(max_pos.y > 0.0, egui::Slider::new(&mut self.camera_pos.y, 0.0..=max_pos.y).step_by(1.0).prefix("y: "));
+        });
+    }
+
+    fn show_ratio(ui: &mut egui::Ui, map_data: &MapData) {
+        let max_pos = MapViewWidget::max_camera_pos(map_data);
+        let max_para_pos = MapViewWidget::calc_para_camera_pos(map_data, max_pos);
+        let ratio = |para: f32, full: f32| if full > 0.0 { format!("{:.2}", para / full) } else { "-".to_owned() };
+        ui.label(format!(
+            "Parallax {}x{} on map {}x{}: parallax scrolls at {} (horizontal) and {} (vertical) of the map speed.",
+            map_data.para_width, map_data.para_height, map_data.width, map_data.height,
+            ratio(max_para_pos.x, max_pos.x), ratio(max_para_pos.y, max_pos.y)
+        ));
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, map_data: &MapData, tileset: &Tileset) -> bool {
+        if ! self.open { return false; }
+
+        let mut closed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 700.0, "Parallax Preview", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                    if self.playing {
+                        self.pan_camera(map_data, ui.input(|i| i.stable_dt).min(0.1));
+                        ui.ctx().request_repaint();
+                    }
+                    self.show_controls(ui, map_data);
+                    ui.add_space(8.0);
+                    MapViewWidget::show_screen(ui, wc, map_data, tileset, self.camera_pos.floor(), PREVIEW_ZOOM);
+                    ui.add_space(8.0);
+                    Self::show_ratio(ui, map_data);
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Close").clicked() {
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+            closed = true;
+        }
+        closed
+    }
+}
@@ -0,0 +1,136 @@
+use crate::data_asset::{
+    DataAssetId,
+    MapData,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    MapResizeAnchor,
+};
+
+pub struct ResizeDialog {
+    pub open: bool,
+    pub width: u32,
+    pub height: u32,
+    pub anchor: MapResizeAnchor,
+    pub resize_parallax: bool,
+    pub new_tile: u8,
+    window_id: egui::Id,
+    old_width: u32,
+    old_height: u32,
+    old_para_width: u32,
+    old_para_height: u32,
+}
+
+impl ResizeDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        ResizeDialog {
+            window_id: egui::Id::new(format!("editor_{}_resize", id)),
+            open: false,
+            width: 0,
+            height: 0,
+            anchor: MapResizeAnchor::TOP_LEFT,
+            resize_parallax: true,
+            new_tile: 0,
+            old_width: 0,
+            old_height: 0,
+            old_para_width: 0,
+            old_para_height: 0,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, map_data: &MapData, new_tile: u8) {
+        self.width = map_data.width;
+        self.height = map_data.height;
+        self.old_width = map_data.width;
+        self.old_height = map_data.height;
+        self.old_para_width = map_data.para_width;
+        self.old_para_height = map_data.para_height;
+        self.new_tile = new_tile;
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    /**
+     * Return the new parallax size: if requested, it's changed by the same
+     * amount as the map, but it must never be larger than the map.
+     */
+    pub fn parallax_size(&self) -> (u32, u32) {
+        if self.old_para_width == 0 || self.old_para_height == 0 {
+            return (self.old_para_width, self.old_para_height);
+        }
+        let calc = |para: u32, old: u32, new: u32| {
+            let para = if self.resize_parallax { (para + new).saturating_sub(old).max(1) } else { para };
+            para.min(new)
+        };
+        (calc(self.old_para_width, self.old_width, self.width), calc(self.old_para_height, self.old_height, self.height))
+    }
+
+    fn show_anchor(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new(self.window_id.with("anchor")).spacing([2.0, 2.0]).show(ui, |ui| {
+            for y in 0..3 {
+                for x in 0..3 {
+                    let anchor = MapResizeAnchor { x, y };
+                    let text = if anchor == self.anchor { "\u{25cf}" } else { "\u{25cb}" };
+                    if ui.add(egui::Button::new(text).min_size(egui::Vec2::splat(24.0)).selected(anchor == self.anchor)).clicked() {
+                        self.anchor = anchor;
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext) -> bool {
+        if ! self.open { return false; }
+
+        let mut confirmed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 350.0, "Resize Map", |ui, _wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                egui::Grid::new(self.window_id.with("grid"))
+                    .num_columns(2)
+                    .spacing([8.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("Width:");
+                        ui.add(egui::Slider::new(&mut self.width, 1..=512).step_by(1.0));
+                        ui.end_row();
+
+                        ui.label("Height:");
+                        ui.add(egui::Slider::new(&mut self.height, 1..=512).step_by(1.0));
+                        ui.end_row();
+
+                        ui.label("Anchor:");
+                        self.show_anchor(ui);
+                        ui.end_row();
+
+                        if self.old_para_width != 0 && self.old_para_height != 0 {
+                            ui.label("Parallax:");
+                            ui.vertical(|ui| {
+                                ui.checkbox(&mut self.resize_parallax, "Resize by the same amount");
+                                let (para_width, para_height) = self.parallax_size();
+                                ui.label(format!("{}x{} \u{2192} {}x{}", self.old_para_width, self.old_para_height, para_width, para_height));
+                            });
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(8.0);
+                ui.label("Rooms using this map will be adjusted to keep the anchored contents in place.");
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    confirmed = true;
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+        confirmed
+    }
+}
@@ -0,0 +1,101 @@
+use crate::data_asset::DataAssetId;
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    MapLayer,
+};
+
+const LAYER_OPTIONS: &[(Option<MapLayer>, &str)] = &[
+    (None, "All layers"),
+    (Some(MapLayer::Foreground), "Foreground"),
+    (Some(MapLayer::Background), "Background"),
+    (Some(MapLayer::Effects), "Effects"),
+    (Some(MapLayer::Parallax), "Parallax"),
+];
+
+pub struct ShiftDialog {
+    pub open: bool,
+    pub dx: i32,
+    pub dy: i32,
+    pub layer: Option<MapLayer>,
+    pub wrap: bool,
+    pub new_tile: u8,
+    window_id: egui::Id,
+    layer_combo_id: egui::Id,
+}
+
+impl ShiftDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        ShiftDialog {
+            window_id: egui::Id::new(format!("editor_{}_shift", id)),
+            layer_combo_id: egui::Id::new(format!("editor_{}_shift_layer_combo", id)),
+            open: false,
+            dx: 0,
+            dy: 0,
+            layer: None,
+            wrap: true,
+            new_tile: 0,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, new_tile: u8) {
+        self.new_tile = new_tile;
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn layer_text(layer: Option<MapLayer>) -> &'static str {
+        LAYER_OPTIONS.iter().find(|&&(l, _)| l == layer).map(|&(_, text)| text).unwrap_or("?")
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext) -> bool {
+        if ! self.open { return false; }
+
+        let mut confirmed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 350.0, "Shift Map", |ui, _wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                egui::Grid::new(self.window_id.with("grid"))
+                    .num_columns(2)
+                    .spacing([8.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("Layer:");
+                        egui::ComboBox::from_id_salt(self.layer_combo_id)
+                            .selected_text(Self::layer_text(self.layer))
+                            .show_ui(ui, |ui| {
+                                for &(layer, text) in LAYER_OPTIONS {
+                                    ui.selectable_value(&mut self.layer, layer, text);
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Horizontal:");
+                        ui.add(egui::DragValue::new(&mut self.dx).range(-512..=512).suffix(" tiles"));
+                        ui.end_row();
+
+                        ui.label("Vertical:");
+                        ui.add(egui::DragValue::new(&mut self.dy).range(-512..=512).suffix(" tiles"));
+                        ui.end_row();
+
+                        ui.label("");
+                        ui.checkbox(&mut self.wrap, "Wrap around");
+                        ui.end_row();
+                    });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    confirmed = self.dx != 0 || self.dy != 0;
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+        confirmed
+    }
+}
@@ -0,0 +1,195 @@
+use crate::image::{
+    ImageCollection,
+    TextureSlot,
+};
+use crate::data_asset::{
+    DataAssetId,
+    MapData,
+    Tileset,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    MapStamp,
+    MapStampMode,
+    MapClipboardData,
+};
+
+const PREVIEW_TILE_SIZE: f32 = 24.0;
+
+pub struct StampDialog {
+    pub open: bool,
+    pub stamp: MapStamp,
+    window_id: egui::Id,
+    picker_range: Option<(u32, u32)>,
+    left_tile: u8,
+    block_width: u32,
+}
+
+impl StampDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        StampDialog {
+            window_id: egui::Id::new(format!("editor_{}_stamp", id)),
+            open: false,
+            stamp: MapStamp::new(),
+            picker_range: None,
+            left_tile: 0,
+            block_width: 1,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, stamp: &MapStamp, picker_range: Option<(u32, u32)>, left_tile: u8) {
+        self.stamp = stamp.clone();
+        self.picker_range = picker_range;
+        self.left_tile = left_tile;
+        if let Some((first, last)) = picker_range {
+            self.block_width = self.block_width.clamp(1, last - first + 1);
+        }
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn paint_tile(ui: &egui::Ui, wc: &mut WindowContext, tileset: &Tileset, tile: u8, rect: egui::Rect) {
+        ui.painter().rect_filled(rect, egui::CornerRadius::ZERO, wc.settings.map_bg_color);
+        if tile == MapData::NO_TILE || tile as u32 >= tileset.num_tiles { return; }
+        let texture = tileset.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent);
+        let size = egui::Vec2::splat(Tileset::TILE_SIZE as f32);
+        egui::Image::from_texture((texture.id(), size)).uv(tileset.get_item_uv(tile as u32)).paint_at(ui, rect);
+    }
+
+    fn show_block_preview(&self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        let block = &self.stamp.block;
+        let size = PREVIEW_TILE_SIZE * egui::Vec2::new(block.width as f32, block.height as f32);
+        let (resp, _) = ui.allocate_painter(size, egui::Sense::hover());
+        for y in 0..block.height {
+            for x in 0..block.width {
+                let pos = resp.rect.min + PREVIEW_TILE_SIZE * egui::Vec2::new(x as f32, y as f32);
+                let rect = egui::Rect::from_min_size(pos, egui::Vec2::splat(PREVIEW_TILE_SIZE));
+                Self::paint_tile(ui, wc, tileset, block.get_tile(x, y), rect);
+            }
+        }
+    }
+
+    fn show_block(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        egui::ScrollArea::both().id_salt("stamp_block_preview").max_height(200.0).show(ui, |ui| {
+            self.show_block_preview(ui, wc, tileset);
+        });
+        ui.add_space(8.0);
+
+        match self.picker_range {
+            Some((first, last)) => {
+                ui.horizontal(|ui| {
+                    let count = last - first + 1;
+                    ui.label(format!("Tiles {} to {} from the tile picker, width:", first, last));
+                    ui.add(egui::DragValue::new(&mut self.block_width).range(1..=count));
+                    if ui.button("Use").clicked() {
+                        let width = self.block_width;
+                        let height = count.div_ceil(width);
+                        let data = (0..width * height).map(|i| {
+                            if i < count { (first + i) as u8 } else { MapData::NO_TILE }
+                        }).collect();
+                        self.stamp.set_block(width, height, data);
+                    }
+                });
+            }
+            None => {
+                ui.label("Drag or shift+click in the tile picker to select several tiles.");
+            }
+        }
+
+        let clipboard_block = match &wc.map_clipboard {
+            MapClipboardData::MapLayerFragment(frag) => Some((frag.width, frag.height, &frag.data)),
+            MapClipboardData::MapWholeFragment(frag) => Some((frag.width, frag.height, &frag.fg_data)),
+            MapClipboardData::Empty => None,
+        };
+        if ui.add_enabled(clipboard_block.is_some(), egui::Button::new("Use map clipboard"))
+            .on_hover_text("Use the tiles copied from a map or from the tileset editor grid")
+            .clicked() && let Some((width, height, data)) = clipboard_block {
+                self.stamp.set_block(width, height, data.clone());
+            }
+    }
+
+    fn show_random(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        let total = self.stamp.random_weights.iter().sum::<u32>().max(1);
+        let mut remove = None;
+        egui::ScrollArea::vertical().id_salt("stamp_random_tiles").max_height(200.0).show(ui, |ui| {
+            let stamp = &mut self.stamp;
+            for (index, (tile, weight)) in stamp.random_tiles.iter().zip(stamp.random_weights.iter_mut()).enumerate() {
+                ui.horizontal(|ui| {
+                    let (resp, _) = ui.allocate_painter(egui::Vec2::splat(PREVIEW_TILE_SIZE), egui::Sense::hover());
+                    Self::paint_tile(ui, wc, tileset, *tile, resp.rect);
+                    ui.label(format!("tile {:3}", tile));
+                    ui.add_space(8.0);
+                    ui.label("Weight:");
+                    ui.add(egui::DragValue::new(weight).range(0..=100));
+                    ui.label(format!("({}%)", *weight * 100 / total));
+                    if ui.button("Remove").clicked() {
+                        remove = Some(index);
+                    }
+                });
+            }
+        });
+        if let Some(index) = remove {
+            self.stamp.random_tiles.remove(index);
+            self.stamp.random_weights.remove(index);
+        }
+
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            let new_tiles = match self.picker_range {
+                Some((first, last)) => (first..=last).map(|t| t as u8).collect::<Vec<_>>(),
+                None if self.left_tile != MapData::NO_TILE => vec![self.left_tile],
+                None => Vec::new(),
+            };
+            if ui.add_enabled(! new_tiles.is_empty(), egui::Button::new("Add selected tiles")).clicked() {
+                for tile in new_tiles {
+                    if self.stamp.random_tiles.contains(&tile) { continue; }
+                    self.stamp.random_tiles.push(tile);
+                    self.stamp.random_weights.push(1);
+                }
+            }
+            if ui.add_enabled(! self.stamp.random_tiles.is_empty(), egui::Button::new("Clear")).clicked() {
+                self.stamp.random_tiles.clear();
+                self.stamp.random_weights.clear();
+            }
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, tileset: &Tileset) -> bool {
+        if ! self.open { return false; }
+
+        let mut confirmed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 450.0, "Stamp", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Stamp type:");
+                        for mode in [MapStampMode::Block, MapStampMode::Random] {
+                            ui.radio_value(&mut self.stamp.mode, mode, mode.text());
+                        }
+                    });
+                    ui.add_space(8.0);
+                    match self.stamp.mode {
+                        MapStampMode::Block => self.show_block(ui, wc, tileset),
+                        MapStampMode::Random => self.show_random(ui, wc, tileset),
+                    }
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    confirmed = true;
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+        confirmed
+    }
+}
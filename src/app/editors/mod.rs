@@ -122,8 +122,14 @@ impl AssetEditorBase {
         self.saved_hash = self.cur_hash;
     }
 
-    pub fn update_dirty_flag(&mut self, asset: &impl std::hash::Hash) {
-        self.cur_hash = calc_hash(asset);
+    /**
+     * Update the current hash of the asset, returning true if it changed.
+     */
+    pub fn update_dirty_flag(&mut self, asset: &impl std::hash::Hash) -> bool {
+        let hash = calc_hash(asset);
+        let changed = hash != self.cur_hash;
+        self.cur_hash = hash;
+        changed
     }
 
     fn calc_image_editor_window_size(image: &impl ImageCollection) -> (egui::Vec2, egui::Vec2) {
@@ -11,7 +11,8 @@ use egui_extras::{
 use crate::misc::{
     IMAGES,
     mod_utils,
-    wav_utils,
+    iff_utils,
+    sample_utils,
 };
 use crate::sound::SoundPlayer;
 use crate::data_asset::{
@@ -32,6 +33,7 @@ use super::widgets::{
     SfxEditorWidget,
     SfxEditorAction,
 };
+use super::dialogs::ImportSampleDialog;
 use super::super::{
     menu_item,
     menu_item_no_image,
@@ -60,7 +62,7 @@ impl ModDataEditor {
         ModDataEditor {
             base: AssetEditorBase::new(id, open),
             editor: Editor::new(id),
-            dialogs: Dialogs::new(),
+            dialogs: Dialogs::new(id),
         }
     }
 
@@ -86,7 +88,7 @@ impl ModDataEditor {
     }
 
     pub fn show(&mut self, wc: &mut WindowContext, mod_data: &mut ModData, sound_player: &mut SoundPlayer) {
-        self.dialogs.show(wc, &mut self.editor, mod_data);
+        self.dialogs.show(wc, &mut self.editor, mod_data, sound_player);
 
         self.base.show_window(wc, mod_data, [610.0, 380.0], [610.0, 400.0], |ui, wc, mod_data, base| {
             Self::show_footer(ui, wc, mod_data, base);
@@ -99,18 +101,20 @@ struct Dialogs {
     properties_dialog: PropertiesDialog,
     export_sample_dialog: ExportSampleDialog,
     transpose_dialog: TransposeDialog,
+    import_sample_dialog: ImportSampleDialog,
 }
 
 impl Dialogs {
-    fn new() -> Self {
+    fn new(id: DataAssetId) -> Self {
         Dialogs {
             properties_dialog: PropertiesDialog::new(),
             export_sample_dialog: ExportSampleDialog::new(),
             transpose_dialog: TransposeDialog::new(),
+            import_sample_dialog: ImportSampleDialog::new(id),
         }
     }
 
-    pub fn show(&mut self, wc: &mut WindowContext, _editor: &mut Editor, mod_data: &mut ModData) {
+    pub fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, mod_data: &mut ModData, sound_player: &mut SoundPlayer) {
         if self.properties_dialog.open {
             self.properties_dialog.show(wc, mod_data);
         }
@@ -120,6 +124,9 @@ impl Dialogs {
         if self.transpose_dialog.open {
             self.transpose_dialog.show(wc, mod_data);
         }
+        if self.import_sample_dialog.open && self.import_sample_dialog.show(wc, sound_player, editor.play_volume) {
+            editor.import_samples(wc, &mut self.import_sample_dialog, mod_data);
+        }
     }
 }
 
@@ -134,6 +141,9 @@ struct Editor {
     sfx_editor: SfxEditorWidget,
     play_volume: f32,
     play_freq: f32,
+    crossfade_len: u32,
+    sample_undo: Option<(usize, ModSample)>,
+    sample_redo: Option<(usize, ModSample)>,
 }
 
 impl Editor {
@@ -149,9 +159,30 @@ impl Editor {
             sfx_editor: SfxEditorWidget::new(),
             play_volume: 0.5,
             play_freq: 11025.0,
+            crossfade_len: 256,
+            sample_undo: None,
+            sample_redo: None,
         }
     }
 
+    fn undo_sample(&mut self, mod_data: &mut ModData) {
+        if let Some((sample_index, undo_sample)) = self.sample_undo.take() &&
+            let Some(sample) = mod_data.samples.get_mut(sample_index) {
+                self.sample_redo = Some((sample_index, std::mem::replace(sample, undo_sample)));
+                self.selected_sample = sample_index;
+                self.sfx_editor.selection = None;
+            }
+    }
+
+    fn redo_sample(&mut self, mod_data: &mut ModData) {
+        if let Some((sample_index, redo_sample)) = self.sample_redo.take() &&
+            let Some(sample) = mod_data.samples.get_mut(sample_index) {
+                self.sample_undo = Some((sample_index, std::mem::replace(sample, redo_sample)));
+                self.selected_sample = sample_index;
+                self.sfx_editor.selection = None;
+            }
+    }
+
     fn get_pattern_sample_to_play(cell_index: usize, mod_data: &ModData) -> Option<usize> {
         let row_stride = mod_data.num_channels as usize;
         let cell_row = cell_index / row_stride;
@@ -206,14 +237,17 @@ impl Editor {
                         self.select_sample(sample_index);
                     }
                     egui::Popup::context_menu(&resp).show(|ui| {
-                        if ui.add(menu_item(IMAGES.import, " Import WAV...")).clicked() {
+                        if ui.add(menu_item(IMAGES.import, " Import sample...")).clicked() {
                             wc.sys_dialogs.open_file(
                                 Some(wc.egui.window),
                                 self.import_sample_sys_dlg_id.clone(),
                                 "mod",
-                                "Import WAVE file",
+                                "Import sample file",
                                 &[
+                                    ("Sample files (*.wav, *.aiff, *.8svx)", &["wav", "aif", "aiff", "aifc", "8svx", "iff"]),
                                     ("WAVE files (*.wav)", &["wav"]),
+                                    ("AIFF files (*.aiff)", &["aif", "aiff", "aifc"]),
+                                    ("IFF-8SVX files (*.8svx)", &["8svx", "iff"]),
                                     ("All files (*)", &[""]),
                                 ]
                             );
@@ -243,6 +277,31 @@ impl Editor {
                 ).on_hover_text("Set Loop Bounds").clicked() {
                     self.sfx_editor.set_tool(SfxTool::SetLoop);
                 }
+                ui.toggle_value(&mut self.sfx_editor.snap_to_zero_crossing, "Snap")
+                    .on_hover_text("Snap loop and selection to zero crossings");
+                ui.separator();
+
+                let Some(sample) = mod_data.samples.get_mut(self.selected_sample) else { return; };
+                let loop_start = sample.loop_start;
+                let loop_end = sample.loop_start + sample.loop_len;
+                let Some(sample_data) = &mut sample.data else { return; };
+                ui.add(egui::DragValue::new(&mut self.crossfade_len).speed(4.0).range(1..=65536));
+                if ui.add_enabled(loop_end > loop_start, egui::Button::new("Crossfade")).on_hover_text("Crossfade loop end into the loop start").clicked() {
+                    let undo_data = sample_data.clone();
+                    let len = sample_utils::crossfade_loop(sample_data, loop_start, loop_end, self.crossfade_len);
+                    if len == 0 {
+                        wc.open_message_box("Loop Crossfade", "The loop needs samples before its start to crossfade.\n\nMove the loop start forward and try again.");
+                    } else {
+                        self.sample_undo = Some((self.selected_sample, ModSample { data: Some(undo_data), ..*sample }));
+                        self.sample_redo = None;
+                    }
+                }
+                if sound_player.is_available() &&
+                    ui.add_enabled(loop_end > loop_start, egui::Button::new("▶ Seam ")).on_hover_text("Play the loop seam repeatedly").clicked() {
+                        let seam_len = (self.play_freq / 4.0) as u32;
+                        let preview = sample_utils::loop_seam_preview(sample_data, loop_start, loop_end, seam_len, 4);
+                        sound_player.play_s16(&preview, self.play_freq, self.play_volume);
+                    }
             });
         });
 
@@ -503,28 +562,36 @@ impl Editor {
         }
     }
 
-    fn import_sample(&mut self, wc: &mut WindowContext, file: SysDialogOpenFile, mod_data: &mut ModData) {
-        let result = file.read_data().and_then(|data| wav_utils::WavFile::read(&data)).and_then(|mut wav_file| {
-            if wav_file.channels.is_empty() { return Err(Error::other("WAV with no channels!?")); }
-            let wav_sample = wav_file.channels.remove(0);
-            let sample = mod_data.samples.get_mut(self.selected_sample)
-                .ok_or(Error::other(format!("can't find selected sample: {}", self.selected_sample)))?;
-            sample.len = wav_sample.len() as u32;
-            sample.data = Some(wav_sample);
-            sample.bits_per_sample = wav_file.bits_per_sample;
-            sample.loop_start = 0;
-            sample.loop_len = 0;
-            if sample.volume == 0 {
-                sample.volume = MAX_VOLUME;
-            }
+    fn import_sample(&mut self, wc: &mut WindowContext, dialogs: &mut Dialogs, file: SysDialogOpenFile) {
+        let result = file.read_data().and_then(|data| iff_utils::read_sample_file(&data)).and_then(|wav_file| {
+            if wav_file.channels.is_empty() { return Err(Error::other("sample file with no channels!?")); }
+            dialogs.import_sample_dialog.set_open(wc, wav_file, file.filename(), self.play_freq as u32);
             Ok(())
         });
 
         if let Err(e) = result {
-            wc.logger.log(format!("ERROR reading WAVE file from {}:", file.filename()));
+            wc.logger.log(format!("ERROR reading sample file from {}:", file.filename()));
             wc.logger.log(format!("{}", e));
-            wc.open_message_box("Error importing sample", "Error importing WAVE file.\n\nConsult the log window for more information.");
+            wc.open_message_box("Error importing sample", "Error importing sample file.\n\nConsult the log window for more information.");
+        }
+    }
+
+    fn import_samples(&mut self, wc: &mut WindowContext, import_sample_dialog: &mut ImportSampleDialog, mod_data: &mut ModData) {
+        let Some(sample) = mod_data.samples.get_mut(self.selected_sample) else {
+            wc.logger.log(format!("ERROR importing sample: can't find selected sample: {}", self.selected_sample));
+            return;
+        };
+        let data = std::mem::take(&mut import_sample_dialog.samples);
+        sample.len = data.len() as u32;
+        sample.data = Some(data);
+        sample.bits_per_sample = import_sample_dialog.bits_per_sample;
+        sample.loop_start = import_sample_dialog.loop_start;
+        sample.loop_len = import_sample_dialog.loop_len;
+        if sample.volume == 0 {
+            sample.volume = MAX_VOLUME;
         }
+        self.play_freq = import_sample_dialog.play_freq();
+        self.sfx_editor.reset();
     }
 
     pub fn show(
@@ -536,7 +603,7 @@ impl Editor {
         sound_player: &mut SoundPlayer
     ) {
         if let Some(SysDialogResponse::File(file)) = wc.sys_dialogs.get_response_for(&self.import_sample_sys_dlg_id) {
-            self.import_sample(wc, file, mod_data);
+            self.import_sample(wc, dialogs, file);
         }
         if let Some(SysDialogResponse::File(file)) = wc.sys_dialogs.get_response_for(&self.import_mod_sys_dlg_id) {
             self.import_mod(wc, file, mod_data);
@@ -545,6 +612,17 @@ impl Editor {
             self.export_mod(wc, file, mod_data);
         }
 
+        if wc.is_editor_on_top(self.asset_id) {
+            let ctrl_shift_z = egui::KeyboardShortcut::new(egui::Modifiers::CTRL|egui::Modifiers::SHIFT, egui::Key::Z);
+            if ui.input_mut(|i| i.consume_shortcut(&ctrl_shift_z)) {
+                self.redo_sample(mod_data);
+            }
+            let ctrl_z = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Z);
+            if ui.input_mut(|i| i.consume_shortcut(&ctrl_z)) {
+                self.undo_sample(mod_data);
+            }
+        }
+
         // header:
         egui::Panel::top(format!("editor_panel_{}_top", self.asset_id)).show(ui, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
@@ -579,6 +657,15 @@ impl Editor {
                     }
                 });
                 ui.menu_button("Edit", |ui| {
+                    if ui.add_enabled(self.sample_undo.is_some(), menu_item(IMAGES.undo, " Undo")).clicked() {
+                        self.undo_sample(mod_data);
+                    }
+                    if ui.add_enabled(self.sample_redo.is_some(), menu_item(IMAGES.redo, " Redo")).clicked() {
+                        self.redo_sample(mod_data);
+                    }
+
+                    ui.separator();
+
                     if ui.add(menu_item_no_image(" Transpose...")).clicked() {
                         dialogs.transpose_dialog.set_open(wc, mod_data);
                     }
@@ -33,6 +33,7 @@ use super::widgets::{
     ImageEditorWidget,
     ImageEditorAction,
     ImageDrawingTool,
+    ImageBrushMenuAction,
     ImageDisplay,
 };
 use super::super::{
@@ -329,7 +330,8 @@ impl Editor {
         });
     }
 
-    fn show_toolbar(&mut self, ui: &mut egui::Ui, _wc: &mut WindowContext, pal_sprite: &mut PalSprite) {
+    fn show_toolbar(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, pal_sprite: &mut PalSprite) {
+        self.image_editor.reference.update(wc, format!("pal_sprite/{}", pal_sprite.asset.name));
         egui::Panel::top(format!("editor_panel_{}_toolbar", self.asset_id)).show(ui, |ui| {
             ui.add_space(2.0);
             ui.horizontal(|ui| {
@@ -356,6 +358,26 @@ impl Editor {
                     self.image_editor.set_tool(ImageDrawingTool::Select);
                 }
 
+                for &tool in ImageDrawingTool::SELECTIONS.iter().chain(ImageDrawingTool::SHAPES) {
+                    if ui.add(egui::Button::new(tool.shape_icon())
+                              .selected(self.image_editor.get_tool() == tool)
+                              .frame_when_inactive(self.image_editor.get_tool() == tool)).on_hover_text(tool.shape_name()).clicked() {
+                        self.image_editor.set_tool(tool);
+                    }
+                }
+
+                ui.add_space(5.0);
+                let can_capture = ! self.image_editor.selection.is_empty();
+                if let ImageBrushMenuAction::Capture = self.image_editor.brush.show_menu(ui, can_capture) {
+                    self.image_editor.capture_brush(pal_sprite);
+                }
+                ui.add_space(5.0);
+                self.image_editor.onion_skin.show_menu(ui, &[], &mut None);
+                ui.add_space(5.0);
+                self.image_editor.symmetry.show_menu(ui);
+                ui.add_space(5.0);
+                self.image_editor.reference.show_menu(ui, wc);
+
                 ui.add_space(5.0);
                 ui.separator();
                 ui.add_space(5.0);
@@ -8,8 +8,8 @@ use super::super::{
     SysDialogOpenFile,
 };
 
-const ALLOWED_SAMPLE_RATES: &[u32] = &[ 8000, 11025, 22050, 44100 ];
-const ALLOWED_BITS_PER_SAMPLE: &[u16] = &[ 8, 16 ];
+pub const ALLOWED_SAMPLE_RATES: &[u32] = &[ 8000, 11025, 22050, 44100 ];
+pub const ALLOWED_BITS_PER_SAMPLE: &[u16] = &[ 8, 16 ];
 
 pub struct ExportDialog {
     pub open: bool,
@@ -0,0 +1,241 @@
+use crate::sound::SoundPlayer;
+use crate::misc::sfx_synth::{
+    self,
+    SfxSynthRng,
+    SfxSynthCategory,
+    SfxSynthPreset,
+};
+use crate::data_asset::{
+    Sfx,
+    DataAssetId,
+    SfxSynthWave,
+    SfxSynthParams,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+};
+use super::export::{
+    ALLOWED_SAMPLE_RATES,
+    ALLOWED_BITS_PER_SAMPLE,
+};
+
+pub struct GeneratorDialog {
+    pub open: bool,
+    pub dlg_window_id: egui::Id,
+    pub params: SfxSynthParams,
+    pub bits_per_sample: u16,
+    pub samples: Vec<i16>,
+    rng: SfxSynthRng,
+}
+
+impl GeneratorDialog {
+    pub fn new() -> Self {
+        GeneratorDialog {
+            open: false,
+            dlg_window_id: egui::Id::new("dlg_sfx_generator"),
+            params: SfxSynthParams::new(),
+            bits_per_sample: 16,
+            samples: Vec::new(),
+            rng: SfxSynthRng::new(0),
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, sfx: &Sfx, sample_rate: u32) {
+        self.params = sfx.synth.clone().unwrap_or_else(|| {
+            let mut params = SfxSynthParams::new();
+            // the generator only renders at the sample rates we can export
+            params.sample_rate = ALLOWED_SAMPLE_RATES.iter().copied().min_by_key(|rate| rate.abs_diff(sample_rate)).unwrap_or(sample_rate);
+            params
+        });
+        self.bits_per_sample = sfx.bits_per_sample;
+        self.rng = SfxSynthRng::new((wc.egui.ctx.input(|i| i.time) * 1000.0) as u32);
+        self.open = true;
+        wc.set_dialog_open(self.dlg_window_id, self.open);
+    }
+
+    fn close(&mut self, wc: &mut WindowContext) {
+        self.open = false;
+        wc.set_dialog_open(self.dlg_window_id, self.open);
+    }
+
+    fn confirm(&mut self, wc: &mut WindowContext) -> bool {
+        self.samples = sfx_synth::render_samples(&self.params, self.bits_per_sample);
+        if self.samples.is_empty() {
+            wc.open_message_box("Empty Sound", "The generated sound is empty.\n\nIncrease the envelope times and try again.");
+            return false;
+        }
+        true
+    }
+
+    fn play(&self, sound_player: &mut SoundPlayer, volume: f32) {
+        let samples = sfx_synth::render_samples(&self.params, self.bits_per_sample);
+        sound_player.play_s16(&samples, self.params.sample_rate as f32, volume);
+    }
+
+    fn param_slider(ui: &mut egui::Ui, label: &str, value: &mut f32, signed: bool) {
+        ui.label(label);
+        let range = if signed { -1.0..=1.0 } else { 0.0..=1.0 };
+        ui.add(egui::Slider::new(value, range).fixed_decimals(2));
+        ui.end_row();
+    }
+
+    fn category_header(ui: &mut egui::Ui, title: &str) -> bool {
+        ui.strong(title);
+        let clicked = ui.button("Randomize").clicked();
+        ui.end_row();
+        clicked
+    }
+
+    fn show_params(&mut self, ui: &mut egui::Ui, sfx_id: DataAssetId) -> Option<SfxSynthCategory> {
+        let mut randomize = None;
+        let p = &mut self.params;
+
+        ui.columns(2, |columns| {
+            egui::Grid::new(format!("editor_{}_sfx_gen_grid_left", sfx_id))
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(&mut columns[0], |ui| {
+                    if Self::category_header(ui, "Wave") { randomize = Some(SfxSynthCategory::Wave); }
+                    ui.label("Waveform:");
+                    egui::ComboBox::from_id_salt(format!("editor_{}_sfx_gen_wave", sfx_id))
+                        .selected_text(p.wave.text())
+                        .width(100.0)
+                        .show_ui(ui, |ui| {
+                            for wave in SfxSynthWave::ALL {
+                                ui.selectable_value(&mut p.wave, *wave, wave.text());
+                            }
+                        });
+                    ui.end_row();
+                    Self::param_slider(ui, "Square duty:", &mut p.duty, false);
+                    Self::param_slider(ui, "Duty sweep:", &mut p.duty_sweep, true);
+                    Self::param_slider(ui, "Noise:", &mut p.noise, false);
+
+                    if Self::category_header(ui, "Envelope") { randomize = Some(SfxSynthCategory::Envelope); }
+                    Self::param_slider(ui, "Attack:", &mut p.attack, false);
+                    Self::param_slider(ui, "Decay:", &mut p.decay, false);
+                    Self::param_slider(ui, "Sustain:", &mut p.sustain, false);
+                    Self::param_slider(ui, "Sustain level:", &mut p.sustain_level, false);
+                    Self::param_slider(ui, "Release:", &mut p.release, false);
+
+                    if Self::category_header(ui, "Vibrato") { randomize = Some(SfxSynthCategory::Vibrato); }
+                    Self::param_slider(ui, "Depth:", &mut p.vibrato_depth, false);
+                    Self::param_slider(ui, "Speed:", &mut p.vibrato_speed, false);
+                });
+
+            egui::Grid::new(format!("editor_{}_sfx_gen_grid_right", sfx_id))
+                .num_columns(2)
+                .spacing([8.0, 4.0])
+                .show(&mut columns[1], |ui| {
+                    if Self::category_header(ui, "Frequency") { randomize = Some(SfxSynthCategory::Frequency); }
+                    Self::param_slider(ui, "Base:", &mut p.base_freq, false);
+                    Self::param_slider(ui, "Minimum:", &mut p.min_freq, false);
+                    Self::param_slider(ui, "Slide:", &mut p.freq_slide, true);
+                    Self::param_slider(ui, "Delta slide:", &mut p.freq_delta_slide, true);
+
+                    if Self::category_header(ui, "Arpeggio") { randomize = Some(SfxSynthCategory::Arpeggio); }
+                    Self::param_slider(ui, "Change:", &mut p.arp_mult, true);
+                    Self::param_slider(ui, "Speed:", &mut p.arp_speed, false);
+
+                    if Self::category_header(ui, "Filter") { randomize = Some(SfxSynthCategory::Filter); }
+                    Self::param_slider(ui, "Low-pass cutoff:", &mut p.lp_cutoff, false);
+                    Self::param_slider(ui, "Low-pass sweep:", &mut p.lp_sweep, true);
+                    Self::param_slider(ui, "Low-pass resonance:", &mut p.lp_resonance, false);
+                    Self::param_slider(ui, "High-pass cutoff:", &mut p.hp_cutoff, false);
+                    Self::param_slider(ui, "High-pass sweep:", &mut p.hp_sweep, true);
+                });
+        });
+
+        randomize
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, sfx: &Sfx, sound_player: &mut SoundPlayer, play_volume: f32) -> bool {
+        if ! self.open { return false; }
+
+        let mut confirmed = false;
+        if AssetEditorBase::show_dialog_window(wc, self.dlg_window_id, 640.0, "Generate Sfx", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                let mut play = false;
+
+                ui.horizontal_wrapped(|ui| {
+                    for preset in SfxSynthPreset::ALL {
+                        if ui.button(preset.text()).clicked() {
+                            self.params = sfx_synth::preset(*preset, self.params.sample_rate, &mut self.rng);
+                            play = true;
+                        }
+                    }
+                    if ui.button("Random").clicked() {
+                        for category in [
+                            SfxSynthCategory::Wave,
+                            SfxSynthCategory::Envelope,
+                            SfxSynthCategory::Frequency,
+                            SfxSynthCategory::Vibrato,
+                            SfxSynthCategory::Arpeggio,
+                            SfxSynthCategory::Filter,
+                        ] {
+                            sfx_synth::randomize(&mut self.params, category, &mut self.rng);
+                        }
+                        play = true;
+                    }
+                });
+                ui.add_space(8.0);
+
+                if let Some(category) = self.show_params(ui, sfx.asset.id) {
+                    sfx_synth::randomize(&mut self.params, category, &mut self.rng);
+                    play = true;
+                }
+                ui.add_space(8.0);
+
+                ui.horizontal(|ui| {
+                    ui.label("Sample rate:");
+                    egui::ComboBox::from_id_salt(format!("editor_{}_sfx_gen_sample_rate", sfx.asset.id))
+                        .selected_text(self.params.sample_rate.to_string())
+                        .width(60.0)
+                        .show_ui(ui, |ui| {
+                            for sample_rate in ALLOWED_SAMPLE_RATES {
+                                ui.selectable_value(&mut self.params.sample_rate, *sample_rate, sample_rate.to_string());
+                            }
+                        });
+                    ui.label("Hz");
+                    ui.add_space(16.0);
+                    ui.label("Bits/sample:");
+                    egui::ComboBox::from_id_salt(format!("editor_{}_sfx_gen_bits_per_sample", sfx.asset.id))
+                        .selected_text(self.bits_per_sample.to_string())
+                        .width(60.0)
+                        .show_ui(ui, |ui| {
+                            for bits_per_sample in ALLOWED_BITS_PER_SAMPLE {
+                                ui.selectable_value(&mut self.bits_per_sample, *bits_per_sample, bits_per_sample.to_string());
+                            }
+                        });
+                    ui.add_space(16.0);
+                    ui.label("Volume:");
+                    ui.add(egui::Slider::new(&mut self.params.volume, 0.0..=1.0).fixed_decimals(2));
+                });
+
+                if sound_player.is_available() {
+                    ui.add_space(8.0);
+                    if ui.button("▶ Preview ").clicked() {
+                        play = true;
+                    }
+                    if play {
+                        self.play(sound_player, play_volume);
+                    }
+                }
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() && self.confirm(wc) {
+                    confirmed = true;
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.close(wc);
+        }
+        confirmed
+    }
+}
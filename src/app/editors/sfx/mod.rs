@@ -1,17 +1,20 @@
 mod properties;
 mod export;
+mod generator;
 
 use std::io::Error;
 
 use crate::sound::SoundPlayer;
 use crate::misc::{
     IMAGES,
-    wav_utils,
+    iff_utils,
+    sample_utils,
 };
 use crate::data_asset::{
     Sfx,
     DataAssetId,
     GenericAsset,
+    SfxSynthParams,
 };
 
 use super::{
@@ -23,11 +26,18 @@ use super::{
 use super::widgets::{
     SfxTool,
     SfxEditorWidget,
+    PianoWidget,
+    PianoEvent,
+};
+use super::dialogs::ImportSampleDialog;
+use super::super::{
+    menu_item,
+    menu_item_no_image,
 };
-use super::super::menu_item;
 
 use properties::PropertiesDialog;
 use export::ExportDialog;
+use generator::GeneratorDialog;
 
 pub struct SfxEditor {
     pub base: AssetEditorBase,
@@ -40,7 +50,7 @@ impl SfxEditor {
         SfxEditor {
             base: AssetEditorBase::new(id, open),
             editor: Editor::new(id),
-            dialogs: Dialogs::new(),
+            dialogs: Dialogs::new(id),
         }
     }
 
@@ -75,23 +85,62 @@ impl SfxEditor {
 struct Dialogs {
     properties_dialog: PropertiesDialog,
     export_dialog: ExportDialog,
+    generator_dialog: GeneratorDialog,
+    import_sample_dialog: ImportSampleDialog,
 }
 
 impl Dialogs {
-    fn new() -> Self {
+    fn new(id: DataAssetId) -> Self {
         Dialogs {
             properties_dialog: PropertiesDialog::new(),
             export_dialog: ExportDialog::new(),
+            generator_dialog: GeneratorDialog::new(),
+            import_sample_dialog: ImportSampleDialog::new(id),
         }
     }
 
-    pub fn show(&mut self, wc: &mut WindowContext, _editor: &mut Editor, sfx: &mut Sfx, _sound_player: &mut SoundPlayer) {
+    pub fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, sfx: &mut Sfx, sound_player: &mut SoundPlayer) {
         if self.properties_dialog.open {
             self.properties_dialog.show(wc, sfx);
         }
         if self.export_dialog.open {
             self.export_dialog.show(wc, sfx);
         }
+        if self.generator_dialog.open && self.generator_dialog.show(wc, sfx, sound_player, editor.play_volume) {
+            editor.generate_samples(&mut self.generator_dialog, sfx);
+        }
+        if self.import_sample_dialog.open && self.import_sample_dialog.show(wc, sound_player, editor.play_volume) {
+            editor.import_samples(&mut self.import_sample_dialog, sfx);
+        }
+    }
+}
+
+struct SfxUndoData {
+    samples: Vec<i16>,
+    bits_per_sample: u16,
+    loop_start: u32,
+    loop_len: u32,
+    synth: Option<SfxSynthParams>,
+}
+
+impl SfxUndoData {
+    fn from_sfx(sfx: &Sfx) -> Self {
+        SfxUndoData {
+            samples: sfx.samples.clone(),
+            bits_per_sample: sfx.bits_per_sample,
+            loop_start: sfx.loop_start,
+            loop_len: sfx.loop_len,
+            synth: sfx.synth.clone(),
+        }
+    }
+
+    fn restore(self, sfx: &mut Sfx) {
+        sfx.len = self.samples.len() as u32;
+        sfx.samples = self.samples;
+        sfx.bits_per_sample = self.bits_per_sample;
+        sfx.loop_start = self.loop_start;
+        sfx.loop_len = self.loop_len;
+        sfx.synth = self.synth;
     }
 }
 
@@ -101,6 +150,12 @@ struct Editor {
     sfx_editor: SfxEditorWidget,
     play_volume: f32,
     play_freq: f32,
+    play_loop: bool,
+    play_id: u64,
+    crossfade_len: u32,
+    piano: PianoWidget,
+    undo_data: Option<SfxUndoData>,
+    redo_data: Option<SfxUndoData>,
 }
 
 impl Editor {
@@ -111,31 +166,109 @@ impl Editor {
             sfx_editor: SfxEditorWidget::new(),
             play_volume: 0.5,
             play_freq: 11025.0,
+            play_loop: false,
+            play_id: 0,
+            crossfade_len: 256,
+            piano: PianoWidget::new(),
+            undo_data: None,
+            redo_data: None,
         }
     }
 
-    fn import_wav(&mut self, wc: &mut WindowContext, file: SysDialogOpenFile, sfx: &mut Sfx) {
-        let result = file.read_data().and_then(|data| wav_utils::WavFile::read(&data)).and_then(|mut wav_file| {
-            if wav_file.channels.is_empty() { return Err(Error::other("WAV with no channels!?")); }
-            let samples = wav_file.channels.remove(0);
-            sfx.len = samples.len() as u32;
-            sfx.samples = samples;
-            sfx.bits_per_sample = wav_file.bits_per_sample;
-            sfx.loop_start = 0;
-            sfx.loop_len = 0;
+    fn set_undo_point(&mut self, sfx: &Sfx) {
+        self.undo_data = Some(SfxUndoData::from_sfx(sfx));
+        self.redo_data = None;
+    }
+
+    fn undo(&mut self, sfx: &mut Sfx) {
+        if let Some(undo_data) = self.undo_data.take() {
+            self.redo_data = Some(SfxUndoData::from_sfx(sfx));
+            undo_data.restore(sfx);
+            self.sfx_editor.selection = None;
+        }
+    }
+
+    fn redo(&mut self, sfx: &mut Sfx) {
+        if let Some(redo_data) = self.redo_data.take() {
+            self.undo_data = Some(SfxUndoData::from_sfx(sfx));
+            redo_data.restore(sfx);
+            self.sfx_editor.selection = None;
+        }
+    }
+
+    fn import_sample_file(&mut self, wc: &mut WindowContext, dialogs: &mut Dialogs, file: SysDialogOpenFile) {
+        let result = file.read_data().and_then(|data| iff_utils::read_sample_file(&data)).and_then(|wav_file| {
+            if wav_file.channels.is_empty() { return Err(Error::other("sample file with no channels!?")); }
+            dialogs.import_sample_dialog.set_open(wc, wav_file, file.filename(), self.play_freq as u32);
             Ok(())
         });
 
         if let Err(e) = result {
-            wc.logger.log(format!("ERROR reading WAV file from {}:", file.filename()));
+            wc.logger.log(format!("ERROR reading sample file from {}:", file.filename()));
             wc.logger.log(format!("{}", e));
-            wc.open_message_box("Error importing Sfx", "Error importing WAV file.\n\nConsult the log window for more information.");
+            wc.open_message_box("Error importing Sfx", "Error importing sample file.\n\nConsult the log window for more information.");
+        }
+    }
+
+    fn import_samples(&mut self, import_sample_dialog: &mut ImportSampleDialog, sfx: &mut Sfx) {
+        sfx.samples = std::mem::take(&mut import_sample_dialog.samples);
+        sfx.len = sfx.samples.len() as u32;
+        sfx.bits_per_sample = import_sample_dialog.bits_per_sample;
+        sfx.loop_start = import_sample_dialog.loop_start;
+        sfx.loop_len = import_sample_dialog.loop_len;
+        sfx.synth = None;
+        self.play_freq = import_sample_dialog.play_freq();
+        self.sfx_editor.reset();
+    }
+
+    fn generate_samples(&mut self, generator_dialog: &mut GeneratorDialog, sfx: &mut Sfx) {
+        self.set_undo_point(sfx);
+        sfx.samples = std::mem::take(&mut generator_dialog.samples);
+        sfx.len = sfx.samples.len() as u32;
+        sfx.bits_per_sample = generator_dialog.bits_per_sample;
+        sfx.loop_start = 0;
+        sfx.loop_len = 0;
+        sfx.synth = Some(generator_dialog.params.clone());
+        self.play_freq = generator_dialog.params.sample_rate as f32;
+        self.sfx_editor.reset();
+    }
+
+    fn play(&mut self, sound_player: &mut SoundPlayer, sfx: &Sfx, freq: f32) {
+        let (loop_start, loop_len) = if self.play_loop { (sfx.loop_start, sfx.loop_len) } else { (0, 0) };
+        self.play_id = sound_player.play_s16_loop(&sfx.samples, freq, self.play_volume, loop_start, loop_len);
+    }
+
+    fn handle_piano_events(&mut self, events: Vec<PianoEvent>, sound_player: &mut SoundPlayer, sfx: &Sfx) {
+        for event in events {
+            match event {
+                PianoEvent::NoteOn(note) => {
+                    self.play(sound_player, sfx, PianoWidget::note_freq(note, self.play_freq));
+                }
+                PianoEvent::NoteOff(_) => {
+                    // looping sounds only play while the note is held
+                    if self.play_loop && sound_player.position(self.play_id).is_some() {
+                        sound_player.stop();
+                    }
+                }
+            }
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, dialogs: &mut Dialogs, sfx: &mut Sfx, sound_player: &mut SoundPlayer) {
         if let Some(SysDialogResponse::File(file)) = wc.sys_dialogs.get_response_for(&self.import_sys_dlg_id) {
-            self.import_wav(wc, file, sfx);
+            self.import_sample_file(wc, dialogs, file);
+        }
+
+        // undo/redo before reading the loop points, so the restored ones are shown
+        if wc.is_editor_on_top(self.asset_id) {
+            let ctrl_shift_z = egui::KeyboardShortcut::new(egui::Modifiers::CTRL|egui::Modifiers::SHIFT, egui::Key::Z);
+            if ui.input_mut(|i| i.consume_shortcut(&ctrl_shift_z)) {
+                self.redo(sfx);
+            }
+            let ctrl_z = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Z);
+            if ui.input_mut(|i| i.consume_shortcut(&ctrl_z)) {
+                self.undo(sfx);
+            }
         }
 
         let mut loop_start = sfx.loop_start;
@@ -150,9 +283,12 @@ impl Editor {
                             Some(wc.egui.window),
                             self.import_sys_dlg_id.clone(),
                             "sfx",
-                            "Import WAVE file",
+                            "Import sample file",
                             &[
+                                ("Sample files (*.wav, *.aiff, *.8svx)", &["wav", "aif", "aiff", "aifc", "8svx", "iff"]),
                                 ("WAVE files (*.wav)", &["wav"]),
+                                ("AIFF files (*.aiff)", &["aif", "aiff", "aifc"]),
+                                ("IFF-8SVX files (*.8svx)", &["8svx", "iff"]),
                                 ("All files (*.*)", &["*"]),
                             ]
                         );
@@ -160,6 +296,9 @@ impl Editor {
                     if ui.add(menu_item(IMAGES.export, " Export...")).clicked() {
                         dialogs.export_dialog.set_open(wc, sfx, 22050);
                     }
+                    if ui.add(menu_item_no_image(" Generate...")).clicked() {
+                        dialogs.generator_dialog.set_open(wc, sfx, self.play_freq as u32);
+                    }
 
                     ui.separator();
 
@@ -167,6 +306,18 @@ impl Editor {
                         dialogs.properties_dialog.set_open(wc, sfx);
                     }
                 });
+                ui.menu_button("Edit", |ui| {
+                    if ui.add_enabled(self.undo_data.is_some(), menu_item(IMAGES.undo, " Undo")).clicked() {
+                        self.undo(sfx);
+                        loop_start = sfx.loop_start;
+                        loop_end = sfx.loop_start + sfx.loop_len;
+                    }
+                    if ui.add_enabled(self.redo_data.is_some(), menu_item(IMAGES.redo, " Redo")).clicked() {
+                        self.redo(sfx);
+                        loop_start = sfx.loop_start;
+                        loop_end = sfx.loop_start + sfx.loop_len;
+                    }
+                });
             });
         });
 
@@ -205,9 +356,13 @@ impl Editor {
                         ui.label("Hz");
                         ui.add_space(5.0);
                         if ui.button("▶ Play ").clicked() {
-                            sound_player.play_s16(&sfx.samples, self.play_freq, self.play_volume);
+                            self.play(sound_player, sfx, self.play_freq);
+                        }
+                        if ui.add_enabled(self.sfx_editor.play_position.is_some(), egui::Button::new("■ Stop ")).clicked() {
+                            sound_player.stop();
                         }
                     });
+                    ui.checkbox(&mut self.play_loop, "Loop");
                     ui.add(egui::Slider::new(&mut self.play_volume, 0.0..=2.0)).on_hover_ui(|ui| {
                         ui.label("Volume");
                     });
@@ -233,6 +388,26 @@ impl Editor {
                 ).on_hover_text("Set Loop Bounds").clicked() {
                     self.sfx_editor.set_tool(SfxTool::SetLoop);
                 }
+                ui.toggle_value(&mut self.sfx_editor.snap_to_zero_crossing, "Snap")
+                    .on_hover_text("Snap loop and selection to zero crossings");
+                ui.separator();
+                ui.add(egui::DragValue::new(&mut self.crossfade_len).speed(4.0).range(1..=65536));
+                if ui.add_enabled(loop_end > loop_start, egui::Button::new("Crossfade")).on_hover_text("Crossfade loop end into the loop start").clicked() {
+                    let undo_data = SfxUndoData::from_sfx(sfx);
+                    let len = sample_utils::crossfade_loop(&mut sfx.samples, loop_start, loop_end, self.crossfade_len);
+                    if len == 0 {
+                        wc.open_message_box("Loop Crossfade", "The loop needs samples before its start to crossfade.\n\nMove the loop start forward and try again.");
+                    } else {
+                        self.undo_data = Some(undo_data);
+                        self.redo_data = None;
+                    }
+                }
+                if sound_player.is_available() &&
+                    ui.add_enabled(loop_end > loop_start, egui::Button::new("▶ Seam ")).on_hover_text("Play the loop seam repeatedly").clicked() {
+                        let seam_len = (self.play_freq / 4.0) as u32;
+                        let preview = sample_utils::loop_seam_preview(&sfx.samples, loop_start, loop_end, seam_len, 4);
+                        sound_player.play_s16(&preview, self.play_freq, self.play_volume);
+                    }
             });
         });
 
@@ -246,14 +421,35 @@ impl Editor {
             });
         });
 
+        if sound_player.is_available() {
+            egui::Panel::bottom(format!("editor_panel_{}_piano", self.asset_id)).resizable(false).show(ui, |ui| {
+                ui.add_space(2.0);
+                ui.horizontal(|ui| {
+                    let events = self.piano.show(ui);
+                    self.handle_piano_events(events, sound_player, sfx);
+                    ui.vertical(|ui| {
+                        ui.label("Octave:");
+                        ui.add(egui::DragValue::new(&mut self.piano.octave).range(PianoWidget::MIN_OCTAVE..=PianoWidget::MAX_OCTAVE));
+                    });
+                }).response.on_hover_text(format!("{} plays at {} Hz", PianoWidget::note_name(0), self.play_freq));
+                ui.add_space(2.0);
+            });
+        }
+
         // body:
         egui::CentralPanel::default().show(ui, |ui| {
+            self.sfx_editor.play_position = sound_player.position(self.play_id);
+            if self.sfx_editor.play_position.is_some() {
+                ui.ctx().request_repaint();
+            }
             self.sfx_editor.show(ui, &sfx.samples, &mut loop_start, &mut loop_end, 0.0);
         });
 
         // handle keyboard
         if wc.is_editor_on_top(self.asset_id) {
             self.sfx_editor.handle_keyboard(ui, &mut sfx.samples, &mut loop_start, &mut loop_end);
+            let events = self.piano.handle_keyboard(ui);
+            self.handle_piano_events(events, sound_player, sfx);
         }
 
         sfx.loop_start = loop_start;
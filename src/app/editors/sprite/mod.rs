@@ -15,6 +15,8 @@ use crate::image::{
 use crate::data_asset::{
     DataAssetId,
     Sprite,
+    SpriteAnimation,
+    AssetList,
     GenericAsset,
 };
 
@@ -25,17 +27,28 @@ use super::{
     WindowContext,
     SysDialogResponse,
     SpriteFrameFixer,
+    ImageAssetName,
+};
+use super::dialogs::{
+    CreateColorsetDialog,
+    ColorRemapDialog,
+    ImageTransformDialog,
+    ColorAdjustDialog,
+    AdjustScope,
 };
-use super::dialogs::CreateColorsetDialog;
 use super::widgets::{
     ColorPickerWidget,
     ColorPickerResponse,
     ImagePickerWidget,
     ImageEditorWidget,
     ImageDrawingTool,
+    ImageBrushMenuAction,
     ImageDisplay,
 };
-use super::super::menu_item;
+use super::super::{
+    menu_item,
+    menu_item_no_image,
+};
 
 use properties::PropertiesDialog;
 use remove_frames::RemoveFramesDialog;
@@ -62,6 +75,14 @@ impl SpriteEditor {
         self.editor.image_editor.drop_selection(sprite);
     }
 
+    /**
+     * Must be called when animations were changed, to refresh the onion
+     * skin loops.
+     */
+    pub fn animations_changed(&mut self) {
+        self.editor.onion_skin_loops_dirty = true;
+    }
+
     fn show_footer(ui: &mut egui::Ui, wc: &WindowContext, editor: &Editor, sprite: &Sprite, base: &AssetEditorBase) {
         let margin = egui::Margin { left: 5, right: 5, top: 4, bottom: 0 };
         let bottom_frame = egui::Frame::NONE.inner_margin(margin).fill(base.footer_bg_color(wc, sprite.asset.id));
@@ -105,8 +126,12 @@ impl SpriteEditor {
         });
     }
 
-    pub fn show(&mut self, wc: &mut WindowContext, sprite: &mut Sprite) {
-        self.dialogs.show(wc, &mut self.editor, sprite);
+    pub fn show(&mut self, wc: &mut WindowContext, sprite: &mut Sprite, animations: &AssetList<SpriteAnimation>,
+                image_assets: &[ImageAssetName]) {
+        self.dialogs.show(wc, &mut self.editor, sprite, image_assets);
+        if self.editor.onion_skin_loops_dirty {
+            self.editor.update_onion_skin_loops(sprite, animations);
+        }
 
         let (min_size, default_size) = AssetEditorBase::calc_image_editor_window_size(sprite);
         self.base.show_window(wc, sprite, min_size, default_size, |ui, wc, sprite, base| {
@@ -116,6 +141,18 @@ impl SpriteEditor {
     }
 }
 
+impl SpriteEditor {
+    pub fn remap_colors(&mut self, wc: &mut WindowContext, sprite: &mut Sprite, frame: Option<u32>, color_map: &[u8; 256]) {
+        let frames = match frame {
+            Some(frame) => frame..frame+1,
+            None => 0..sprite.num_frames,
+        };
+        if self.editor.image_editor.remap_colors(sprite, frames, color_map) {
+            Editor::reload_images(wc, sprite);
+        }
+    }
+}
+
 impl SpriteFrameFixer for SpriteEditor {
     fn move_frame(&mut self, src_index: u32, dest_index: u32) {
         self.editor.image_editor.move_frame_undo_history(src_index, dest_index);
@@ -129,6 +166,9 @@ struct Dialogs {
     import_dialog: ImportDialog,
     export_dialog: ExportDialog,
     create_colorset_dialog: CreateColorsetDialog,
+    color_remap_dialog: ColorRemapDialog,
+    image_transform_dialog: ImageTransformDialog,
+    color_adjust_dialog: ColorAdjustDialog,
 }
 
 impl Dialogs {
@@ -140,6 +180,9 @@ impl Dialogs {
             import_dialog: ImportDialog::new(),
             export_dialog: ExportDialog::new(),
             create_colorset_dialog: CreateColorsetDialog::new(id),
+            color_remap_dialog: ColorRemapDialog::new(id),
+            image_transform_dialog: ImageTransformDialog::new(id),
+            color_adjust_dialog: ColorAdjustDialog::new(id),
         }
     }
 
@@ -150,7 +193,7 @@ impl Dialogs {
         }
     }
 
-    fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, sprite: &mut Sprite) {
+    fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, sprite: &mut Sprite, image_assets: &[ImageAssetName]) {
         if self.properties_dialog.open && self.properties_dialog.show(wc, sprite) {
             Editor::reload_images(wc, sprite);
             Self::ensure_valid_selected_image(editor, sprite);
@@ -173,6 +216,15 @@ impl Dialogs {
         if self.create_colorset_dialog.open && self.create_colorset_dialog.show(wc, sprite) {
             editor.color_picker.set_colorset(self.create_colorset_dialog.created_colorset_index);
         }
+        if self.color_remap_dialog.open {
+            self.color_remap_dialog.show(wc, sprite, image_assets);
+        }
+        if self.image_transform_dialog.show(wc) {
+            editor.image_editor.transform(sprite, self.image_transform_dialog.transform, editor.color_picker.state.right_color);
+        }
+        if self.color_adjust_dialog.show(wc, sprite) {
+            editor.adjust_colors(wc, sprite, self.color_adjust_dialog.scope, &self.color_adjust_dialog.color_map);
+        }
     }
 }
 
@@ -182,6 +234,9 @@ struct Editor {
     color_picker: ColorPickerWidget,
     image_picker: ImagePickerWidget,
     image_editor: ImageEditorWidget<Sprite>,
+    onion_skin_loops: Vec<((DataAssetId, usize), String)>,
+    onion_skin_loop: Option<(DataAssetId, usize)>,
+    onion_skin_loops_dirty: bool,
 }
 
 impl Editor {
@@ -192,7 +247,35 @@ impl Editor {
             color_picker: ColorPickerWidget::new(format!("editor_{}_color_picker", asset_id), colors::RED, colors::GREEN, true),
             image_picker: ImagePickerWidget::new(),
             image_editor: ImageEditorWidget::<Sprite>::new(),
+            onion_skin_loops: Vec::new(),
+            onion_skin_loop: None,
+            onion_skin_loops_dirty: true,
+        }
+    }
+
+    fn update_onion_skin_loops(&mut self, sprite: &Sprite, animations: &AssetList<SpriteAnimation>) {
+        self.onion_skin_loops_dirty = false;
+        self.onion_skin_loops.clear();
+        self.image_editor.onion_skin.loop_frames.clear();
+        let mut selected_loop_found = false;
+        for anim in animations.iter().filter(|anim| anim.sprite_id == sprite.asset.id) {
+            for (loop_index, aloop) in anim.loops.iter().enumerate() {
+                if aloop.frame_indices.is_empty() { continue; }
+                let key = (anim.asset.id, loop_index);
+                self.onion_skin_loops.push((key, format!("{}: {}", anim.asset.name, aloop.name_id)));
+                if self.onion_skin_loop == Some(key) {
+                    selected_loop_found = true;
+                    let heads = aloop.frame_indices.iter().filter_map(|f| f.head_index).map(u32::from).collect::<Vec<_>>();
+                    let feet = aloop.frame_indices.iter().filter_map(|f| f.foot_index).map(u32::from).collect::<Vec<_>>();
+                    self.image_editor.onion_skin.loop_frames = vec![heads, feet];
+                    self.image_editor.onion_skin.loop_wraps = ! aloop.dont_loop;
+                }
+            }
+        }
+        if ! selected_loop_found {
+            self.onion_skin_loop = None;
         }
+        self.onion_skin_loops.sort_by(|(_, a), (_, b)| a.cmp(b));
     }
 
     fn get_selection_rectangle(&self) -> Option<egui::Rect> {
@@ -269,6 +352,20 @@ impl Editor {
                             ]
                         );
                     }
+                    if ui.add(menu_item_no_image(" Transform...")).clicked() {
+                        dialogs.image_transform_dialog.set_open(wc, self.color_picker.state.left_color);
+                    }
+                    if ui.add(menu_item_no_image(" Adjust colors...")).clicked() {
+                        dialogs.color_adjust_dialog.set_open(wc, self.image_editor.get_selected_image(), ! self.image_editor.selection.is_empty());
+                    }
+                    if ui.add(menu_item_no_image(" Remap colors...")).clicked() {
+                        dialogs.color_remap_dialog.set_open(
+                            wc,
+                            self.image_editor.get_selected_image(),
+                            self.color_picker.state.left_color,
+                            self.color_picker.state.right_color
+                        );
+                    }
 
                     ui.separator();
 
@@ -296,7 +393,8 @@ impl Editor {
         });
     }
 
-    fn show_toolbar(&mut self, ui: &mut egui::Ui, _wc: &mut WindowContext, sprite: &mut Sprite) {
+    fn show_toolbar(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, sprite: &mut Sprite) {
+        self.image_editor.reference.update(wc, format!("sprite/{}", sprite.asset.name));
         egui::Panel::top(format!("editor_panel_{}_toolbar", self.asset_id)).show(ui, |ui| {
             ui.add_space(2.0);
             ui.horizontal(|ui| {
@@ -323,6 +421,30 @@ impl Editor {
                     self.image_editor.set_tool(ImageDrawingTool::Select);
                 }
 
+                for &tool in ImageDrawingTool::SELECTIONS.iter().chain(ImageDrawingTool::SHAPES) {
+                    if ui.add(egui::Button::new(tool.shape_icon())
+                              .selected(self.image_editor.get_tool() == tool)
+                              .frame_when_inactive(self.image_editor.get_tool() == tool)).on_hover_text(tool.shape_name()).clicked() {
+                        self.image_editor.set_tool(tool);
+                    }
+                }
+
+                ui.add_space(5.0);
+                let can_capture = ! self.image_editor.selection.is_empty();
+                if let ImageBrushMenuAction::Capture = self.image_editor.brush.show_menu(ui, can_capture) {
+                    self.image_editor.capture_brush(sprite);
+                }
+                ui.add_space(5.0);
+                let onion_skin_loop = self.onion_skin_loop;
+                self.image_editor.onion_skin.show_menu(ui, &self.onion_skin_loops, &mut self.onion_skin_loop);
+                if self.onion_skin_loop != onion_skin_loop {
+                    self.onion_skin_loops_dirty = true;
+                }
+                ui.add_space(5.0);
+                self.image_editor.symmetry.show_menu(ui);
+                ui.add_space(5.0);
+                self.image_editor.reference.show_menu(ui, wc);
+
                 ui.add_space(5.0);
                 ui.separator();
                 ui.add_space(5.0);
@@ -387,6 +509,18 @@ impl Editor {
         });
     }
 
+    fn adjust_colors(&mut self, wc: &mut WindowContext, sprite: &mut Sprite, scope: AdjustScope, color_map: &[u8; 256]) {
+        let frame = self.image_editor.get_selected_image();
+        let changed = match scope {
+            AdjustScope::Selection => self.image_editor.remap_selection_colors(sprite, color_map),
+            AdjustScope::CurrentImage => self.image_editor.remap_colors(sprite, frame..frame+1, color_map),
+            AdjustScope::AllImages => self.image_editor.remap_colors(sprite, 0..sprite.num_frames, color_map),
+        };
+        if changed {
+            Self::reload_images(wc, sprite);
+        }
+    }
+
     pub fn reload_images(wc: &mut WindowContext, asset: &impl ImageCollection) {
         asset.load_texture(wc.tex_man, wc.egui.ctx, TextureSlot::Opaque, true);
         asset.load_texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent, true);
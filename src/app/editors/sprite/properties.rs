@@ -4,6 +4,7 @@ use crate::data_asset::Sprite;
 use super::super::{
     AssetEditorBase,
     WindowContext,
+    EditorAction,
 };
 
 pub struct PropertiesDialog {
@@ -44,7 +45,10 @@ impl PropertiesDialog {
         wc.set_dialog_open(Self::id(), self.open);
     }
 
-    fn confirm(&mut self, sprite: &mut Sprite) {
+    fn confirm(&mut self, wc: &mut WindowContext, sprite: &mut Sprite) {
+        if sprite.asset.name != self.name {
+            wc.add_editor_action(EditorAction::ImageAssetRenamed);
+        }
         sprite.asset.name.clear();
         sprite.asset.name.push_str(&self.name);
 
@@ -60,7 +64,7 @@ impl PropertiesDialog {
     pub fn show(&mut self, wc: &mut WindowContext, sprite: &mut Sprite) -> bool {
         if ! self.open { return false; }
 
-        if AssetEditorBase::show_dialog_window(wc, Self::id(), 300.0, "Sprite Properties", |ui, _wc| {
+        if AssetEditorBase::show_dialog_window(wc, Self::id(), 300.0, "Sprite Properties", |ui, wc| {
             egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
                 egui::Grid::new(format!("editor_panel_{}_prop_grid", sprite.asset.id))
                     .num_columns(2)
@@ -89,7 +93,7 @@ impl PropertiesDialog {
                     ui.close();
                 }
                 if ui.button("Ok").clicked() {
-                    self.confirm(sprite);
+                    self.confirm(wc, sprite);
                     ui.close();
                 }
             });
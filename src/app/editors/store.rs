@@ -9,8 +9,10 @@ use crate::data_asset::{
     DataAssetStore,
     AssetList,
     Room,
+    GenericAsset,
 };
 use super::{
+    ImageAssetName,
     AssetEditorBase,
     TilesetEditor,
     MapDataEditor,
@@ -39,6 +41,8 @@ pub struct EditorStore {
     pub fonts: HashMap<DataAssetId, FontEditor>,
     pub prop_fonts: HashMap<DataAssetId, PropFontEditor>,
     pub room_names: HashMap<DataAssetId, String>,
+    pub image_asset_names: Vec<ImageAssetName>,
+    image_asset_names_dirty: bool,
 }
 
 impl EditorStore {
@@ -57,6 +61,8 @@ impl EditorStore {
             fonts: HashMap::new(),
             prop_fonts: HashMap::new(),
             room_names: HashMap::new(),
+            image_asset_names: Vec::new(),
+            image_asset_names_dirty: true,
         }
     }
 
@@ -74,6 +80,8 @@ impl EditorStore {
         self.fonts.clear();
         self.prop_fonts.clear();
         self.room_names.clear();
+        self.image_asset_names.clear();
+        self.image_asset_names_dirty = true;
     }
 
     pub fn create_editors_for_new_store(&mut self, store: &DataAssetStore) {
@@ -122,13 +130,22 @@ impl EditorStore {
     }
 
     pub fn remove_editor(&mut self, id: DataAssetId) -> bool {
+        self.image_asset_names_dirty = true;
         if let Some(editor) = self.tilesets.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
-        if let Some(editor) = self.maps.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
+        if let Some(editor) = self.maps.remove(&id) {
+            self.egui_id_to_asset_id.remove(&editor.base.egui_id);
+            for tileset_editor in self.tilesets.values_mut() { tileset_editor.maps_changed(); }
+            return true;
+        }
         if let Some(editor) = self.rooms.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
         if let Some(editor) = self.worlds.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
         if let Some(editor) = self.sprites.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
         if let Some(editor) = self.pal_sprites.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
-        if let Some(editor) = self.animations.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
+        if let Some(editor) = self.animations.remove(&id) {
+            self.egui_id_to_asset_id.remove(&editor.base.egui_id);
+            for sprite_editor in self.sprites.values_mut() { sprite_editor.animations_changed(); }
+            return true;
+        }
         if let Some(editor) = self.sfxs.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
         if let Some(editor) = self.mods.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
         if let Some(editor) = self.fonts.remove(&id) { self.egui_id_to_asset_id.remove(&editor.base.egui_id); return true; }
@@ -157,6 +174,7 @@ impl EditorStore {
         let egui_id = editor.base.egui_id;
         self.egui_id_to_asset_id.insert(egui_id, editor.base.id);
         self.tilesets.insert(id, editor);
+        self.image_asset_names_dirty = true;
         egui_id
     }
 
@@ -189,6 +207,7 @@ impl EditorStore {
         let egui_id = editor.base.egui_id;
         self.egui_id_to_asset_id.insert(egui_id, editor.base.id);
         self.sprites.insert(id, editor);
+        self.image_asset_names_dirty = true;
         egui_id
     }
 
@@ -229,6 +248,7 @@ impl EditorStore {
         let egui_id = editor.base.egui_id;
         self.egui_id_to_asset_id.insert(egui_id, editor.base.id);
         self.fonts.insert(id, editor);
+        self.image_asset_names_dirty = true;
         egui_id
     }
 
@@ -241,11 +261,15 @@ impl EditorStore {
     }
 
     pub fn update_dirty_flags(&mut self, store: &DataAssetStore) {
-        for tileset in store.assets.tilesets.iter() {
-            if let Some(editor) = self.tilesets.get_mut(&tileset.asset.id) { editor.base.update_dirty_flag(tileset); }
-        }
+        let mut maps_changed = false;
         for map in store.assets.maps.iter() {
-            if let Some(editor) = self.maps.get_mut(&map.asset.id) { editor.base.update_dirty_flag(map); }
+            if let Some(editor) = self.maps.get_mut(&map.asset.id) && editor.base.update_dirty_flag(map) { maps_changed = true; }
+        }
+        for tileset in store.assets.tilesets.iter() {
+            if let Some(editor) = self.tilesets.get_mut(&tileset.asset.id) {
+                editor.base.update_dirty_flag(tileset);
+                if maps_changed { editor.maps_changed(); }
+            }
         }
         for room in store.assets.rooms.iter() {
             if let Some(editor) = self.rooms.get_mut(&room.asset.id) { editor.base.update_dirty_flag(room); }
@@ -259,8 +283,12 @@ impl EditorStore {
         for pal_sprite in store.assets.pal_sprites.iter() {
             if let Some(editor) = self.pal_sprites.get_mut(&pal_sprite.asset.id) { editor.base.update_dirty_flag(pal_sprite); }
         }
+        let mut animations_changed = false;
         for anim in store.assets.animations.iter() {
-            if let Some(editor) = self.animations.get_mut(&anim.asset.id) { editor.base.update_dirty_flag(anim); }
+            if let Some(editor) = self.animations.get_mut(&anim.asset.id) && editor.base.update_dirty_flag(anim) { animations_changed = true; }
+        }
+        if animations_changed {
+            for editor in self.sprites.values_mut() { editor.animations_changed(); }
         }
         for sfx in store.assets.sfxs.iter() {
             if let Some(editor) = self.sfxs.get_mut(&sfx.asset.id) { editor.base.update_dirty_flag(sfx); }
@@ -377,4 +405,33 @@ impl EditorStore {
             self.room_names.remove(room_id);
         }
     }
+
+    /**
+     * Mark the list of image asset names for refresh (must be called
+     * when an asset is renamed).
+     */
+    pub fn set_image_asset_names_dirty(&mut self) {
+        self.image_asset_names_dirty = true;
+    }
+
+    /**
+     * Refresh the list of names of assets that can have their colors
+     * remapped (tilesets, sprites and fonts) if assets were added,
+     * removed or renamed.
+     */
+    pub fn refresh_image_asset_names(&mut self, store: &DataAssetStore) {
+        if ! self.image_asset_names_dirty { return; }
+        self.image_asset_names_dirty = false;
+        self.image_asset_names.clear();
+        let tilesets = store.asset_ids.tilesets.iter().filter_map(|id| store.assets.tilesets.get(id)).map(|a| a.asset());
+        let sprites = store.asset_ids.sprites.iter().filter_map(|id| store.assets.sprites.get(id)).map(|a| a.asset());
+        let fonts = store.asset_ids.fonts.iter().filter_map(|id| store.assets.fonts.get(id)).map(|a| a.asset());
+        for asset in tilesets.chain(sprites).chain(fonts) {
+            self.image_asset_names.push(ImageAssetName {
+                id: asset.id,
+                asset_type: asset.asset_type,
+                name: asset.name.clone(),
+            });
+        }
+    }
 }
@@ -0,0 +1,205 @@
+use crate::image::{
+    ImageCollection,
+    TextureSlot,
+};
+use crate::data_asset::{
+    DataAssetId,
+    Tileset,
+    TileAnimation,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+};
+
+const FRAME_TILE_SIZE: f32 = 32.0;
+const PICKER_TILE_SIZE: f32 = 24.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum PickTarget {
+    AnimatedTile,
+    NewFrame,
+}
+
+pub struct AnimationsDialog {
+    pub open: bool,
+    window_id: egui::Id,
+    anim_combo_id: egui::Id,
+    animations: Vec<TileAnimation>,
+    selected_anim: usize,
+    pick_target: PickTarget,
+}
+
+impl AnimationsDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        AnimationsDialog {
+            window_id: egui::Id::new(format!("editor_{}_animations", id)),
+            anim_combo_id: egui::Id::new(format!("editor_{}_animations_combo", id)),
+            open: false,
+            animations: Vec::new(),
+            selected_anim: 0,
+            pick_target: PickTarget::NewFrame,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, tileset: &Tileset) {
+        self.animations = tileset.animations.clone();
+        self.selected_anim = self.selected_anim.min(self.animations.len().saturating_sub(1));
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn paint_tile(ui: &egui::Ui, wc: &mut WindowContext, tileset: &Tileset, tile: u8, rect: egui::Rect) {
+        if tile as u32 >= tileset.num_tiles {
+            ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, "-", egui::FontId::monospace(12.0), ui.visuals().text_color());
+            return;
+        }
+        let texture = tileset.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Opaque);
+        let size = egui::Vec2::splat(Tileset::TILE_SIZE as f32);
+        egui::Image::from_texture((texture.id(), size)).uv(tileset.get_item_uv(tile as u32)).paint_at(ui, rect);
+    }
+
+    fn show_anim_selector(&mut self, ui: &mut egui::Ui, tileset: &Tileset) {
+        ui.horizontal(|ui| {
+            ui.label("Animation:");
+            let selected_text = match self.animations.get(self.selected_anim) {
+                Some(anim) => format!("tile {}", anim.tile),
+                None => "-".to_owned(),
+            };
+            egui::ComboBox::from_id_salt(self.anim_combo_id)
+                .selected_text(selected_text)
+                .width(150.0)
+                .show_ui(ui, |ui| {
+                    for (index, anim) in self.animations.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected_anim, index, format!("tile {}", anim.tile));
+                    }
+                });
+            let free_tile = (0..tileset.num_tiles.min(255) as u8).find(|&tile| ! self.animations.iter().any(|anim| anim.tile == tile));
+            if ui.add_enabled(free_tile.is_some(), egui::Button::new("Add")).clicked() && let Some(tile) = free_tile {
+                self.animations.push(TileAnimation::new(tile));
+                self.selected_anim = self.animations.len() - 1;
+                self.pick_target = PickTarget::AnimatedTile;
+            }
+            if ui.add_enabled(! self.animations.is_empty(), egui::Button::new("Remove")).clicked() {
+                self.animations.remove(self.selected_anim);
+                self.selected_anim = self.selected_anim.min(self.animations.len().saturating_sub(1));
+            }
+        });
+    }
+
+    fn show_frames(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        let Some(anim) = self.animations.get_mut(self.selected_anim) else { return; };
+        let mut remove_frame = None;
+        ui.horizontal_wrapped(|ui| {
+            for (index, (tile, duration)) in anim.frame_tiles.iter().zip(anim.frame_durations.iter_mut()).enumerate() {
+                ui.vertical(|ui| {
+                    let (resp, _) = ui.allocate_painter(egui::Vec2::splat(FRAME_TILE_SIZE), egui::Sense::click());
+                    Self::paint_tile(ui, wc, tileset, *tile, resp.rect);
+                    if resp.on_hover_text(format!("tile {} (right click to remove)", tile)).secondary_clicked() {
+                        remove_frame = Some(index);
+                    }
+                    ui.add(egui::DragValue::new(duration).range(1..=10000).speed(10.0).suffix(" ms"));
+                });
+            }
+        });
+        if let Some(index) = remove_frame && anim.frame_tiles.len() > 1 {
+            anim.frame_tiles.remove(index);
+            anim.frame_durations.remove(index);
+        }
+    }
+
+    fn show_preview(&self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        let Some(anim) = self.animations.get(self.selected_anim) else { return; };
+        ui.horizontal(|ui| {
+            ui.label("Preview:");
+            let time = (ui.input(|i| i.time) * 1000.0) as u64;
+            let (resp, _) = ui.allocate_painter(egui::Vec2::splat(FRAME_TILE_SIZE), egui::Sense::hover());
+            Self::paint_tile(ui, wc, tileset, anim.tile_at(time), resp.rect);
+            ui.label(format!("{} ms per cycle", anim.total_duration()));
+        });
+        ui.ctx().request_repaint();
+    }
+
+    fn show_tile_picker(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        let Some(selected_anim) = self.animations.get(self.selected_anim).map(|anim| anim.tile) else { return; };
+        egui::ScrollArea::vertical().id_salt("animation_tile_picker").max_height(120.0).show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing = egui::Vec2::splat(2.0);
+                for tile in 0..tileset.num_tiles.min(255) {
+                    let tile = tile as u8;
+                    let (resp, _) = ui.allocate_painter(egui::Vec2::splat(PICKER_TILE_SIZE), egui::Sense::click());
+                    Self::paint_tile(ui, wc, tileset, tile, resp.rect);
+                    let used = self.animations.iter().any(|anim| anim.tile == tile && anim.tile != selected_anim);
+                    if resp.clicked() {
+                        match self.pick_target {
+                            PickTarget::AnimatedTile if ! used => {
+                                if let Some(anim) = self.animations.get_mut(self.selected_anim) { anim.tile = tile; }
+                            }
+                            PickTarget::NewFrame => {
+                                if let Some(anim) = self.animations.get_mut(self.selected_anim) {
+                                    anim.frame_tiles.push(tile);
+                                    anim.frame_durations.push(TileAnimation::DEFAULT_DURATION);
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    let hover = if used { format!("tile {} (already animated)", tile) } else { format!("tile {}", tile) };
+                    resp.on_hover_text(hover);
+                }
+            });
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, tileset: &mut Tileset) {
+        if ! self.open { return; }
+
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 500.0, "Tile Animations", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                    self.show_anim_selector(ui, tileset);
+
+                    if let Some(anim) = self.animations.get(self.selected_anim) {
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            ui.label("Animated tile:");
+                            let (resp, _) = ui.allocate_painter(egui::Vec2::splat(FRAME_TILE_SIZE), egui::Sense::hover());
+                            Self::paint_tile(ui, wc, tileset, anim.tile, resp.rect);
+                        });
+
+                        ui.add_space(8.0);
+                        ui.label("Frames (right click to remove):");
+                        egui::ScrollArea::vertical().id_salt("animation_frames").max_height(200.0).show(ui, |ui| {
+                            self.show_frames(ui, wc, tileset);
+                        });
+
+                        ui.add_space(8.0);
+                        self.show_preview(ui, wc, tileset);
+
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            ui.label("Click a tile to:");
+                            ui.radio_value(&mut self.pick_target, PickTarget::NewFrame, "add a frame");
+                            ui.radio_value(&mut self.pick_target, PickTarget::AnimatedTile, "set the animated tile");
+                        });
+                        self.show_tile_picker(ui, wc, tileset);
+                    }
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    tileset.animations = std::mem::take(&mut self.animations);
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::data_asset::{
+    DataAssetId,
+    Tileset,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    EditorAction,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum CompactOrder {
+    Current,
+    Usage,
+}
+
+impl CompactOrder {
+    pub fn text(&self) -> &'static str {
+        match self {
+            CompactOrder::Current => "keep current order",
+            CompactOrder::Usage => "most used first",
+        }
+    }
+}
+
+pub struct CompactTilesetDialog {
+    pub open: bool,
+    window_id: egui::Id,
+    order_combo_id: egui::Id,
+    remove_unused: bool,
+    order: CompactOrder,
+}
+
+impl CompactTilesetDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        CompactTilesetDialog {
+            window_id: egui::Id::new(format!("editor_{}_compact", id)),
+            order_combo_id: egui::Id::new(format!("editor_{}_compact_order_combo", id)),
+            open: false,
+            remove_unused: true,
+            order: CompactOrder::Current,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext) {
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    /**
+     * Return the old index of each tile in the new order.
+     */
+    fn calc_order(&self, usage: &[u32]) -> Vec<u8> {
+        let mut order = (0..usage.len())
+            .filter(|&tile| ! self.remove_unused || usage[tile] != 0)
+            .map(|tile| tile as u8)
+            .collect::<Vec<_>>();
+        if self.order == CompactOrder::Usage {
+            order.sort_by_key(|&tile| std::cmp::Reverse(usage[tile as usize]));
+        }
+        order
+    }
+
+    fn confirm(&mut self, wc: &mut WindowContext, tileset: &Tileset, usage: &[u32]) {
+        let order = self.calc_order(usage);
+        if order.is_empty() {
+            wc.open_message_box("Compact Tileset", "No tile of the tileset is used in any map.");
+            return;
+        }
+        wc.add_editor_action(EditorAction::ReorderTilesetTiles {
+            tileset_id: tileset.asset.id,
+            order,
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, tileset: &Tileset, usage: &[u32]) {
+        if ! self.open { return; }
+
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 350.0, "Compact Tileset", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                    let num_unused = usage.iter().filter(|&&count| count == 0).count();
+                    let tile_bytes = 4 * tileset.width.div_ceil(4) * tileset.height;
+                    ui.label(format!("{} of {} tiles are not used in any map.", num_unused, tileset.num_tiles));
+                    ui.add_space(8.0);
+
+                    ui.checkbox(&mut self.remove_unused, format!("Remove unused tiles (saves {} bytes)", num_unused as u32 * tile_bytes));
+                    ui.horizontal(|ui| {
+                        ui.label("Tile order:");
+                        egui::ComboBox::from_id_salt(self.order_combo_id)
+                            .selected_text(self.order.text())
+                            .width(150.0)
+                            .show_ui(ui, |ui| {
+                                for order in [CompactOrder::Current, CompactOrder::Usage] {
+                                    ui.selectable_value(&mut self.order, order, order.text());
+                                }
+                            });
+                    });
+                    ui.add_space(8.0);
+                    ui.label("All maps using the tileset will be updated.");
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    self.confirm(wc, tileset, usage);
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+    }
+}
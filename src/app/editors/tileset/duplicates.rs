@@ -0,0 +1,116 @@
+use crate::image::{
+    ImageCollection,
+    TextureSlot,
+};
+use crate::data_asset::{
+    DataAssetId,
+    Tileset,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    EditorAction,
+    TileDuplicate,
+    TileDuplicateKind,
+    find_duplicate_tiles,
+};
+
+const PREVIEW_TILE_SIZE: f32 = 32.0;
+
+pub struct DuplicateTilesDialog {
+    pub open: bool,
+    window_id: egui::Id,
+    include_flipped: bool,
+    duplicates: Vec<TileDuplicate>,
+}
+
+impl DuplicateTilesDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        DuplicateTilesDialog {
+            window_id: egui::Id::new(format!("editor_{}_duplicate_tiles", id)),
+            open: false,
+            include_flipped: false,
+            duplicates: Vec::new(),
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, tileset: &Tileset) {
+        self.duplicates = find_duplicate_tiles(tileset, self.include_flipped);
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn num_exact(&self) -> usize {
+        self.duplicates.iter().filter(|dup| dup.kind == TileDuplicateKind::Exact).count()
+    }
+
+    fn paint_tile(ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset, tile: u8) {
+        let (resp, _) = ui.allocate_painter(egui::Vec2::splat(PREVIEW_TILE_SIZE), egui::Sense::hover());
+        let texture = tileset.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Opaque);
+        let size = egui::Vec2::splat(Tileset::TILE_SIZE as f32);
+        egui::Image::from_texture((texture.id(), size)).uv(tileset.get_item_uv(tile as u32)).paint_at(ui, resp.rect);
+    }
+
+    fn confirm(&mut self, wc: &mut WindowContext, tileset: &Tileset) {
+        let merges = self.duplicates.iter()
+            .filter(|dup| dup.kind == TileDuplicateKind::Exact)
+            .map(|dup| (dup.tile, dup.original))
+            .collect::<Vec<_>>();
+        if merges.is_empty() { return; }
+        wc.add_editor_action(EditorAction::MergeTilesetTiles {
+            tileset_id: tileset.asset.id,
+            merges,
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, tileset: &Tileset) {
+        if ! self.open { return; }
+
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 450.0, "Duplicate Tiles", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                    if ui.checkbox(&mut self.include_flipped, "Also find flipped tiles").changed() {
+                        self.duplicates = find_duplicate_tiles(tileset, self.include_flipped);
+                    }
+                    ui.add_space(8.0);
+
+                    if self.duplicates.is_empty() {
+                        ui.label("No duplicate tiles found.");
+                        return;
+                    }
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for dup in &self.duplicates {
+                            ui.horizontal(|ui| {
+                                Self::paint_tile(ui, wc, tileset, dup.tile);
+                                Self::paint_tile(ui, wc, tileset, dup.original);
+                                ui.label(format!("tile {} is {} tile {}", dup.tile, dup.kind.text(), dup.original));
+                            });
+                        }
+                    });
+
+                    ui.add_space(8.0);
+                    let num_exact = self.num_exact();
+                    let tile_bytes = 4 * tileset.width.div_ceil(4) * tileset.height;
+                    ui.label(format!("Merging {} duplicate tiles will save {} bytes.", num_exact, num_exact as u32 * tile_bytes));
+                    if num_exact < self.duplicates.len() {
+                        ui.label("Flipped tiles are only reported: map tiles can't be flipped, so they can't be merged.");
+                    }
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Close").clicked() {
+                    ui.close();
+                }
+                if ui.add_enabled(self.num_exact() > 0, egui::Button::new("Merge")).clicked() {
+                    self.confirm(wc, tileset);
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+    }
+}
@@ -3,6 +3,10 @@ mod add_tiles;
 mod remove_tiles;
 mod import;
 mod export;
+mod terrains;
+mod duplicates;
+mod compact;
+mod animations;
 
 use core::fmt::NumBuffer;
 
@@ -12,11 +16,14 @@ use crate::image::{
     ImageCollection,
     ImagePixels,
     ImageRotation,
+    ImageTransform,
 };
 use crate::data_asset::{
     DataAssetId,
     GenericAsset,
     Tileset,
+    MapData,
+    AssetList,
 };
 
 use super::{
@@ -28,8 +35,19 @@ use super::{
     TileGrid,
     TileGridImage,
     MapTileFixer,
+    ImageAssetName,
+    MapClipboardData,
+    TilesetMergeUndo,
+    EditorAction,
+    count_tileset_tile_usage,
+};
+use super::dialogs::{
+    CreateColorsetDialog,
+    ColorRemapDialog,
+    ImageTransformDialog,
+    ColorAdjustDialog,
+    AdjustScope,
 };
-use super::dialogs::CreateColorsetDialog;
 use super::widgets::{
     ColorPickerWidget,
     ColorPickerResponse,
@@ -39,15 +57,23 @@ use super::widgets::{
     TileGridEditorAction,
     ImageEditorAction,
     ImageDrawingTool,
+    ImageBrushMenuAction,
     ImageDisplay,
 };
-use super::super::menu_item;
+use super::super::{
+    menu_item,
+    menu_item_no_image,
+};
 
 use properties::PropertiesDialog;
 use remove_tiles::RemoveTilesDialog;
 use add_tiles::{AddTilesDialog, AddTilesAction};
 use export::ExportDialog;
 use import::ImportDialog;
+use terrains::TerrainsDialog;
+use duplicates::DuplicateTilesDialog;
+use compact::CompactTilesetDialog;
+use animations::AnimationsDialog;
 
 enum EditorTab {
     Tile,
@@ -74,6 +100,35 @@ impl TilesetEditor {
         self.editor.prepare_for_saving(tileset);
     }
 
+    /**
+     * Must be called when the tiles were changed outside the editor.
+     */
+    pub fn tiles_changed(&mut self, tileset: &Tileset) {
+        self.dialogs.ensure_valid_selected_image(&mut self.editor, tileset);
+        self.editor.tile_image_editor.set_image_changed();
+    }
+
+    pub fn tiles_reordered(&mut self, tileset: &Tileset) {
+        self.editor.tile_image_editor.clear_undo_history();
+        self.tiles_changed(tileset);
+    }
+
+    pub fn set_merge_undo(&mut self, tileset: &Tileset, undo: TilesetMergeUndo) {
+        self.editor.merge_undo = Some(undo);
+        self.tiles_changed(tileset);
+    }
+
+    pub fn take_merge_undo(&mut self) -> Option<TilesetMergeUndo> {
+        self.editor.merge_undo.take()
+    }
+
+    /**
+     * Must be called when maps were changed, to recount the tile usage.
+     */
+    pub fn maps_changed(&mut self) {
+        self.editor.tile_usage = None;
+    }
+
     fn get_tile_name(tile: Option<u32>, buf: &mut NumBuffer<u32>) -> &str {
         if let Some(tile) = tile {
             tile.format_into(buf)
@@ -131,19 +186,31 @@ impl TilesetEditor {
         });
     }
 
-    pub fn show(&mut self, wc: &mut WindowContext, tileset: &mut Tileset) {
-        self.dialogs.show(wc, &mut self.editor, tileset);
+    pub fn show(&mut self, wc: &mut WindowContext, tileset: &mut Tileset, image_assets: &[ImageAssetName], maps: &AssetList<MapData>) {
+        self.dialogs.show(wc, &mut self.editor, tileset, image_assets, maps);
 
         let (min_size, default_size) = AssetEditorBase::calc_image_editor_window_size(tileset);
         let min_size = min_size.max(egui::Vec2::new(500.0, 400.0));
         let default_size = default_size.max(egui::Vec2::new(500.0, 400.0));
         self.base.show_window(wc, tileset, min_size, default_size, |ui, wc, tileset, base| {
             Self::show_footer(ui, wc, &self.editor, base, tileset);
-            self.editor.show(ui, wc, &mut self.dialogs, tileset);
+            self.editor.show(ui, wc, &mut self.dialogs, tileset, maps);
         });
     }
 }
 
+impl TilesetEditor {
+    pub fn remap_colors(&mut self, wc: &mut WindowContext, tileset: &mut Tileset, frame: Option<u32>, color_map: &[u8; 256]) {
+        let frames = match frame {
+            Some(frame) => frame..frame+1,
+            None => 0..tileset.num_tiles,
+        };
+        if self.editor.tile_image_editor.remap_colors(tileset, frames, color_map) {
+            ImageEditorWidget::<Tileset>::update_texture(wc, tileset);
+        }
+    }
+}
+
 impl MapTileFixer for TilesetEditor {
     fn get_tile_planes_mut(&mut self) -> Vec<&mut [u8]> {
         self.editor.tile_grid.get_tile_planes_mut()
@@ -161,6 +228,13 @@ struct Dialogs {
     import_dialog: ImportDialog,
     export_dialog: ExportDialog,
     create_colorset_dialog: CreateColorsetDialog,
+    color_remap_dialog: ColorRemapDialog,
+    image_transform_dialog: ImageTransformDialog,
+    color_adjust_dialog: ColorAdjustDialog,
+    terrains_dialog: TerrainsDialog,
+    duplicates_dialog: DuplicateTilesDialog,
+    compact_dialog: CompactTilesetDialog,
+    animations_dialog: AnimationsDialog,
 }
 
 impl Dialogs {
@@ -172,6 +246,13 @@ impl Dialogs {
             import_dialog: ImportDialog::new(),
             export_dialog: ExportDialog::new(),
             create_colorset_dialog: CreateColorsetDialog::new(id),
+            color_remap_dialog: ColorRemapDialog::new(id),
+            image_transform_dialog: ImageTransformDialog::new(id),
+            color_adjust_dialog: ColorAdjustDialog::new(id),
+            terrains_dialog: TerrainsDialog::new(id),
+            duplicates_dialog: DuplicateTilesDialog::new(id),
+            compact_dialog: CompactTilesetDialog::new(id),
+            animations_dialog: AnimationsDialog::new(id),
        }
     }
 
@@ -182,7 +263,8 @@ impl Dialogs {
         }
     }
 
-    fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, tileset: &mut Tileset) {
+    fn show(&mut self, wc: &mut WindowContext, editor: &mut Editor, tileset: &mut Tileset, image_assets: &[ImageAssetName],
+            maps: &AssetList<MapData>) {
         if self.properties_dialog.open && self.properties_dialog.show(wc, tileset) {
             self.ensure_valid_selected_image(editor, tileset);
             editor.tile_image_editor.set_image_changed();
@@ -206,6 +288,28 @@ impl Dialogs {
         if self.create_colorset_dialog.open && self.create_colorset_dialog.show(wc, tileset) {
             editor.color_picker.set_colorset(self.create_colorset_dialog.created_colorset_index);
         }
+        if self.color_remap_dialog.open {
+            self.color_remap_dialog.show(wc, tileset, image_assets);
+        }
+        if self.image_transform_dialog.show(wc) {
+            editor.transform(wc, tileset, self.image_transform_dialog.transform);
+        }
+        if self.color_adjust_dialog.open {
+            let confirmed = match editor.selected_tab {
+                EditorTab::GridTiles => self.color_adjust_dialog.show(wc, editor.tile_grid.get_image(tileset)),
+                _ => self.color_adjust_dialog.show(wc, tileset),
+            };
+            if confirmed {
+                editor.adjust_colors(wc, tileset, self.color_adjust_dialog.scope, &self.color_adjust_dialog.color_map);
+            }
+        }
+        self.terrains_dialog.show(wc, tileset);
+        self.duplicates_dialog.show(wc, tileset);
+        if self.compact_dialog.open {
+            Editor::update_tile_usage(&mut editor.tile_usage, tileset, maps);
+            self.compact_dialog.show(wc, tileset, editor.tile_usage.as_deref().unwrap_or_default());
+        }
+        self.animations_dialog.show(wc, tileset);
     }
 }
 
@@ -221,6 +325,11 @@ struct Editor {
     grid_image_editor: ImageEditorWidget<TileGridImage>,
     tile_grid_editor: TileGridEditorWidget,
     tile_grid: TileGrid,
+    wrap_preview: bool,
+    wrap_preview_repeat: u32,
+    merge_undo: Option<TilesetMergeUndo>,
+    show_usage: bool,
+    tile_usage: Option<Vec<u32>>,
 }
 
 impl Editor {
@@ -240,6 +349,11 @@ impl Editor {
             grid_image_editor: ImageEditorWidget::new(),
             tile_grid_editor: TileGridEditorWidget::new(),
             tile_grid: TileGrid::new(asset_id),
+            wrap_preview: false,
+            wrap_preview_repeat: 3,
+            merge_undo: None,
+            show_usage: false,
+            tile_usage: None,
         }
     }
 
@@ -284,6 +398,41 @@ impl Editor {
         }
     }
 
+    fn show_brush_menu(&mut self, ui: &mut egui::Ui, tileset: &mut Tileset) {
+        let can_capture = match self.selected_tab {
+            EditorTab::Tile => ! self.tile_image_editor.selection.is_empty(),
+            EditorTab::GridTiles => ! self.grid_image_editor.selection.is_empty(),
+            EditorTab::Grid => false,
+        };
+        match self.tile_image_editor.brush.show_menu(ui, can_capture) {
+            ImageBrushMenuAction::Changed => {
+                self.grid_image_editor.brush = self.tile_image_editor.brush.clone();
+            }
+            ImageBrushMenuAction::Capture => match self.selected_tab {
+                EditorTab::Tile => {
+                    self.tile_image_editor.capture_brush(tileset);
+                    self.grid_image_editor.brush = self.tile_image_editor.brush.clone();
+                    self.grid_image_editor.set_tool(ImageDrawingTool::Pencil);
+                }
+                EditorTab::GridTiles => {
+                    let image = self.tile_grid.get_image_mut(tileset);
+                    self.grid_image_editor.capture_brush(image);
+                    self.tile_image_editor.brush = self.grid_image_editor.brush.clone();
+                    self.tile_image_editor.set_tool(ImageDrawingTool::Pencil);
+                }
+                EditorTab::Grid => {}
+            }
+            ImageBrushMenuAction::None => {}
+        }
+        ui.add_space(5.0);
+        if self.tile_image_editor.symmetry.show_menu(ui) {
+            self.grid_image_editor.symmetry.mode = self.tile_image_editor.symmetry.mode;
+            if self.tile_image_editor.symmetry.is_axis_centered() {
+                self.grid_image_editor.symmetry.center_axis();
+            }
+        }
+    }
+
     fn vflip(&mut self, wc: &mut WindowContext, tileset: &mut Tileset) {
         match self.selected_tab {
             EditorTab::Tile => {
@@ -326,6 +475,54 @@ impl Editor {
         }
     }
 
+    fn transform(&mut self, wc: &mut WindowContext, tileset: &mut Tileset, transform: ImageTransform) {
+        match self.selected_tab {
+            EditorTab::Tile => {
+                self.tile_image_editor.transform(tileset, transform, self.color_picker.state.right_color);
+            }
+            EditorTab::GridTiles => {
+                let image = self.tile_grid.get_image_mut(tileset);
+                self.grid_image_editor.transform(image, transform, self.color_picker.state.right_color);
+                self.handle_grid_image_changed(wc, tileset);
+            }
+            _ => {}
+        }
+    }
+
+    fn adjust_colors(&mut self, wc: &mut WindowContext, tileset: &mut Tileset, scope: AdjustScope, color_map: &[u8; 256]) {
+        match scope {
+            AdjustScope::Selection => match self.selected_tab {
+                EditorTab::Tile => {
+                    self.tile_image_editor.remap_selection_colors(tileset, color_map);
+                }
+                EditorTab::GridTiles => {
+                    let image = self.tile_grid.get_image_mut(tileset);
+                    self.grid_image_editor.remap_selection_colors(image, color_map);
+                    self.handle_grid_image_changed(wc, tileset);
+                }
+                _ => {}
+            }
+            AdjustScope::CurrentImage | AdjustScope::AllImages => match self.selected_tab {
+                EditorTab::Tile => {
+                    let tile = self.tile_image_editor.get_selected_image();
+                    let tiles = if scope == AdjustScope::CurrentImage { tile..tile+1 } else { 0..tileset.num_tiles };
+                    if self.tile_image_editor.remap_colors(tileset, tiles, color_map) {
+                        ImageEditorWidget::<Tileset>::update_texture(wc, tileset);
+                    }
+                }
+                EditorTab::GridTiles => {
+                    let image = self.tile_grid.get_image_mut(tileset);
+                    let item = self.grid_image_editor.get_selected_image();
+                    let items = if scope == AdjustScope::CurrentImage { item..item+1 } else { 0..image.num_items() };
+                    if self.grid_image_editor.remap_colors(image, items, color_map) {
+                        self.handle_grid_image_changed(wc, tileset);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
     fn can_undo(&self) -> bool {
         match self.selected_tab {
             EditorTab::Tile => { self.tile_image_editor.can_undo() }
@@ -471,6 +668,22 @@ impl Editor {
 
                     ui.separator();
 
+                    if ui.add(menu_item_no_image(" Terrains...")).clicked() {
+                        dialogs.terrains_dialog.set_open(wc, tileset);
+                    }
+                    if ui.add(menu_item_no_image(" Tile animations...")).clicked() {
+                        dialogs.animations_dialog.set_open(wc, tileset);
+                    }
+                    if ui.add(menu_item_no_image(" Find duplicate tiles...")).clicked() {
+                        dialogs.duplicates_dialog.set_open(wc, tileset);
+                    }
+                    let can_undo_merge = self.merge_undo.as_ref().is_some_and(|undo| undo.can_undo(tileset));
+                    if ui.add_enabled(can_undo_merge, menu_item(IMAGES.undo, " Undo tile merge")).clicked() {
+                        wc.add_editor_action(EditorAction::UndoMergeTilesetTiles { tileset_id: tileset.asset.id });
+                    }
+                    if ui.add(menu_item_no_image(" Compact tileset...")).clicked() {
+                        dialogs.compact_dialog.set_open(wc);
+                    }
                     if ui.add(menu_item(IMAGES.properties, " Properties...")).clicked() {
                         dialogs.properties_dialog.set_open(wc, tileset, self.color_picker.state.right_color);
                     }
@@ -518,6 +731,24 @@ impl Editor {
                             ]
                         );
                     }
+                    if ui.add(menu_item_no_image(" Transform...")).clicked() {
+                        dialogs.image_transform_dialog.set_open(wc, self.color_picker.state.left_color);
+                    }
+                    if ui.add(menu_item_no_image(" Adjust colors...")).clicked() {
+                        let selected_image = match self.selected_tab {
+                            EditorTab::GridTiles => self.grid_image_editor.get_selected_image(),
+                            _ => self.tile_image_editor.get_selected_image(),
+                        };
+                        dialogs.color_adjust_dialog.set_open(wc, selected_image, ! self.selection_is_empty());
+                    }
+                    if ui.add_enabled(can_change_tiles, menu_item_no_image(" Remap colors...")).clicked() {
+                        dialogs.color_remap_dialog.set_open(
+                            wc,
+                            self.tile_image_editor.get_selected_image(),
+                            self.color_picker.state.left_color,
+                            self.color_picker.state.right_color
+                        );
+                    }
 
                     ui.separator();
 
@@ -540,12 +771,20 @@ impl Editor {
                     if ui.add_enabled(can_change_tiles, menu_item(IMAGES.trash, " Remove tiles...")).clicked() {
                         dialogs.rm_tiles_dialog.set_open(wc, tileset, self.tile_image_editor.get_selected_image());
                     }
+
+                    ui.separator();
+
+                    if ui.add(menu_item(IMAGES.copy, " Copy grid tiles to map")).on_hover_text("Copy the grid tiles to paste or use as a stamp in the map editor").clicked() {
+                        wc.map_clipboard = MapClipboardData::MapLayerFragment(self.tile_grid.to_map_fragment());
+                    }
                 });
             });
         });
     }
 
     fn show_toolbar(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &mut Tileset) {
+        self.tile_image_editor.reference.update(wc, format!("tileset_tile/{}", tileset.asset.name));
+        self.grid_image_editor.reference.update(wc, format!("tileset_grid/{}", tileset.asset.name));
         egui::Panel::top(format!("editor_panel_{}_toolbar", self.asset_id)).show(ui, |ui| {
             ui.add_space(2.0);
             ui.horizontal(|ui| {
@@ -578,6 +817,26 @@ impl Editor {
                         self.grid_image_editor.set_tool(ImageDrawingTool::Select);
                     }
 
+                let tools = ImageDrawingTool::SELECTIONS.iter().chain(ImageDrawingTool::SHAPES).chain(&[ImageDrawingTool::Offset]);
+                for &tool in tools {
+                    if ui.add(egui::Button::new(tool.shape_icon())
+                              .selected(self.tile_image_editor.get_tool() == tool)
+                              .frame_when_inactive(self.tile_image_editor.get_tool() == tool))
+                        .on_hover_text(tool.shape_name()).clicked() {
+                            self.tile_image_editor.set_tool(tool);
+                            self.grid_image_editor.set_tool(tool);
+                        }
+                }
+
+                ui.add_space(5.0);
+                self.show_brush_menu(ui, tileset);
+                ui.add_space(5.0);
+                match self.selected_tab {
+                    EditorTab::Tile => { self.tile_image_editor.reference.show_menu(ui, wc); }
+                    EditorTab::GridTiles => { self.grid_image_editor.reference.show_menu(ui, wc); }
+                    EditorTab::Grid => {}
+                }
+
                 ui.add_space(5.0);
                 ui.separator();
                 ui.add_space(5.0);
@@ -601,6 +860,18 @@ impl Editor {
                     ui.horizontal(|ui| {
                         let spacing = ui.spacing().item_spacing;
                         ui.spacing_mut().item_spacing = egui::Vec2::new(1.0, 0.0);
+                        if ui.add(egui::Button::new("Wrap")
+                                  .selected(self.wrap_preview)
+                                  .frame_when_inactive(self.wrap_preview))
+                            .on_hover_text("Wrap-around preview").clicked() {
+                                self.wrap_preview = ! self.wrap_preview;
+                            }
+                        if ui.add(egui::Button::new("Usage")
+                                  .selected(self.show_usage)
+                                  .frame_when_inactive(self.show_usage))
+                            .on_hover_text("Show how many times each tile is used in maps").clicked() {
+                                self.show_usage = ! self.show_usage;
+                            }
                         if ui.add(egui::Button::image(IMAGES.grid)
                                   .selected(self.tile_image_editor.display.has_bits(ImageDisplay::GRID))
                                   .frame_when_inactive(self.tile_image_editor.display.has_bits(ImageDisplay::GRID)))
@@ -654,7 +925,65 @@ impl Editor {
         });
     }
 
-    fn show_tile_tab(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &mut Tileset) {
+    /**
+     * Show the image repeated a few times in each direction, to check how
+     * it looks when tiled.
+     */
+    fn show_wrap_preview(ui: &mut egui::Ui, wc: &mut WindowContext, panel_id: String, repeat: &mut u32,
+                         image: &impl ImageCollection, item: u32, display: ImageDisplay) {
+        egui::Panel::right(panel_id).resizable(true).default_size(200.0).show(ui, |ui| {
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Repeat:");
+                ui.add(egui::DragValue::new(repeat).range(3..=8));
+            });
+            ui.add_space(5.0);
+
+            let image_size = image.get_item_size();
+            let total_size = image_size * *repeat as f32;
+            let zoom = (ui.available_width() / total_size.x).floor().max(1.0);
+            let (response, painter) = ui.allocate_painter(total_size * zoom, egui::Sense::hover());
+            painter.rect_filled(response.rect, egui::CornerRadius::ZERO, wc.settings.image_bg_color);
+
+            let texture = image.texture(wc.tex_man, wc.egui.ctx, image.texture_slot(display.is_transparent(), false));
+            let uv = image.get_item_uv(item);
+            for y in 0..*repeat {
+                for x in 0..*repeat {
+                    let pos = response.rect.min + egui::Vec2::new(x as f32, y as f32) * image_size * zoom;
+                    let rect = egui::Rect::from_min_size(pos, image_size * zoom);
+                    egui::Image::from_texture((texture.id(), image_size)).uv(uv).paint_at(ui, rect);
+                }
+            }
+        });
+    }
+
+    /**
+     * Count the tile usage if the maps or the number of tiles changed
+     * since it was last counted. Returns true if it was counted.
+     */
+    fn update_tile_usage(tile_usage: &mut Option<Vec<u32>>, tileset: &Tileset, maps: &AssetList<MapData>) -> bool {
+        if tile_usage.as_ref().is_some_and(|usage| usage.len() == tileset.num_tiles as usize) { return false; }
+        *tile_usage = Some(count_tileset_tile_usage(maps, tileset));
+        true
+    }
+
+    fn update_usage_labels(&mut self, tileset: &Tileset, maps: &AssetList<MapData>) {
+        if ! self.show_usage {
+            self.tile_picker.item_labels.clear();
+            return;
+        }
+        let counted = Self::update_tile_usage(&mut self.tile_usage, tileset, maps);
+        if ! counted && ! self.tile_picker.item_labels.is_empty() { return; }
+        let Some(usage) = &self.tile_usage else { return; };
+        self.tile_picker.item_labels.clear();
+        self.tile_picker.item_labels.extend(usage.iter().map(|&count| {
+            let color = if count == 0 { egui::Color32::from_rgb(255, 96, 96) } else { egui::Color32::WHITE };
+            (count.to_string(), color)
+        }));
+    }
+
+    fn show_tile_tab(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &mut Tileset, maps: &AssetList<MapData>) {
+        self.update_usage_labels(tileset, maps);
         // tile picker (use the SAME ID as the other tab's panel to avoid red flashing)
         egui::Panel::left(self.tile_picker_panel_id).resizable(false).show(ui, |ui| {
             ui.add_space(5.0);
@@ -668,12 +997,19 @@ impl Editor {
             }
         });
 
+        if self.wrap_preview {
+            let panel_id = format!("editor_panel_{}_wrap_preview", self.asset_id);
+            let item = self.tile_image_editor.get_selected_image();
+            Self::show_wrap_preview(ui, wc, panel_id, &mut self.wrap_preview_repeat, tileset, item, self.tile_image_editor.display);
+        }
+
         // tile editor
         egui::CentralPanel::default().show(ui, |ui| {
             let colors = (self.color_picker.state.left_color, self.color_picker.state.right_color);
             self.tile_image_editor.show(ui, wc, tileset, colors);
             if self.tile_image_editor.has_image_changed() {
                 self.grid_image_editor.set_image_changed();
+                if self.wrap_preview { ui.ctx().request_repaint(); }  // the preview is drawn before the texture is updated
             }
             self.color_picker.maybe_set_colors(
                 self.tile_image_editor.pick_left_color.take(),
@@ -748,6 +1084,12 @@ impl Editor {
     }
 
     fn show_grid_tiles_tab(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &mut Tileset) {
+        if self.wrap_preview {
+            let panel_id = format!("editor_panel_{}_wrap_preview", self.asset_id);
+            let grid_image = self.tile_grid.get_image_mut(tileset);
+            Self::show_wrap_preview(ui, wc, panel_id, &mut self.wrap_preview_repeat, grid_image, 0, self.grid_image_editor.display);
+        }
+
         egui::CentralPanel::default().show(ui, |ui| {
             let colors = (self.color_picker.state.left_color, self.color_picker.state.right_color);
 
@@ -755,6 +1097,7 @@ impl Editor {
             self.grid_image_editor.show(ui, wc, grid_image, colors);
             if self.grid_image_editor.has_image_changed() {
                 self.handle_grid_image_changed(wc, tileset);
+                if self.wrap_preview { ui.ctx().request_repaint(); }  // the preview is drawn before the texture is updated
             }
             self.color_picker.maybe_set_colors(
                 self.grid_image_editor.pick_left_color.take(),
@@ -774,7 +1117,7 @@ impl Editor {
         }
     }
 
-    fn show(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, dialogs: &mut Dialogs, tileset: &mut Tileset) {
+    fn show(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, dialogs: &mut Dialogs, tileset: &mut Tileset, maps: &AssetList<MapData>) {
         self.show_menu_bar(ui, wc, dialogs, tileset);
         self.show_toolbar(ui, wc, tileset);
 
@@ -804,7 +1147,7 @@ impl Editor {
             });
         });
         match self.selected_tab {
-            EditorTab::Tile => { self.show_tile_tab(ui, wc, tileset); }
+            EditorTab::Tile => { self.show_tile_tab(ui, wc, tileset, maps); }
             EditorTab::Grid => { self.show_grid_tab(ui, wc, tileset); }
             EditorTab::GridTiles => { self.show_grid_tiles_tab(ui, wc, tileset); }
         }
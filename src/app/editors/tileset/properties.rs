@@ -4,6 +4,7 @@ use crate::data_asset::Tileset;
 use super::super::{
     AssetEditorBase,
     WindowContext,
+    EditorAction,
 };
 
 pub struct PropertiesDialog {
@@ -38,7 +39,10 @@ impl PropertiesDialog {
         wc.set_dialog_open(Self::id(), self.open);
     }
 
-    fn confirm(&mut self, tileset: &mut Tileset) {
+    fn confirm(&mut self, wc: &mut WindowContext, tileset: &mut Tileset) {
+        if tileset.asset.name != self.name {
+            wc.add_editor_action(EditorAction::ImageAssetRenamed);
+        }
         tileset.asset.name.clear();
         tileset.asset.name.push_str(&self.name);
         if self.num_tiles != tileset.num_tiles {
@@ -48,7 +52,7 @@ impl PropertiesDialog {
     }
 
     pub fn show(&mut self, wc: &mut WindowContext, tileset: &mut Tileset) -> bool {
-        if AssetEditorBase::show_dialog_window(wc, Self::id(), 350.0, "Tileset Properties", |ui, _wc| {
+        if AssetEditorBase::show_dialog_window(wc, Self::id(), 350.0, "Tileset Properties", |ui, wc| {
             egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
                 egui::Grid::new(format!("editor_panel_{}_prop_grid", tileset.asset.id))
                     .num_columns(2)
@@ -69,7 +73,7 @@ impl PropertiesDialog {
                     ui.close();
                 }
                 if ui.button("Ok").clicked() {
-                    self.confirm(tileset);
+                    self.confirm(wc, tileset);
                     ui.close();
                 }
             });
@@ -0,0 +1,202 @@
+use crate::image::{
+    ImageCollection,
+    TextureSlot,
+};
+use crate::data_asset::{
+    DataAssetId,
+    MapData,
+    Tileset,
+    TilesetTerrain,
+    TerrainKind,
+};
+
+use super::super::{
+    AssetEditorBase,
+    WindowContext,
+    terrain_index_to_mask,
+};
+
+const MASK_CELL_SIZE: f32 = 6.0;
+const SLOT_TILE_SIZE: f32 = 32.0;
+const PICKER_TILE_SIZE: f32 = 24.0;
+
+pub struct TerrainsDialog {
+    pub open: bool,
+    window_id: egui::Id,
+    terrain_combo_id: egui::Id,
+    kind_combo_id: egui::Id,
+    terrains: Vec<TilesetTerrain>,
+    selected_terrain: usize,
+    selected_slot: usize,
+}
+
+impl TerrainsDialog {
+    pub fn new(id: DataAssetId) -> Self {
+        TerrainsDialog {
+            window_id: egui::Id::new(format!("editor_{}_terrains", id)),
+            terrain_combo_id: egui::Id::new(format!("editor_{}_terrains_combo", id)),
+            kind_combo_id: egui::Id::new(format!("editor_{}_terrains_kind_combo", id)),
+            open: false,
+            terrains: Vec::new(),
+            selected_terrain: 0,
+            selected_slot: 0,
+        }
+    }
+
+    pub fn set_open(&mut self, wc: &mut WindowContext, tileset: &Tileset) {
+        self.terrains = tileset.terrains.clone();
+        self.selected_terrain = self.selected_terrain.min(self.terrains.len().saturating_sub(1));
+        self.selected_slot = 0;
+        self.open = true;
+        wc.set_dialog_open(self.window_id, self.open);
+    }
+
+    fn paint_tile(ui: &egui::Ui, wc: &mut WindowContext, tileset: &Tileset, tile: u8, rect: egui::Rect) {
+        if tile == MapData::NO_TILE || tile as u32 >= tileset.num_tiles {
+            ui.painter().text(rect.center(), egui::Align2::CENTER_CENTER, "-", egui::FontId::monospace(12.0), ui.visuals().text_color());
+            return;
+        }
+        let texture = tileset.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Opaque);
+        let size = egui::Vec2::splat(Tileset::TILE_SIZE as f32);
+        egui::Image::from_texture((texture.id(), size)).uv(tileset.get_item_uv(tile as u32)).paint_at(ui, rect);
+    }
+
+    fn paint_mask(ui: &egui::Ui, mask: u8, rect: egui::Rect) {
+        // cells in the same order as the neighbor bits, starting at north
+        const CELLS: [(f32, f32); 8] = [(1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0), (0.0, 2.0), (0.0, 1.0), (0.0, 0.0)];
+        let on = ui.visuals().strong_text_color();
+        let off = ui.visuals().faint_bg_color;
+        let cell = |x: f32, y: f32| egui::Rect::from_min_size(rect.min + MASK_CELL_SIZE * egui::Vec2::new(x, y), egui::Vec2::splat(MASK_CELL_SIZE - 1.0));
+        ui.painter().rect_filled(cell(1.0, 1.0), egui::CornerRadius::ZERO, on);
+        for (bit, &(x, y)) in CELLS.iter().enumerate() {
+            let color = if mask & (1 << bit) != 0 { on } else { off };
+            ui.painter().rect_filled(cell(x, y), egui::CornerRadius::ZERO, color);
+        }
+    }
+
+    fn show_slots(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        let Some(terrain) = self.terrains.get_mut(self.selected_terrain) else { return; };
+        ui.horizontal_wrapped(|ui| {
+            for (index, tile) in terrain.tiles.iter_mut().enumerate() {
+                let size = egui::Vec2::new(SLOT_TILE_SIZE + 4.0 * MASK_CELL_SIZE, SLOT_TILE_SIZE) + egui::Vec2::splat(4.0);
+                let (resp, _) = ui.allocate_painter(size, egui::Sense::click());
+                let inner = resp.rect.shrink(2.0);
+                Self::paint_mask(ui, terrain_index_to_mask(terrain.kind, index), inner);
+                let tile_rect = egui::Rect::from_min_size(inner.min + egui::Vec2::new(4.0 * MASK_CELL_SIZE, 0.0), egui::Vec2::splat(SLOT_TILE_SIZE));
+                Self::paint_tile(ui, wc, tileset, *tile, tile_rect);
+                if index == self.selected_slot {
+                    let stroke = egui::Stroke::new(2.0, ui.visuals().selection.stroke.color);
+                    ui.painter().rect_stroke(resp.rect, egui::CornerRadius::ZERO, stroke, egui::StrokeKind::Inside);
+                }
+                if resp.clicked() {
+                    self.selected_slot = index;
+                }
+                if resp.secondary_clicked() {
+                    *tile = MapData::NO_TILE;
+                }
+            }
+        });
+    }
+
+    fn show_tile_picker(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, tileset: &Tileset) {
+        let Some(terrain) = self.terrains.get_mut(self.selected_terrain) else { return; };
+        egui::ScrollArea::vertical().id_salt("terrain_tile_picker").max_height(120.0).show(ui, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing = egui::Vec2::splat(2.0);
+                for tile in 0..tileset.num_tiles {
+                    let (resp, _) = ui.allocate_painter(egui::Vec2::splat(PICKER_TILE_SIZE), egui::Sense::click());
+                    Self::paint_tile(ui, wc, tileset, tile as u8, resp.rect);
+                    if resp.clicked() && let Some(slot) = terrain.tiles.get_mut(self.selected_slot) {
+                        *slot = tile as u8;
+                        self.selected_slot = (self.selected_slot + 1) % terrain.tiles.len();
+                    }
+                    resp.on_hover_text(format!("tile {}", tile));
+                }
+            });
+        });
+    }
+
+    fn show_terrain_selector(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Terrain:");
+            let selected_text = self.terrains.get(self.selected_terrain).map(|t| t.name.as_str()).unwrap_or("-");
+            egui::ComboBox::from_id_salt(self.terrain_combo_id)
+                .selected_text(selected_text)
+                .width(150.0)
+                .show_ui(ui, |ui| {
+                    for (index, terrain) in self.terrains.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected_terrain, index, &terrain.name);
+                    }
+                });
+            if ui.button("Add").clicked() {
+                let name = format!("terrain{}", self.terrains.len() + 1);
+                self.terrains.push(TilesetTerrain::new(name, TerrainKind::Blob47));
+                self.selected_terrain = self.terrains.len() - 1;
+                self.selected_slot = 0;
+            }
+            if ui.add_enabled(! self.terrains.is_empty(), egui::Button::new("Remove")).clicked() {
+                self.terrains.remove(self.selected_terrain);
+                self.selected_terrain = self.selected_terrain.min(self.terrains.len().saturating_sub(1));
+                self.selected_slot = 0;
+            }
+        });
+    }
+
+    pub fn show(&mut self, wc: &mut WindowContext, tileset: &mut Tileset) {
+        if ! self.open { return; }
+
+        if AssetEditorBase::show_dialog_window(wc, self.window_id, 600.0, "Terrains", |ui, wc| {
+            egui::Frame::NONE.outer_margin(24.0).show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                    self.show_terrain_selector(ui);
+
+                    if let Some(terrain) = self.terrains.get_mut(self.selected_terrain) {
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            ui.label("Name:");
+                            ui.add(egui::TextEdit::singleline(&mut terrain.name).desired_width(150.0));
+                            ui.add_space(8.0);
+                            ui.label("Kind:");
+                            let mut kind = terrain.kind;
+                            egui::ComboBox::from_id_salt(self.kind_combo_id)
+                                .selected_text(kind.text())
+                                .width(180.0)
+                                .show_ui(ui, |ui| {
+                                    for &k in TerrainKind::ALL {
+                                        ui.selectable_value(&mut kind, k, k.text());
+                                    }
+                                });
+                            if kind != terrain.kind {
+                                terrain.set_kind(kind);
+                                self.selected_slot = self.selected_slot.min(terrain.tiles.len() - 1);
+                            }
+                        });
+
+                        ui.add_space(8.0);
+                        ui.label("Tiles for each neighbor combination (right click to clear):");
+                        egui::ScrollArea::vertical().id_salt("terrain_slots").max_height(260.0).show(ui, |ui| {
+                            self.show_slots(ui, wc, tileset);
+                        });
+
+                        ui.add_space(8.0);
+                        ui.label("Click a tile to assign it to the selected combination:");
+                        self.show_tile_picker(ui, wc, tileset);
+                    }
+                });
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                if ui.button("Cancel").clicked() {
+                    ui.close();
+                }
+                if ui.button("Ok").clicked() {
+                    tileset.terrains = std::mem::take(&mut self.terrains);
+                    ui.close();
+                }
+            });
+        }).should_close() {
+            self.open = false;
+            wc.set_dialog_open(self.window_id, self.open);
+        }
+    }
+}
@@ -2,6 +2,16 @@ use crate::image::{
     ImagePixels,
     ImageSlicingMethod,
 };
+use crate::data_asset::{
+    DataAssetId,
+    DataAssetType,
+    DataAssetStore,
+};
+
+use super::super::{
+    WindowContext,
+    EditorStore,
+};
 
 pub enum ImageClipboardData {
     Empty,
@@ -38,3 +48,33 @@ impl ImageSlicingMethodOption {
         }
     }
 }
+
+pub struct ImageAssetName {
+    pub id: DataAssetId,
+    pub asset_type: DataAssetType,
+    pub name: String,
+}
+
+pub fn remap_image_asset_colors(
+    wc: &mut WindowContext,
+    store: &mut DataAssetStore,
+    editors: &mut EditorStore,
+    asset_ids: &[DataAssetId],
+    frame: Option<u32>,
+    color_map: &[u8; 256]
+) {
+    for asset_id in asset_ids {
+        if let Some(tileset) = store.assets.tilesets.get_mut(asset_id) &&
+            let Some(editor) = editors.tilesets.get_mut(asset_id) {
+                editor.remap_colors(wc, tileset, frame, color_map);
+            }
+        if let Some(sprite) = store.assets.sprites.get_mut(asset_id) &&
+            let Some(editor) = editors.sprites.get_mut(asset_id) {
+                editor.remap_colors(wc, sprite, frame, color_map);
+            }
+        if let Some(font) = store.assets.fonts.get_mut(asset_id) &&
+            let Some(editor) = editors.fonts.get_mut(asset_id) {
+                editor.remap_colors(wc, font, frame, color_map);
+            }
+    }
+}
@@ -2,6 +2,11 @@ use crate::data_asset::{
     DataAssetId,
     DataAssetStore,
     MapData,
+    Tileset,
+    TilesetTerrain,
+    TileAnimation,
+    GenericAsset,
+    AssetList,
 };
 use crate::image::ImageCollection;
 
@@ -108,6 +113,7 @@ impl MapLayerFragment {
 
         if (x > 0 &&   x  as u32 >= map_width) || (y > 0 &&   y  as u32 >= map_height) { return; }
         if (x < 0 && (-x) as u32 >= map_width) || (y < 0 && (-y) as u32 >= map_height) { return; }
+        if (x < 0 && (-x) as u32 >= self.width) || (y < 0 && (-y) as u32 >= self.height) { return; }
 
         let mut src_x = 0;
         let mut src_y = 0;
@@ -144,7 +150,20 @@ impl MapLayerFragment {
     }
 }
 
+/**
+ * Position of a map in a room, saved to undo changes to the rooms when
+ * the map is resized.
+ */
+#[derive(Clone)]
+pub struct MapRoomPlacement {
+    pub room_id: DataAssetId,
+    pub index: usize,
+    pub x: u16,
+    pub y: u16,
+}
+
 pub struct MapUndoData {
+    pub room_placements: Option<Vec<MapRoomPlacement>>,
     pub width: u32,
     pub height: u32,
     pub para_width: u32,
@@ -178,6 +197,7 @@ impl MapUndoData {
             }
         }
         MapUndoData {
+            room_placements: None,
             width: map_data.width,
             height: map_data.height,
             para_width: map_data.para_width,
@@ -192,7 +212,16 @@ impl MapUndoData {
     pub fn to_map(&self, map_data: &mut MapData) -> bool {
         if self.width != map_data.width || self.height != map_data.height ||
             self.para_width != map_data.para_width || self.para_height != map_data.para_height {
-                return false;
+                // the map was resized: restore the old size and all tiles
+                map_data.width = self.width;
+                map_data.height = self.height;
+                map_data.para_width = self.para_width;
+                map_data.para_height = self.para_height;
+                map_data.fg_tiles = self.fg_tiles.clone();
+                map_data.bg_tiles = self.bg_tiles.clone();
+                map_data.fx_tiles = self.fx_tiles.clone();
+                map_data.para_tiles = self.para_tiles.clone();
+                return true;
             }
         let map_width = map_data.width as usize;
         for y in 0..map_data.height {
@@ -282,6 +311,7 @@ impl MapWholeFragment {
         let map_height = map_data.height;
         if (x > 0 &&   x  as u32 >= map_width) || (y > 0 &&   y  as u32 >= map_height) { return; }
         if (x < 0 && (-x) as u32 >= map_width) || (y < 0 && (-y) as u32 >= map_height) { return; }
+        if (x < 0 && (-x) as u32 >= self.width) || (y < 0 && (-y) as u32 >= self.height) { return; }
 
         let mut src_x = 0;
         let mut src_y = 0;
@@ -325,6 +355,116 @@ impl MapWholeFragment {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum MapStampMode {
+    Block,
+    Random,
+}
+
+impl MapStampMode {
+    pub fn text(&self) -> &'static str {
+        match self {
+            MapStampMode::Block => "Block",
+            MapStampMode::Random => "Random",
+        }
+    }
+}
+
+/**
+ * Tiles painted by the stamp tool: either a block of tiles (where
+ * `NO_TILE` leaves the map untouched) or a single tile chosen at random
+ * from a list of tiles with weights.
+ */
+#[derive(Clone)]
+pub struct MapStamp {
+    pub mode: MapStampMode,
+    pub block: MapLayerFragment,
+    pub random_tiles: Vec<u8>,
+    pub random_weights: Vec<u32>,
+    rng_state: u32,
+}
+
+impl MapStamp {
+    pub fn new() -> Self {
+        MapStamp {
+            mode: MapStampMode::Block,
+            block: MapLayerFragment {
+                width: 1,
+                height: 1,
+                layer: MapLayer::Foreground,
+                data: vec![0],
+            },
+            random_tiles: Vec::new(),
+            random_weights: Vec::new(),
+            rng_state: 0x2545_f491,
+        }
+    }
+
+    pub fn set_block(&mut self, width: u32, height: u32, data: Vec<u8>) {
+        if width == 0 || height == 0 || data.len() != (width * height) as usize { return; }
+        self.block = MapLayerFragment {
+            width,
+            height,
+            layer: MapLayer::Foreground,
+            data,
+        };
+    }
+
+    /**
+     * Return the size (in tiles) of the area painted by the stamp.
+     */
+    pub fn size(&self) -> (u32, u32) {
+        match self.mode {
+            MapStampMode::Block => (self.block.width, self.block.height),
+            MapStampMode::Random => (1, 1),
+        }
+    }
+
+    pub fn text(&self) -> String {
+        match self.mode {
+            MapStampMode::Block => format!("{}x{} block", self.block.width, self.block.height),
+            MapStampMode::Random => format!("random ({} tiles)", self.random_tiles.len()),
+        }
+    }
+
+    fn next_random(&mut self) -> u32 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng_state = x;
+        x
+    }
+
+    pub fn random_tile(&mut self) -> u8 {
+        let total = self.random_weights.iter().sum::<u32>();
+        if total == 0 { return MapData::NO_TILE; }
+        let mut pick = self.next_random() % total;
+        for (&tile, &weight) in self.random_tiles.iter().zip(self.random_weights.iter()) {
+            if pick < weight { return tile; }
+            pick -= weight;
+        }
+        MapData::NO_TILE
+    }
+
+    /**
+     * Paint the stamp in the map layer with the top left corner at (x, y).
+     */
+    pub fn paint(&mut self, x: i32, y: i32, map_data: &mut MapData, layer: MapLayer) {
+        match self.mode {
+            MapStampMode::Block => {
+                self.block.paste_in_map(x, y, map_data, layer);
+            }
+            MapStampMode::Random => {
+                let tile = self.random_tile();
+                if tile == MapData::NO_TILE { return; }
+                let frag = MapLayerFragment { width: 1, height: 1, layer, data: vec![tile] };
+                frag.paste_in_map(x, y, map_data, layer);
+            }
+        }
+    }
+}
+
 pub enum MapClipboardData {
     Empty,
     MapLayerFragment(MapLayerFragment),
@@ -366,6 +506,24 @@ pub trait MapTileFixer {
         }
     }
 
+    fn replace_tile(&mut self, old_index: u8, new_index: u8) {
+        for plane in self.get_tile_planes_mut() {
+            for tile in plane.iter_mut() {
+                if *tile == old_index {
+                    *tile = new_index;
+                }
+            }
+        }
+    }
+
+    fn remap_tiles(&mut self, tile_map: &[u8; 256]) {
+        for plane in self.get_tile_planes_mut() {
+            for tile in plane.iter_mut() {
+                *tile = tile_map[*tile as usize];
+            }
+        }
+    }
+
     fn remove_tileset_hole(&mut self, hole_start: u8, hole_size: u8, num_tiles_after_hole: u8) {
         fn rm_plane_hole(tiles: &mut [u8], tile_index: u8, num_tiles: u8) {
             for tile in tiles {
@@ -391,6 +549,16 @@ impl MapTileFixer for MapData {
     }
 }
 
+impl MapTileFixer for Tileset {
+    fn get_tile_planes_mut(&mut self) -> Vec<&mut [u8]> {
+        let terrain_planes = self.terrains.iter_mut().map(|terrain| terrain.tiles.as_mut_slice());
+        let animation_planes = self.animations.iter_mut().flat_map(|anim| {
+            [std::slice::from_mut(&mut anim.tile), anim.frame_tiles.as_mut_slice()]
+        });
+        terrain_planes.chain(animation_planes).collect()
+    }
+}
+
 pub fn fix_after_tileset_tiles_added(
     wc: &mut WindowContext,
     store: &mut DataAssetStore,
@@ -412,6 +580,7 @@ pub fn fix_after_tileset_tiles_added(
         }
     }
     if let Some(tileset) = store.assets.tilesets.get_mut(&tileset_id) {
+        tileset.add_tileset_hole(hole_start, hole_size, num_tiles_after_hole);
         tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(false, false), true);
         tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(true, false), true);
     }
@@ -438,9 +607,244 @@ pub fn fix_after_tileset_tiles_removed(
         }
     }
     if let Some(tileset) = store.assets.tilesets.get_mut(&tileset_id) {
+        tileset.clear_terrain_tiles(hole_start, hole_size);
+        tileset.clear_animation_tiles(hole_start, hole_size);
+        tileset.remove_tileset_hole(hole_start, hole_size, num_tiles_after_hole);
+        tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(false, false), true);
+        tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(true, false), true);
+    }
+}
+
+// (plane index, tile index, merged tile) of the merged tiles of a map
+type MergedMapTiles = Vec<(usize, usize, u8)>;
+
+/**
+ * Count how many times each tile of the tileset is used in the maps that
+ * use it (the effects layer doesn't use tileset tiles, so it's ignored).
+ */
+pub fn count_tileset_tile_usage(maps: &AssetList<MapData>, tileset: &Tileset) -> Vec<u32> {
+    let mut usage = vec![0; tileset.num_tiles as usize];
+    for map_data in maps.iter().filter(|map_data| map_data.tileset_id == tileset.asset.id) {
+        for &tile in map_data.fg_tiles.iter().chain(map_data.bg_tiles.iter()).chain(map_data.para_tiles.iter()) {
+            if let Some(count) = usage.get_mut(tile as usize) {
+                *count += 1;
+            }
+        }
+    }
+    usage
+}
+
+/**
+ * Rearrange the tiles of a tileset so that the new tile `i` is the old
+ * tile `order[i]`, removing the tiles not in `order`, and fix all maps.
+ */
+pub fn reorder_tileset_tiles(
+    wc: &mut WindowContext,
+    store: &mut DataAssetStore,
+    editors: &mut EditorStore,
+    tileset_id: DataAssetId,
+    order: &[u8]
+) {
+    let Some(tileset) = store.assets.tilesets.get_mut(&tileset_id) else { return; };
+    if order.is_empty() || order.iter().any(|&tile| tile as u32 >= tileset.num_tiles) { return; }
+
+    let mut tile_map = [MapData::NO_TILE; 256];
+    for (new_index, &old_index) in order.iter().enumerate() {
+        tile_map[old_index as usize] = new_index as u8;
+    }
+
+    let old_size = tileset.data_size();
+    let old_num_tiles = tileset.num_tiles;
+    let tile_size = (tileset.width * tileset.height) as usize;
+    let data = order.iter().flat_map(|&tile| {
+        let start = tile as usize * tile_size;
+        tileset.data[start..start + tile_size].iter().copied()
+    }).collect();
+    tileset.data = data;
+    tileset.num_tiles = order.len() as u32;
+    tileset.remap_tiles(&tile_map);
+    tileset.remove_empty_animation_tiles();
+    tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(false, false), true);
+    tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(true, false), true);
+    let new_size = tileset.data_size();
+
+    let mut num_maps = 0;
+    for map_id in store.asset_ids.maps.iter() {
+        if let Some(map_data) = store.assets.maps.get_mut(map_id) && map_data.tileset_id == tileset_id {
+            map_data.remap_tiles(&tile_map);
+            if let Some(map_editor) = editors.maps.get_mut(map_id) {
+                map_editor.remap_tiles(&tile_map);
+            }
+            num_maps += 1;
+        }
+    }
+    if let Some(tileset_editor) = editors.tilesets.get_mut(&tileset_id) {
+        tileset_editor.remap_tiles(&tile_map);
+        tileset_editor.tiles_reordered(tileset);
+    }
+
+    wc.logger.log(format!(
+        "Compacted tileset '{}': {} tiles -> {} tiles, {} bytes saved, {} maps updated",
+        tileset.asset.name, old_num_tiles, tileset.num_tiles, old_size - new_size, num_maps
+    ));
+    let moved = order.iter().enumerate()
+        .filter(|&(new_index, &old_index)| new_index != old_index as usize)
+        .map(|(new_index, old_index)| format!("{}->{}", old_index, new_index))
+        .collect::<Vec<_>>();
+    if ! moved.is_empty() {
+        wc.logger.log(format!("  moved tiles: {}", moved.join(" ")));
+    }
+}
+
+/**
+ * Data needed to undo a tile merge. The tileset is only restored if it
+ * wasn't changed after the merge.
+ */
+pub struct TilesetMergeUndo {
+    merges: Vec<(u8, u8)>,
+    num_tiles: u32,
+    data: Vec<u8>,
+    terrains: Vec<TilesetTerrain>,
+    animations: Vec<TileAnimation>,
+    tileset_hash: u64,
+    map_tiles: Vec<(DataAssetId, MergedMapTiles)>,
+}
+
+impl TilesetMergeUndo {
+    fn calc_tileset_hash(tileset: &Tileset) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::hash::DefaultHasher::new();
+        tileset.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn can_undo(&self, tileset: &Tileset) -> bool {
+        self.tileset_hash == Self::calc_tileset_hash(tileset)
+    }
+}
+
+/**
+ * Merge the tiles of a tileset: each `(tile, original)` pair makes all
+ * maps use `original` in place of `tile`, and then `tile` is removed from
+ * the tileset.
+ */
+pub fn merge_tileset_tiles(
+    wc: &mut WindowContext,
+    store: &mut DataAssetStore,
+    editors: &mut EditorStore,
+    tileset_id: DataAssetId,
+    merges: &[(u8, u8)]
+) {
+    let Some(tileset) = store.assets.tilesets.get(&tileset_id) else { return; };
+    let old_size = tileset.data_size();
+    let mut undo = TilesetMergeUndo {
+        merges: merges.to_vec(),
+        num_tiles: tileset.num_tiles,
+        data: tileset.data.clone(),
+        terrains: tileset.terrains.clone(),
+        animations: tileset.animations.clone(),
+        tileset_hash: 0,
+        map_tiles: Vec::new(),
+    };
+    undo.merges.sort_by_key(|&(tile, _)| std::cmp::Reverse(tile));
+    undo.merges.dedup_by_key(|&mut (tile, _)| tile);
+
+    // remember where the merged tiles were used, to restore them on undo
+    for map_id in store.asset_ids.maps.iter() {
+        if let Some(map_data) = store.assets.maps.get_mut(map_id) && map_data.tileset_id == tileset_id {
+            let mut tiles = Vec::new();
+            for (plane_index, plane) in map_data.get_tile_planes_mut().into_iter().enumerate() {
+                for (index, &tile) in plane.iter().enumerate() {
+                    if undo.merges.iter().any(|&(merged, _)| merged == tile) {
+                        tiles.push((plane_index, index, tile));
+                    }
+                }
+            }
+            if ! tiles.is_empty() {
+                undo.map_tiles.push((*map_id, tiles));
+            }
+        }
+    }
+
+    // highest tiles first, so the indices of the other tiles don't change
+    for &(tile, original) in undo.merges.iter() {
+        let Some(tileset) = store.assets.tilesets.get_mut(&tileset_id) else { return; };
+        if tile as u32 >= tileset.num_tiles || original >= tile { continue; }
+        let num_tiles_after_hole = (tileset.num_tiles - tile as u32 - 1) as u8;
+        tileset.replace_tile(tile, original);
+        let tile_size = (tileset.width * tileset.height) as usize;
+        tileset.data.drain(tile as usize * tile_size .. (tile as usize + 1) * tile_size);
+        tileset.num_tiles -= 1;
+
+        if let Some(tileset_editor) = editors.tilesets.get_mut(&tileset_id) {
+            tileset_editor.replace_tile(tile, original);
+        }
+        for map_id in store.asset_ids.maps.iter() {
+            if let Some(map_data) = store.assets.maps.get_mut(map_id) && map_data.tileset_id == tileset_id {
+                map_data.replace_tile(tile, original);
+                if let Some(map_editor) = editors.maps.get_mut(map_id) {
+                    map_editor.replace_tile(tile, original);
+                }
+            }
+        }
+        fix_after_tileset_tiles_removed(wc, store, editors, tileset_id, tile, 1, num_tiles_after_hole);
+    }
+
+    let Some(tileset) = store.assets.tilesets.get(&tileset_id) else { return; };
+    let saved = old_size - tileset.data_size();
+    wc.logger.log(format!("Merged {} duplicate tiles of tileset '{}', saved {} bytes", undo.merges.len(), tileset.asset.name, saved));
+    wc.open_message_box("Merge Duplicate Tiles", format!("Merged {} duplicate tiles.\n\n{} bytes saved.", undo.merges.len(), saved));
+    undo.tileset_hash = TilesetMergeUndo::calc_tileset_hash(tileset);
+    if let Some(tileset_editor) = editors.tilesets.get_mut(&tileset_id) {
+        tileset_editor.set_merge_undo(tileset, undo);
+    }
+}
+
+/**
+ * Undo the last tile merge of the tileset, putting back the removed tiles
+ * and making the maps use them again.
+ */
+pub fn undo_tileset_tiles_merge(
+    wc: &mut WindowContext,
+    store: &mut DataAssetStore,
+    editors: &mut EditorStore,
+    tileset_id: DataAssetId
+) {
+    let Some(undo) = editors.tilesets.get_mut(&tileset_id).and_then(|editor| editor.take_merge_undo()) else { return; };
+    let Some(tileset) = store.assets.tilesets.get(&tileset_id) else { return; };
+    if ! undo.can_undo(tileset) { return; }
+
+    // lowest tiles first, to put them back at their original indices
+    for &(tile, _) in undo.merges.iter().rev() {
+        let Some(tileset) = store.assets.tilesets.get(&tileset_id) else { return; };
+        let num_tiles_after_hole = (tileset.num_tiles - tile as u32) as u8;
+        fix_after_tileset_tiles_added(wc, store, editors, tileset_id, tile, 1, num_tiles_after_hole);
+    }
+
+    if let Some(tileset) = store.assets.tilesets.get_mut(&tileset_id) {
+        tileset.num_tiles = undo.num_tiles;
+        tileset.data = undo.data;
+        tileset.terrains = undo.terrains;
+        tileset.animations = undo.animations;
         tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(false, false), true);
         tileset.load_texture(wc.tex_man, wc.egui.ctx, tileset.texture_slot(true, false), true);
+        if let Some(tileset_editor) = editors.tilesets.get_mut(&tileset_id) {
+            tileset_editor.tiles_changed(tileset);
+        }
     }
+    for (map_id, tiles) in undo.map_tiles {
+        let Some(map_data) = store.assets.maps.get_mut(&map_id) else { continue; };
+        if map_data.tileset_id != tileset_id { continue; }
+        let mut planes = map_data.get_tile_planes_mut();
+        for (plane_index, index, tile) in tiles {
+            if let Some(t) = planes.get_mut(plane_index).and_then(|plane| plane.get_mut(index)) &&
+                let Some(&(_, original)) = undo.merges.iter().find(|&&(merged, _)| merged == tile) &&
+                *t == original {
+                    *t = tile;
+                }
+        }
+    }
+    wc.logger.log("Undid merge of duplicate tiles");
 }
 
 pub fn resize_map_tiles(tiles: &mut Vec<u8>, old_w: u32, old_h: u32, new_w: u32, new_h: u32, new_tile: u8) {
@@ -463,3 +867,164 @@ pub fn resize_map_tiles(tiles: &mut Vec<u8>, old_w: u32, old_h: u32, new_w: u32,
         tiles.resize(new_w * new_h, new_tile);
     }
 }
+
+/**
+ * Which part of a map stays in place when it's resized: 0 is left
+ * (or top), 1 is center and 2 is right (or bottom).
+ */
+#[derive(Clone, Copy, PartialEq)]
+pub struct MapResizeAnchor {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl MapResizeAnchor {
+    pub const TOP_LEFT: Self = MapResizeAnchor { x: 0, y: 0 };
+
+    /**
+     * Return the position of the old contents in the resized map.
+     */
+    pub fn offset(&self, old_w: u32, old_h: u32, new_w: u32, new_h: u32) -> (i32, i32) {
+        let calc = |anchor: u32, old: u32, new: u32| (new as i32 - old as i32) * anchor as i32 / 2;
+        (calc(self.x, old_w, new_w), calc(self.y, old_h, new_h))
+    }
+}
+
+/**
+ * Return a resized copy of a tile plane with the old tiles placed at
+ * `(x, y)`.
+ */
+fn move_map_tiles(tiles: &[u8], old_w: u32, old_h: u32, new_w: u32, new_h: u32, (x, y): (i32, i32), new_tile: u8) -> Vec<u8> {
+    let mut new_tiles = vec![new_tile; (new_w * new_h) as usize];
+    for new_y in 0..new_h {
+        let old_y = new_y as i32 - y;
+        if old_y < 0 || old_y >= old_h as i32 { continue; }
+        for new_x in 0..new_w {
+            let old_x = new_x as i32 - x;
+            if old_x < 0 || old_x >= old_w as i32 { continue; }
+            new_tiles[(new_y * new_w + new_x) as usize] = tiles[(old_y as u32 * old_w + old_x as u32) as usize];
+        }
+    }
+    new_tiles
+}
+
+/**
+ * Resize a map keeping the contents at the anchor. The parallax layer
+ * is anchored the same way to its own new size. Returns the offset of
+ * the old contents of the foreground in the resized map.
+ */
+pub fn resize_map_anchored(map_data: &mut MapData, new_w: u32, new_h: u32, new_para_w: u32, new_para_h: u32,
+                           anchor: MapResizeAnchor, new_tile: u8) -> (i32, i32) {
+    let (w, h) = (map_data.width, map_data.height);
+    let offset = anchor.offset(w, h, new_w, new_h);
+    map_data.fg_tiles = move_map_tiles(&map_data.fg_tiles, w, h, new_w, new_h, offset, MapData::NO_TILE);
+    map_data.bg_tiles = move_map_tiles(&map_data.bg_tiles, w, h, new_w, new_h, offset, new_tile);
+    map_data.fx_tiles = move_map_tiles(&map_data.fx_tiles, w, h, new_w, new_h, offset, MapData::NO_TILE);
+    map_data.width = new_w;
+    map_data.height = new_h;
+
+    let (para_w, para_h) = (map_data.para_width, map_data.para_height);
+    let para_offset = anchor.offset(para_w, para_h, new_para_w, new_para_h);
+    map_data.para_tiles = move_map_tiles(&map_data.para_tiles, para_w, para_h, new_para_w, new_para_h, para_offset, new_tile);
+    map_data.para_width = new_para_w;
+    map_data.para_height = new_para_h;
+    offset
+}
+
+/**
+ * Shift the tiles of a tile plane by `(dx, dy)`, either wrapping them
+ * around or filling the uncovered area with `new_tile`.
+ */
+pub fn shift_map_tiles(tiles: &mut [u8], width: u32, height: u32, (dx, dy): (i32, i32), wrap: bool, new_tile: u8) {
+    if width == 0 || height == 0 { return; }
+    let old_tiles = tiles.to_vec();
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            let (src_x, src_y) = (x - dx, y - dy);
+            let tile = if wrap {
+                let src_x = src_x.rem_euclid(width as i32) as u32;
+                let src_y = src_y.rem_euclid(height as i32) as u32;
+                old_tiles[(src_y * width + src_x) as usize]
+            } else if src_x < 0 || src_y < 0 || src_x >= width as i32 || src_y >= height as i32 {
+                new_tile
+            } else {
+                old_tiles[(src_y as u32 * width + src_x as u32) as usize]
+            };
+            tiles[(y as u32 * width + x as u32) as usize] = tile;
+        }
+    }
+}
+
+/**
+ * Shift a layer of the map (or all layers, if `layer` is `None`).
+ */
+pub fn shift_map(map_data: &mut MapData, layer: Option<MapLayer>, offset: (i32, i32), wrap: bool, new_tile: u8) {
+    let (w, h) = (map_data.width, map_data.height);
+    let (para_w, para_h) = (map_data.para_width, map_data.para_height);
+    let shift_layer = |l: MapLayer| layer.is_none_or(|layer| layer == l);
+    if shift_layer(MapLayer::Foreground) {
+        shift_map_tiles(&mut map_data.fg_tiles, w, h, offset, wrap, MapData::NO_TILE);
+    }
+    if shift_layer(MapLayer::Background) {
+        shift_map_tiles(&mut map_data.bg_tiles, w, h, offset, wrap, new_tile);
+    }
+    if shift_layer(MapLayer::Effects) {
+        shift_map_tiles(&mut map_data.fx_tiles, w, h, offset, wrap, MapData::NO_TILE);
+    }
+    if shift_layer(MapLayer::Parallax) {
+        shift_map_tiles(&mut map_data.para_tiles, para_w, para_h, offset, wrap, new_tile);
+    }
+}
+
+/**
+ * Move the map in all rooms that use it by `(-dx, -dy)`, to compensate
+ * for its contents moving by `(dx, dy)` when it was resized.
+ */
+pub fn offset_map_in_rooms(wc: &mut WindowContext, store: &mut DataAssetStore, map_id: DataAssetId, (dx, dy): (i32, i32))
+                           -> Vec<MapRoomPlacement> {
+    let mut old_placements = Vec::new();
+    let mut num_moved = 0;
+    let mut num_clamped = 0;
+    for room in store.assets.rooms.iter_mut() {
+        for (index, room_map) in room.maps.iter_mut().enumerate().filter(|(_, room_map)| room_map.map_id == map_id) {
+            old_placements.push(MapRoomPlacement { room_id: room.asset.id, index, x: room_map.x, y: room_map.y });
+            let x = room_map.x as i32 - dx;
+            let y = room_map.y as i32 - dy;
+            if x < 0 || y < 0 || x > u16::MAX as i32 || y > u16::MAX as i32 {
+                wc.logger.log(format!("WARNING: map position in room '{}' clamped to ({}, {})",
+                                      room.asset.name, x.clamp(0, u16::MAX as i32), y.clamp(0, u16::MAX as i32)));
+                num_clamped += 1;
+            }
+            room_map.x = x.clamp(0, u16::MAX as i32) as u16;
+            room_map.y = y.clamp(0, u16::MAX as i32) as u16;
+            num_moved += 1;
+        }
+    }
+    if num_moved > 0 {
+        wc.logger.log(format!("Moved map in {} room placements to compensate for resize", num_moved));
+    }
+    if num_clamped > 0 {
+        wc.open_message_box(
+            "Map Resized",
+            "Some room placements of the map couldn't be fully adjusted.\n\nConsult the log window for more information."
+        );
+    }
+    old_placements
+}
+
+/**
+ * Restore the positions of a map in rooms, returning the positions
+ * they replaced.
+ */
+pub fn set_map_room_placements(store: &mut DataAssetStore, map_id: DataAssetId, placements: &[MapRoomPlacement]) -> Vec<MapRoomPlacement> {
+    let mut old_placements = Vec::new();
+    for placement in placements {
+        if let Some(room) = store.assets.rooms.get_mut(&placement.room_id) &&
+            let Some(room_map) = room.maps.get_mut(placement.index) && room_map.map_id == map_id {
+                old_placements.push(MapRoomPlacement { x: room_map.x, y: room_map.y, ..placement.clone() });
+                room_map.x = placement.x;
+                room_map.y = placement.y;
+            }
+    }
+    old_placements
+}
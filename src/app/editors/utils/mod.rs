@@ -3,6 +3,7 @@ mod room_utils;
 mod image_utils;
 mod sprite_utils;
 mod tileset_utils;
+mod terrain_utils;
 mod asset_id_holder;
 mod pal_sprite_utils;
 mod image_zoom_option;
@@ -13,6 +14,7 @@ pub use room_utils::{*};
 pub use image_utils::{*};
 pub use sprite_utils::{*};
 pub use tileset_utils::{*};
+pub use terrain_utils::{*};
 pub use asset_id_holder::{*};
 pub use pal_sprite_utils::{*};
 pub use image_zoom_option::{*};
@@ -0,0 +1,148 @@
+use std::sync::LazyLock;
+
+use crate::data_asset::{
+    MapData,
+    TilesetTerrain,
+    TerrainKind,
+};
+
+// neighbor bits used for blob masks
+pub const TERRAIN_N: u8 = 1 << 0;
+pub const TERRAIN_NE: u8 = 1 << 1;
+pub const TERRAIN_E: u8 = 1 << 2;
+pub const TERRAIN_SE: u8 = 1 << 3;
+pub const TERRAIN_S: u8 = 1 << 4;
+pub const TERRAIN_SW: u8 = 1 << 5;
+pub const TERRAIN_W: u8 = 1 << 6;
+pub const TERRAIN_NW: u8 = 1 << 7;
+
+const NEIGHBORS: [(i32, i32, u8); 8] = [
+    ( 0, -1, TERRAIN_N),
+    ( 1, -1, TERRAIN_NE),
+    ( 1,  0, TERRAIN_E),
+    ( 1,  1, TERRAIN_SE),
+    ( 0,  1, TERRAIN_S),
+    (-1,  1, TERRAIN_SW),
+    (-1,  0, TERRAIN_W),
+    (-1, -1, TERRAIN_NW),
+];
+
+// all 8-neighbor masks that are relevant for blob tiles (there are 47)
+static BLOB_MASKS: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    (0..=255u8).filter(|&mask| reduce_blob_mask(mask) == mask).collect()
+});
+
+/**
+ * Clear the corner bits of a blob mask that don't matter (a corner only
+ * matters if both edges next to it are set).
+ */
+fn reduce_blob_mask(mask: u8) -> u8 {
+    let mut reduced = mask & (TERRAIN_N | TERRAIN_E | TERRAIN_S | TERRAIN_W);
+    for (corner, edge1, edge2) in [
+        (TERRAIN_NE, TERRAIN_N, TERRAIN_E),
+        (TERRAIN_SE, TERRAIN_S, TERRAIN_E),
+        (TERRAIN_SW, TERRAIN_S, TERRAIN_W),
+        (TERRAIN_NW, TERRAIN_N, TERRAIN_W),
+    ] {
+        if mask & corner != 0 && mask & edge1 != 0 && mask & edge2 != 0 {
+            reduced |= corner;
+        }
+    }
+    reduced
+}
+
+/**
+ * Convert a 8-neighbor mask to the index of the tile in the terrain.
+ */
+pub fn terrain_mask_to_index(kind: TerrainKind, mask: u8) -> usize {
+    let has = |bits: u8| mask & bits == bits;
+    match kind {
+        TerrainKind::Blob47 => {
+            let mask = reduce_blob_mask(mask);
+            BLOB_MASKS.iter().position(|&m| m == mask).unwrap_or(0)
+        }
+        TerrainKind::Edge16 => {
+            [TERRAIN_N, TERRAIN_E, TERRAIN_S, TERRAIN_W].iter().enumerate()
+                .fold(0, |index, (bit, &dir)| if has(dir) { index | (1 << bit) } else { index })
+        }
+        TerrainKind::Corner16 => {
+            [
+                TERRAIN_N | TERRAIN_NW | TERRAIN_W,
+                TERRAIN_N | TERRAIN_NE | TERRAIN_E,
+                TERRAIN_S | TERRAIN_SE | TERRAIN_E,
+                TERRAIN_S | TERRAIN_SW | TERRAIN_W,
+            ].iter().enumerate()
+                .fold(0, |index, (bit, &corner)| if has(corner) { index | (1 << bit) } else { index })
+        }
+    }
+}
+
+/**
+ * Return a 8-neighbor mask with the neighbors considered for the given
+ * tile index, used to show the terrain rules.
+ */
+pub fn terrain_index_to_mask(kind: TerrainKind, index: usize) -> u8 {
+    match kind {
+        TerrainKind::Blob47 => BLOB_MASKS.get(index).copied().unwrap_or(0),
+        TerrainKind::Edge16 => {
+            [TERRAIN_N, TERRAIN_E, TERRAIN_S, TERRAIN_W].iter().enumerate()
+                .fold(0, |mask, (bit, &dir)| if index & (1 << bit) != 0 { mask | dir } else { mask })
+        }
+        TerrainKind::Corner16 => {
+            [
+                TERRAIN_N | TERRAIN_NW | TERRAIN_W,
+                TERRAIN_N | TERRAIN_NE | TERRAIN_E,
+                TERRAIN_S | TERRAIN_SE | TERRAIN_E,
+                TERRAIN_S | TERRAIN_SW | TERRAIN_W,
+            ].iter().enumerate()
+                .fold(0, |mask, (bit, &corner)| if index & (1 << bit) != 0 { mask | corner } else { mask })
+        }
+    }
+}
+
+fn terrain_tile_for_mask(terrain: &TilesetTerrain, mask: u8) -> u8 {
+    let tile = terrain.tiles.get(terrain_mask_to_index(terrain.kind, mask)).copied().unwrap_or(MapData::NO_TILE);
+    if tile != MapData::NO_TILE { return tile; }
+
+    // use the tile for the full terrain, or any tile if that's not set
+    let full = terrain.tiles.get(terrain_mask_to_index(terrain.kind, 0xff)).copied().unwrap_or(MapData::NO_TILE);
+    if full != MapData::NO_TILE { return full; }
+    terrain.tiles.iter().copied().find(|&t| t != MapData::NO_TILE).unwrap_or(MapData::NO_TILE)
+}
+
+/**
+ * Paint (or erase, if `paint` is false) the terrain at (x, y) in the
+ * given tile plane, then update the tile and its neighbors to match the
+ * terrain rules. Tiles outside the plane are considered to be part of
+ * the terrain. When erasing, the tile is replaced with `erase_tile`.
+ */
+pub fn paint_terrain(tiles: &mut [u8], width: u32, height: u32, (x, y): (i32, i32),
+                     terrain: &TilesetTerrain, paint: bool, erase_tile: u8) {
+    if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height { return; }
+    let get = |tiles: &[u8], x: i32, y: i32| -> Option<u8> {
+        if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height { return None; }
+        Some(tiles[(y as u32 * width + x as u32) as usize])
+    };
+
+    let index = (y as u32 * width + x as u32) as usize;
+    if paint {
+        if ! terrain.contains_tile(tiles[index]) {
+            tiles[index] = terrain_tile_for_mask(terrain, 0xff);
+            if tiles[index] == MapData::NO_TILE { return; }  // terrain has no tiles
+        }
+    } else {
+        tiles[index] = erase_tile;
+    }
+
+    for (cx, cy) in std::iter::once((x, y)).chain(NEIGHBORS.iter().map(|&(dx, dy, _)| (x + dx, y + dy))) {
+        let Some(tile) = get(tiles, cx, cy) else { continue; };
+        if ! terrain.contains_tile(tile) { continue; }
+        let mask = NEIGHBORS.iter().fold(0, |mask, &(dx, dy, bit)| {
+            match get(tiles, cx + dx, cy + dy) {
+                Some(t) if ! terrain.contains_tile(t) => mask,
+                _ => mask | bit,
+            }
+        });
+        tiles[(cy as u32 * width + cx as u32) as usize] = terrain_tile_for_mask(terrain, mask);
+    }
+}
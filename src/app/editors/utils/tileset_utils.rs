@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashSet, HashMap};
 
 use crate::image::colors;
 use crate::data_asset::{
@@ -16,6 +16,8 @@ use crate::image::{
 use super::{
     MapTileFixer,
     AssetIdHolder,
+    MapLayer,
+    MapLayerFragment,
 };
 use super::super::resize_map_tiles;
 
@@ -90,6 +92,15 @@ impl TileGrid {
         }
     }
 
+    pub fn to_map_fragment(&self) -> MapLayerFragment {
+        MapLayerFragment {
+            width: self.width,
+            height: self.height,
+            layer: MapLayer::Foreground,
+            data: self.fg_tiles.clone(),
+        }
+    }
+
     fn copy_tile(dest: &mut [u8], dst_stride: usize, src: &[u8], src_stride: usize) {
         let size = Tileset::TILE_SIZE as usize;
         for y in 0..size {
@@ -240,3 +251,153 @@ impl ImageCollection for TileGridImage {
     fn data(&self) -> &Vec<u8> { &self.pixels.data }
     fn data_mut(&mut self) -> &mut Vec<u8> { &mut self.pixels.data }
 }
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TileDuplicateKind {
+    Exact,
+    HFlip,
+    VFlip,
+    HVFlip,
+}
+
+impl TileDuplicateKind {
+    pub fn text(&self) -> &'static str {
+        match self {
+            TileDuplicateKind::Exact => "same as",
+            TileDuplicateKind::HFlip => "horizontal flip of",
+            TileDuplicateKind::VFlip => "vertical flip of",
+            TileDuplicateKind::HVFlip => "rotated 180\u{b0} from",
+        }
+    }
+}
+
+pub struct TileDuplicate {
+    pub tile: u8,
+    pub original: u8,
+    pub kind: TileDuplicateKind,
+}
+
+/**
+ * Find the tiles that are the same as (or, if `include_flipped` is set,
+ * flipped versions of) a tile that comes before them in the tileset.
+ */
+pub fn find_duplicate_tiles(tileset: &Tileset, include_flipped: bool) -> Vec<TileDuplicate> {
+    let width = tileset.width as usize;
+    let height = tileset.height as usize;
+    let tile_size = width * height;
+    let flip = |tile: &[u8], hflip: bool, vflip: bool| -> Vec<u8> {
+        (0..tile_size).map(|i| {
+            let (x, y) = (i % width, i / width);
+            let x = if hflip { width - 1 - x } else { x };
+            let y = if vflip { height - 1 - y } else { y };
+            tile[y * width + x]
+        }).collect()
+    };
+
+    let mut seen = HashMap::<&[u8], u8>::new();
+    let mut duplicates = Vec::new();
+    let num_tiles = (tileset.num_tiles as usize).min(u8::MAX as usize);
+    for (index, tile) in tileset.data.chunks_exact(tile_size).take(num_tiles).enumerate() {
+        let index = index as u8;
+        if let Some(&original) = seen.get(tile) {
+            duplicates.push(TileDuplicate { tile: index, original, kind: TileDuplicateKind::Exact });
+            continue;
+        }
+        if include_flipped {
+            let flipped = [
+                (TileDuplicateKind::HFlip, true, false),
+                (TileDuplicateKind::VFlip, false, true),
+                (TileDuplicateKind::HVFlip, true, true),
+            ].into_iter().find_map(|(kind, hflip, vflip)| {
+                seen.get(flip(tile, hflip, vflip).as_slice()).map(|&original| (kind, original))
+            });
+            if let Some((kind, original)) = flipped {
+                duplicates.push(TileDuplicate { tile: index, original, kind });
+            }
+        }
+        seen.insert(tile, index);
+    }
+    duplicates
+}
+
+/**
+ * Unique tiles and map cells built by slicing a full level image.
+ * Cells that were skipped because they're empty are `None`.
+ */
+pub struct LevelImage {
+    pub width: u32,
+    pub height: u32,
+    pub num_tiles: u32,
+    pub tile_data: Vec<u8>,
+    pub cells: Vec<Option<u32>>,
+}
+
+impl LevelImage {
+    /**
+     * Keep only the first `max_tiles` tiles, leaving the cells that use
+     * the other tiles empty. Returns the number of cells left empty.
+     */
+    pub fn truncate_tiles(&mut self, max_tiles: u32) -> usize {
+        if self.num_tiles <= max_tiles { return 0; }
+        let tile_size = (Tileset::TILE_SIZE * Tileset::TILE_SIZE) as usize;
+        self.tile_data.truncate(max_tiles as usize * tile_size);
+        self.num_tiles = max_tiles;
+        let mut num_emptied = 0;
+        for cell in self.cells.iter_mut() {
+            if cell.is_some_and(|tile| tile >= max_tiles) {
+                *cell = None;
+                num_emptied += 1;
+            }
+        }
+        num_emptied
+    }
+}
+
+/**
+ * Slice an image into tiles of `Tileset::TILE_SIZE`, keeping only one
+ * copy of each distinct tile. If `skip_empty` is set, fully transparent
+ * cells don't get a tile. Cells at the right and bottom borders that are
+ * cut by the image size are padded with transparent pixels.
+ */
+pub fn slice_level_image(pixels: &ImagePixels, skip_empty: bool) -> LevelImage {
+    let size = Tileset::TILE_SIZE;
+    let width = pixels.width.div_ceil(size);
+    let height = pixels.height.div_ceil(size);
+
+    let mut tile_indices = HashMap::<Vec<u8>, u32>::new();
+    let mut tile_data = Vec::new();
+    let mut cells = Vec::with_capacity((width * height) as usize);
+    let mut tile = vec![colors::TRANSPARENT; (size * size) as usize];
+    for cell_y in 0..height {
+        for cell_x in 0..width {
+            for y in 0..size {
+                for x in 0..size {
+                    let (src_x, src_y) = (cell_x * size + x, cell_y * size + y);
+                    tile[(y * size + x) as usize] = if src_x < pixels.width && src_y < pixels.height {
+                        pixels.data[(src_y * pixels.width + src_x) as usize]
+                    } else {
+                        colors::TRANSPARENT
+                    };
+                }
+            }
+            if skip_empty && tile.iter().all(|&pixel| pixel == colors::TRANSPARENT) {
+                cells.push(None);
+                continue;
+            }
+            let num_tiles = tile_indices.len() as u32;
+            let index = *tile_indices.entry(tile.clone()).or_insert_with(|| {
+                tile_data.extend_from_slice(&tile);
+                num_tiles
+            });
+            cells.push(Some(index));
+        }
+    }
+
+    LevelImage {
+        width,
+        height,
+        num_tiles: tile_indices.len() as u32,
+        tile_data,
+        cells,
+    }
+}
@@ -14,15 +14,21 @@ use egui::{
 
 use crate::image::{
     colors,
+    line_points,
     ImageCollection,
     ImageFragment,
+    ImageMask,
     ImagePixels,
     ImageRect,
     ImageRotation,
+    ImageTransform,
 };
 use crate::data_asset;
 
-use super::WidgetZoom;
+use super::{
+    WidgetZoom,
+    ReferenceImage,
+};
 use super::super::{
     AssetIdHolder,
     ImageClipboardData,
@@ -44,6 +50,7 @@ pub enum ImageEditorAction {
 pub enum ImageSelection {
     None,
     Rect(Pos2, Pos2),
+    Mask(ImageMask),
     Fragment(Pos2, ImageFragment),
 }
 
@@ -60,6 +67,7 @@ impl ImageSelection {
                 let height = end.y - origin.y;
                 width.abs() == 0.0 || height.abs() == 0.0
             }
+            ImageSelection::Mask(mask) => mask.is_empty(),
             ImageSelection::Fragment(_, _) => false,
         }
     }
@@ -89,12 +97,50 @@ impl ImageSelection {
             ImageSelection::Rect(origin, end) => {
                 Some(Rect::from_min_max(origin.min(*end), origin.max(*end)))
             }
+            ImageSelection::Mask(mask) => mask.bounding_rect(),
             ImageSelection::Fragment(pos, frag) => {
                 Some(Rect::from_min_size(*pos, Vec2::new(frag.width() as f32, frag.height() as f32)))
             }
             ImageSelection::None => None,
         }
     }
+
+    pub fn contains(&self, pos: Pos2) -> bool {
+        match self {
+            ImageSelection::Mask(mask) => mask.get(pos.x.floor() as i32, pos.y.floor() as i32),
+            _ => self.get_rect().is_some_and(|rect| rect.contains(pos)),
+        }
+    }
+
+    /**
+     * Return the selected pixels of a non-floating selection.
+     */
+    pub fn get_mask(&self, width: u32, height: u32) -> Option<ImageMask> {
+        match self {
+            ImageSelection::Rect(..) => self.get_rect().map(|rect| ImageMask::from_rect(width, height, rect)),
+            ImageSelection::Mask(mask) => Some(mask.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ImageSelectionOp {
+    Replace,
+    Add,
+    Subtract,
+}
+
+impl ImageSelectionOp {
+    fn from_modifiers(modifiers: egui::Modifiers) -> Self {
+        if modifiers.shift {
+            ImageSelectionOp::Add
+        } else if modifiers.ctrl {
+            ImageSelectionOp::Subtract
+        } else {
+            ImageSelectionOp::Replace
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -103,6 +149,422 @@ pub enum ImageDrawingTool {
     Fill,
     Select,
     Collision,
+    Line,
+    Rect,
+    FilledRect,
+    Ellipse,
+    FilledEllipse,
+    MagicWand,
+    Lasso,
+    Polygon,
+    Offset,
+}
+
+impl ImageDrawingTool {
+    pub const SELECTIONS: &[ImageDrawingTool] = &[
+        ImageDrawingTool::MagicWand,
+        ImageDrawingTool::Lasso,
+        ImageDrawingTool::Polygon,
+    ];
+
+    pub const SHAPES: &[ImageDrawingTool] = &[
+        ImageDrawingTool::Line,
+        ImageDrawingTool::Rect,
+        ImageDrawingTool::FilledRect,
+        ImageDrawingTool::Ellipse,
+        ImageDrawingTool::FilledEllipse,
+    ];
+
+    pub fn shape_icon(&self) -> &'static str {
+        match self {
+            ImageDrawingTool::Offset => "✛",
+            ImageDrawingTool::Line => "/",
+            ImageDrawingTool::Rect => "□",
+            ImageDrawingTool::FilledRect => "■",
+            ImageDrawingTool::Ellipse => "○",
+            ImageDrawingTool::FilledEllipse => "●",
+            ImageDrawingTool::MagicWand => "✨",
+            ImageDrawingTool::Lasso => "➰",
+            ImageDrawingTool::Polygon => "⬠",
+            _ => "",
+        }
+    }
+
+    pub fn shape_name(&self) -> &'static str {
+        match self {
+            ImageDrawingTool::Offset => "Offset with wrap-around",
+            ImageDrawingTool::Line => "Line",
+            ImageDrawingTool::Rect => "Rectangle",
+            ImageDrawingTool::FilledRect => "Filled rectangle",
+            ImageDrawingTool::Ellipse => "Ellipse",
+            ImageDrawingTool::FilledEllipse => "Filled ellipse",
+            ImageDrawingTool::MagicWand => "Magic wand (right click selects the color in the whole image)",
+            ImageDrawingTool::Lasso => "Lasso select",
+            ImageDrawingTool::Polygon => "Polygon select (click the first point or right click to close)",
+            _ => "",
+        }
+    }
+
+    pub fn is_selection(&self) -> bool {
+        *self == ImageDrawingTool::Select || Self::SELECTIONS.contains(self)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImageBrushShape {
+    Square,
+    Round,
+}
+
+pub enum ImageBrushMenuAction {
+    None,
+    Changed,
+    Capture,
+}
+
+#[derive(Clone)]
+pub struct ImageBrush {
+    pub size: u32,
+    pub shape: ImageBrushShape,
+    pub pixel_perfect: bool,
+    pub custom: Option<ImagePixels>,
+}
+
+impl ImageBrush {
+    pub const MAX_SIZE: u32 = 16;
+
+    pub fn new() -> Self {
+        ImageBrush {
+            size: 1,
+            shape: ImageBrushShape::Square,
+            pixel_perfect: false,
+            custom: None,
+        }
+    }
+
+    fn is_single_pixel(&self) -> bool {
+        self.custom.is_none() && self.size <= 1
+    }
+
+    fn contains(&self, x: u32, y: u32) -> bool {
+        match self.shape {
+            ImageBrushShape::Square => true,
+            ImageBrushShape::Round => {
+                let r = self.size as f32 / 2.0;
+                let dx = x as f32 + 0.5 - r;
+                let dy = y as f32 + 0.5 - r;
+                dx * dx + dy * dy <= r * r + 0.5
+            }
+        }
+    }
+
+    /**
+     * Paint the brush centered at (x, y). Custom brushes are painted with
+     * their own colors if `brush_colors` is set, otherwise with `color`.
+     */
+    fn paint(&self, image: &mut impl ImageCollection, item: u32, (x, y): (i32, i32), color: u8, brush_colors: bool,
+             symmetry: &ImageSymmetry) -> bool {
+        let mut changed = false;
+        if let Some(pixels) = &self.custom {
+            let left = x - (pixels.width as i32 - 1) / 2;
+            let top = y - (pixels.height as i32 - 1) / 2;
+            for (i, &pixel) in pixels.data.iter().enumerate() {
+                if pixel == colors::TRANSPARENT { continue; }
+                let px = left + (i as u32 % pixels.width) as i32;
+                let py = top + (i as u32 / pixels.width) as i32;
+                changed |= symmetry.set_pixel(image, px, py, item, if brush_colors { pixel } else { color });
+            }
+        } else {
+            let size = self.size.max(1);
+            let left = x - (size as i32 - 1) / 2;
+            let top = y - (size as i32 - 1) / 2;
+            for by in 0..size {
+                for bx in 0..size {
+                    if self.contains(bx, by) {
+                        changed |= symmetry.set_pixel(image, left + bx as i32, top + by as i32, item, color);
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn show_menu(&mut self, ui: &mut egui::Ui, can_capture: bool) -> ImageBrushMenuAction {
+        let mut action = ImageBrushMenuAction::None;
+        let title = match (&self.custom, self.shape) {
+            (Some(pixels), _) => format!("Brush: {}x{}", pixels.width, pixels.height),
+            (None, ImageBrushShape::Square) => format!("Brush: {}■", self.size),
+            (None, ImageBrushShape::Round) => format!("Brush: {}●", self.size),
+        };
+        ui.menu_button(title, |ui| {
+            ui.add_enabled_ui(self.custom.is_none(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Size:");
+                    if ui.add(egui::DragValue::new(&mut self.size).range(1..=Self::MAX_SIZE)).changed() {
+                        action = ImageBrushMenuAction::Changed;
+                    }
+                });
+                for (shape, name) in [(ImageBrushShape::Square, "Square"), (ImageBrushShape::Round, "Round")] {
+                    if ui.radio_value(&mut self.shape, shape, name).changed() {
+                        action = ImageBrushMenuAction::Changed;
+                    }
+                }
+                if ui.checkbox(&mut self.pixel_perfect, "Pixel-perfect").on_hover_text("Remove L-shaped corners from 1-pixel strokes").changed() {
+                    action = ImageBrushMenuAction::Changed;
+                }
+            });
+            ui.separator();
+            if ui.add_enabled(can_capture, egui::Button::new("Capture from selection")).clicked() {
+                action = ImageBrushMenuAction::Capture;
+                ui.close();
+            }
+            if ui.add_enabled(self.custom.is_some(), egui::Button::new("Clear captured brush")).clicked() {
+                self.custom = None;
+                action = ImageBrushMenuAction::Changed;
+                ui.close();
+            }
+        });
+        action
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImageSymmetryMode {
+    None,
+    Horizontal,
+    Vertical,
+    Both,
+}
+
+impl ImageSymmetryMode {
+    pub const ALL: &[ImageSymmetryMode] = &[
+        ImageSymmetryMode::None,
+        ImageSymmetryMode::Horizontal,
+        ImageSymmetryMode::Vertical,
+        ImageSymmetryMode::Both,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImageSymmetryMode::None => "None",
+            ImageSymmetryMode::Horizontal => "Horizontal (left/right)",
+            ImageSymmetryMode::Vertical => "Vertical (top/bottom)",
+            ImageSymmetryMode::Both => "Both axes",
+        }
+    }
+
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            ImageSymmetryMode::None => "off",
+            ImageSymmetryMode::Horizontal => "H",
+            ImageSymmetryMode::Vertical => "V",
+            ImageSymmetryMode::Both => "H+V",
+        }
+    }
+
+    fn has_x_axis(&self) -> bool {
+        matches!(self, ImageSymmetryMode::Horizontal | ImageSymmetryMode::Both)
+    }
+
+    fn has_y_axis(&self) -> bool {
+        matches!(self, ImageSymmetryMode::Vertical | ImageSymmetryMode::Both)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ImageSymmetryAxis {
+    X,
+    Y,
+}
+
+/**
+ * Symmetry axes are stored in half pixels, so they can be placed
+ * between pixels or at the center of a pixel.
+ */
+pub struct ImageSymmetry {
+    pub mode: ImageSymmetryMode,
+    axis: Option<(i32, i32)>,
+    axis_x: i32,
+    axis_y: i32,
+    dragging_axis: Option<ImageSymmetryAxis>,
+}
+
+impl ImageSymmetry {
+    const AXIS_COLOR: egui::Color32 = egui::Color32::from_rgb(0xff, 0x00, 0xff);
+
+    pub fn new() -> Self {
+        ImageSymmetry {
+            mode: ImageSymmetryMode::None,
+            axis: None,
+            axis_x: 0,
+            axis_y: 0,
+            dragging_axis: None,
+        }
+    }
+
+    pub fn center_axis(&mut self) {
+        self.axis = None;
+    }
+
+    pub fn is_axis_centered(&self) -> bool {
+        self.axis.is_none()
+    }
+
+    fn update_axis(&mut self, width: u32, height: u32) {
+        let (x, y) = self.axis.unwrap_or((width as i32, height as i32));
+        self.axis_x = x.clamp(0, 2 * width as i32);
+        self.axis_y = y.clamp(0, 2 * height as i32);
+    }
+
+    /**
+     * Return the point (x, y) and its mirror images.
+     */
+    fn points(&self, x: i32, y: i32) -> Vec<(i32, i32)> {
+        let mx = self.axis_x - 1 - x;
+        let my = self.axis_y - 1 - y;
+        let mut points = vec![(x, y)];
+        let mut add_point = |point| if ! points.contains(&point) { points.push(point); };
+        if self.mode.has_x_axis() { add_point((mx, y)); }
+        if self.mode.has_y_axis() { add_point((x, my)); }
+        if self.mode == ImageSymmetryMode::Both { add_point((mx, my)); }
+        points
+    }
+
+    /**
+     * Return the mirror images of the shape with corners `start` and `end`.
+     */
+    fn shapes(&self, (x0, y0): (i32, i32), (x1, y1): (i32, i32)) -> Vec<((i32, i32), (i32, i32))> {
+        self.points(x0, y0).into_iter().zip(self.points(x1, y1)).collect()
+    }
+
+    fn set_pixel(&self, image: &mut impl ImageCollection, x: i32, y: i32, item: u32, color: u8) -> bool {
+        let mut changed = false;
+        for (px, py) in self.points(x, y) {
+            changed |= image.set_pixel(px, py, item, color);
+        }
+        changed
+    }
+
+    // Return the axis under the screen position `pos`, if any.
+    fn axis_at(&self, pos: Pos2, image_to_canvas: emath::RectTransform) -> Option<ImageSymmetryAxis> {
+        const GRAB_DIST: f32 = 4.0;
+        let axis_pos = image_to_canvas * Pos2::new(self.axis_x as f32 / 2.0, self.axis_y as f32 / 2.0);
+        if self.mode.has_x_axis() && (pos.x - axis_pos.x).abs() <= GRAB_DIST {
+            Some(ImageSymmetryAxis::X)
+        } else if self.mode.has_y_axis() && (pos.y - axis_pos.y).abs() <= GRAB_DIST {
+            Some(ImageSymmetryAxis::Y)
+        } else {
+            None
+        }
+    }
+
+    fn drag_axis(&mut self, axis: ImageSymmetryAxis, image_pos: Pos2) {
+        let (x, y) = self.axis.unwrap_or((self.axis_x, self.axis_y));
+        self.axis = match axis {
+            ImageSymmetryAxis::X => Some(((2.0 * image_pos.x).round() as i32, y)),
+            ImageSymmetryAxis::Y => Some((x, (2.0 * image_pos.y).round() as i32)),
+        };
+    }
+
+    fn draw_axes(&self, painter: &egui::Painter, image_to_canvas: emath::RectTransform, rect: Rect) {
+        let stroke = egui::Stroke::new(2.0, Self::AXIS_COLOR);
+        let axis_pos = image_to_canvas * Pos2::new(self.axis_x as f32 / 2.0, self.axis_y as f32 / 2.0);
+        if self.mode.has_x_axis() {
+            painter.vline(axis_pos.x, rect.y_range(), stroke);
+        }
+        if self.mode.has_y_axis() {
+            painter.hline(rect.x_range(), axis_pos.y, stroke);
+        }
+    }
+
+    pub fn show_menu(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.menu_button(format!("Mirror: {}", self.mode.short_name()), |ui| {
+            for &mode in ImageSymmetryMode::ALL {
+                if ui.radio_value(&mut self.mode, mode, mode.name()).changed() {
+                    changed = true;
+                }
+            }
+            ui.separator();
+            if ui.add_enabled(self.axis.is_some(), egui::Button::new("Center axis")).clicked() {
+                self.center_axis();
+                changed = true;
+                ui.close();
+            }
+        });
+        changed
+    }
+}
+
+pub struct ImageOnionSkin {
+    pub enabled: bool,
+    pub num_frames: u32,
+    pub loop_frames: Vec<Vec<u32>>,
+    pub loop_wraps: bool,
+}
+
+impl ImageOnionSkin {
+    pub const MAX_FRAMES: u32 = 4;
+
+    pub fn new() -> Self {
+        ImageOnionSkin {
+            enabled: false,
+            num_frames: 1,
+            loop_frames: Vec::new(),
+            loop_wraps: false,
+        }
+    }
+
+    /**
+     * Return the frames to display around `frame`, with their distance
+     * to it (negative for previous frames). If there are loop frames,
+     * the frames are taken from the first loop that contains `frame`.
+     */
+    fn frames_around(&self, frame: u32, num_items: u32) -> Vec<(u32, i32)> {
+        let mut frames = Vec::new();
+        let num = self.num_frames as i32;
+        if self.loop_frames.is_empty() {
+            for dist in (-num..=num).filter(|&d| d != 0) {
+                let index = frame as i32 + dist;
+                if index >= 0 && index < num_items as i32 {
+                    frames.push((index as u32, dist));
+                }
+            }
+        } else if let Some(sequence) = self.loop_frames.iter().find(|seq| seq.contains(&frame)) {
+            let len = sequence.len() as i32;
+            let pos = sequence.iter().position(|&f| f == frame).unwrap_or(0) as i32;
+            for dist in (-num..=num).filter(|&d| d != 0) {
+                let index = if self.loop_wraps { (pos + dist).rem_euclid(len) } else { pos + dist };
+                if index >= 0 && index < len && sequence[index as usize] != frame && sequence[index as usize] < num_items {
+                    frames.push((sequence[index as usize], dist));
+                }
+            }
+        }
+        // paint the farthest frames first
+        frames.sort_by_key(|&(_, dist)| -dist.abs());
+        frames
+    }
+
+    pub fn show_menu(&mut self, ui: &mut egui::Ui, loops: &[((data_asset::DataAssetId, usize), String)],
+                     selected_loop: &mut Option<(data_asset::DataAssetId, usize)>) {
+        let title = if self.enabled { format!("Onion: {}", self.num_frames) } else { "Onion: off".to_owned() };
+        ui.menu_button(title, |ui| {
+            ui.checkbox(&mut self.enabled, "Show onion skin");
+            ui.add_enabled_ui(self.enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Frames:");
+                    ui.add(egui::DragValue::new(&mut self.num_frames).range(1..=Self::MAX_FRAMES));
+                });
+                if ! loops.is_empty() {
+                    ui.separator();
+                    ui.radio_value(selected_loop, None, "Adjacent frames");
+                    for (key, name) in loops {
+                        ui.radio_value(selected_loop, Some(*key), name);
+                    }
+                }
+            });
+        });
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -136,6 +598,13 @@ impl ImageDisplay {
     }
 }
 
+// Undo targets with the same (non-zero) group were created by a single
+// operation on multiple frames, and are undone/redone together.
+struct ImageUndoTarget {
+    frag: ImageFragment,
+    group: u32,
+}
+
 pub struct ImageEditorWidget<ImageAsset> {
     pub display: ImageDisplay,
     pub selection: ImageSelection,
@@ -145,16 +614,28 @@ pub struct ImageEditorWidget<ImageAsset> {
     pub selection_enabled: bool,
     pub hover_pos: Vec2,
     pub zoom: WidgetZoom,
+    pub brush: ImageBrush,
+    pub onion_skin: ImageOnionSkin,
+    pub symmetry: ImageSymmetry,
+    pub reference: ReferenceImage,
     last_zoom_level: f32,
     scroll: Vec2,
     tool: ImageDrawingTool,
     selected_image: u32,
-    undo_targets: HashMap<u32, VecDeque<ImageFragment>>,
-    redo_targets: HashMap<u32, VecDeque<ImageFragment>>,
+    undo_targets: HashMap<u32, VecDeque<ImageUndoTarget>>,
+    redo_targets: HashMap<u32, VecDeque<ImageUndoTarget>>,
+    last_undo_group: u32,
     image_changed: bool,
     drop_selection_next_show: bool,
     drag_mouse_origin: Pos2,
     drag_frag_origin: Pos2,
+    shape_start: (i32, i32),
+    shape_drawing: bool,
+    stroke_points: Vec<(i32, i32)>,
+    selection_op: ImageSelectionOp,
+    selection_base: Option<ImageMask>,
+    lasso_points: Vec<Pos2>,
+    offset_last_pos: (i32, i32),
     tool_mouse_down: bool,
     _marker: std::marker::PhantomData<ImageAsset>,
 }
@@ -176,8 +657,20 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             pick_right_color: None,
             drag_mouse_origin: Pos2::ZERO,
             drag_frag_origin: Pos2::ZERO,
+            shape_start: (0, 0),
+            shape_drawing: false,
+            stroke_points: Vec::new(),
+            selection_op: ImageSelectionOp::Replace,
+            selection_base: None,
+            lasso_points: Vec::new(),
+            offset_last_pos: (0, 0),
+            brush: ImageBrush::new(),
+            onion_skin: ImageOnionSkin::new(),
+            symmetry: ImageSymmetry::new(),
+            reference: ReferenceImage::new(),
             undo_targets: HashMap::new(),
             redo_targets: HashMap::new(),
+            last_undo_group: 0,
             selection_enabled: true,
             image_changed: false,
             drop_selection_next_show: false,
@@ -223,18 +716,37 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
         self.image_changed
     }
 
-    pub fn set_undo_target(&mut self, image: &ImageAsset) {
-        if let Some(undo_target) = image.copy_fragment(image.get_asset_id(), self.selected_image, ImageRect::from_image_item(image)) {
-            let image_redo_targets = self.redo_targets.entry(self.selected_image).or_default();
-            let image_undo_targets = self.undo_targets.entry(self.selected_image).or_default();
+    fn push_undo_target(&mut self, image: &ImageAsset, frame: u32, group: u32) {
+        if let Some(frag) = image.copy_fragment(image.get_asset_id(), frame, ImageRect::from_image_item(image)) {
+            let image_redo_targets = self.redo_targets.entry(frame).or_default();
+            let image_undo_targets = self.undo_targets.entry(frame).or_default();
             image_redo_targets.clear();
             if image_undo_targets.len() >= Self::MAX_UNDO_TARGETS {
                 image_undo_targets.pop_front();
             }
-            image_undo_targets.push_back(undo_target);
+            image_undo_targets.push_back(ImageUndoTarget { frag, group });
+        }
+    }
+
+    pub fn set_undo_target(&mut self, image: &ImageAsset) {
+        self.push_undo_target(image, self.selected_image, 0);
+    }
+
+    /**
+     * Set an undo target for each of the given frames, so that undoing
+     * in any of them undoes all of them.
+     */
+    pub fn set_undo_target_for_frames(&mut self, image: &ImageAsset, frames: &[u32]) {
+        self.last_undo_group += 1;
+        for &frame in frames {
+            self.push_undo_target(image, frame, self.last_undo_group);
         }
     }
 
+    fn last_undo_frag(&self) -> Option<&ImageFragment> {
+        self.undo_targets.get(&self.selected_image).and_then(|targets| targets.back()).map(|target| &target.frag)
+    }
+
     pub fn clear_undo_history(&mut self) {
         self.undo_targets.clear();
         self.redo_targets.clear();
@@ -256,8 +768,54 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             }
     }
 
+    fn copy_masked_fragment(&self, image: &ImageAsset, mask: &ImageMask) -> Option<ImageFragment> {
+        let image_rect = ImageRect::from_rect(mask.bounding_rect()?, image);
+        let mut frag = image.copy_fragment(image.get_asset_id(), self.selected_image, image_rect)?;
+        for y in 0..image_rect.height {
+            for x in 0..image_rect.width {
+                if ! mask.get((image_rect.x + x) as i32, (image_rect.y + y) as i32) {
+                    frag.pixels.data[(y * image_rect.width + x) as usize] = colors::TRANSPARENT;
+                }
+            }
+        }
+        frag.mask = Some(mask.sub_mask(image_rect.x, image_rect.y, image_rect.width, image_rect.height));
+        Some(frag)
+    }
+
+    /**
+     * Apply to the mask of a fragment (if any) the transformation applied
+     * to its pixels.
+     */
+    fn transform_frag_mask(frag: &ImageFragment, transform: impl FnOnce(ImageFragment) -> Option<ImagePixels>) -> Option<ImageMask> {
+        let mask = frag.mask.as_ref()?;
+        let pixels = transform(ImageFragment::from_pixels(frag.id, mask.to_pixels()))?;
+        Some(ImageMask::from_pixels(&pixels))
+    }
+
+    fn lift_mask(&mut self, image: &mut ImageAsset, mask: &ImageMask, bg_color: u8) {
+        self.selection = ImageSelection::None;
+        if let Some(sel_rect) = mask.bounding_rect() && let Some(frag) = self.copy_masked_fragment(image, mask) {
+            self.set_undo_target(image);
+            let bg_color = if self.display.is_transparent() { colors::TRANSPARENT } else { bg_color };
+            for y in 0..mask.height as i32 {
+                for x in 0..mask.width as i32 {
+                    if mask.get(x, y) {
+                        image.set_pixel(x, y, self.selected_image, bg_color);
+                    }
+                }
+            }
+            self.selection = ImageSelection::Fragment(sel_rect.min, frag);
+            self.image_changed = true;
+        }
+    }
+
     fn lift_selection(&mut self, image: &mut ImageAsset, bg_color: u8) {
         if self.selection.is_floating() { return; } // already lifted
+        if let ImageSelection::Mask(mask) = &self.selection {
+            let mask = mask.clone();
+            self.lift_mask(image, &mask, bg_color);
+            return;
+        }
 
         if let Some(sel_rect) = self.selection.get_rect() && sel_rect.is_positive() {
             self.set_undo_target(image);
@@ -288,28 +846,91 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
         }
     }
 
-    pub fn undo(&mut self, image: &mut ImageAsset) {
-        let image_undo_targets = self.undo_targets.entry(self.selected_image).or_default();
-        let image_redo_targets = self.redo_targets.entry(self.selected_image).or_default();
-        if let Some(undo_image) = image_undo_targets.pop_back() &&
-            let Some(redo_image) = image.copy_fragment(image.get_asset_id(), self.selected_image, ImageRect::from_image_item(image)) {
-                image_redo_targets.push_back(redo_image);
-                image.paste_fragment(self.selected_image, 0, 0, &undo_image, false);
-                self.image_changed = true;
-                self.selection = ImageSelection::None;
+    // Move the last target of `frame` from `from` to `to`, restoring it
+    // in the image. Returns the group of the target.
+    fn swap_undo_target(image: &mut ImageAsset, frame: u32,
+                        from: &mut HashMap<u32, VecDeque<ImageUndoTarget>>,
+                        to: &mut HashMap<u32, VecDeque<ImageUndoTarget>>) -> Option<u32> {
+        let target = from.entry(frame).or_default().pop_back()?;
+        let frag = image.copy_fragment(image.get_asset_id(), frame, ImageRect::from_image_item(image))?;
+        to.entry(frame).or_default().push_back(ImageUndoTarget { frag, group: target.group });
+        image.paste_fragment(frame, 0, 0, &target.frag, false);
+        Some(target.group)
+    }
+
+    fn swap_undo_target_group(&mut self, image: &mut ImageAsset, redo: bool) {
+        let (from, to) = if redo {
+            (&mut self.redo_targets, &mut self.undo_targets)
+        } else {
+            (&mut self.undo_targets, &mut self.redo_targets)
+        };
+        let Some(group) = Self::swap_undo_target(image, self.selected_image, from, to) else { return; };
+        if group != 0 {
+            let frames = from.iter().filter_map(|(&frame, targets)| {
+                targets.back().is_some_and(|target| target.group == group).then_some(frame)
+            }).collect::<Vec<_>>();
+            for frame in frames {
+                Self::swap_undo_target(image, frame, from, to);
             }
+        }
+        self.image_changed = true;
+        self.selection = ImageSelection::None;
+    }
+
+    pub fn undo(&mut self, image: &mut ImageAsset) {
+        self.swap_undo_target_group(image, false);
     }
 
     pub fn redo(&mut self, image: &mut ImageAsset) {
-        let image_undo_targets = self.undo_targets.entry(self.selected_image).or_default();
-        let image_redo_targets = self.redo_targets.entry(self.selected_image).or_default();
-        if let Some(redo_image) = image_redo_targets.pop_back() &&
-            let Some(undo_image) = image.copy_fragment(image.get_asset_id(), self.selected_image, ImageRect::from_image_item(image)) {
-                image_undo_targets.push_back(undo_image);
-                image.paste_fragment(self.selected_image, 0, 0, &redo_image, false);
-                self.image_changed = true;
-                self.selection = ImageSelection::None;
+        self.swap_undo_target_group(image, true);
+    }
+
+    /**
+     * Replace colors in the given frames according to `color_map`, with
+     * a single undo step for all changed frames.
+     */
+    pub fn remap_colors(&mut self, image: &mut ImageAsset, frames: std::ops::Range<u32>, color_map: &[u8; 256]) -> bool {
+        self.drop_selection(image);
+        let changed_frames = frames.filter(|&frame| {
+            frame < image.num_items() && image.item_data(frame).iter().any(|&pixel| color_map[pixel as usize] != pixel)
+        }).collect::<Vec<_>>();
+        if changed_frames.is_empty() { return false; }
+
+        self.set_undo_target_for_frames(image, &changed_frames);
+        for &frame in &changed_frames {
+            image.remap_colors(frame, color_map);
+        }
+        self.image_changed = true;
+        true
+    }
+
+    /**
+     * Replace the colors of the selected pixels of the current image.
+     */
+    pub fn remap_selection_colors(&mut self, image: &mut ImageAsset, color_map: &[u8; 256]) -> bool {
+        if let ImageSelection::Fragment(_, frag) = &mut self.selection {
+            let width = frag.pixels.width as usize;
+            for (index, pixel) in frag.pixels.data.iter_mut().enumerate() {
+                if frag.mask.as_ref().is_none_or(|mask| mask.get((index % width) as i32, (index / width) as i32)) {
+                    *pixel = color_map[*pixel as usize];
+                }
             }
+            frag.changed = true;
+            return true;
+        }
+
+        let Some(mask) = self.selection.get_mask(image.width(), image.height()) else { return false; };
+        self.set_undo_target(image);
+        for y in 0..mask.height as i32 {
+            for x in 0..mask.width as i32 {
+                if mask.get(x, y) {
+                    let color = image.get_pixel(x, y, self.selected_image);
+                    image.set_pixel(x, y, self.selected_image, color_map[color as usize]);
+                }
+            }
+        }
+        self.image_changed = true;
+        true
     }
 
     pub fn delete_selection(&mut self, image: &mut ImageAsset, fill_color: u8) {
@@ -319,20 +940,20 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
 
     pub fn drop_selection(&mut self, image: &mut ImageAsset) {
         if let ImageSelection::Fragment(pos, frag) = &self.selection {
-            let transparent = self.display.is_transparent();
-            image.paste_fragment(self.selected_image, pos.x as i32, pos.y as i32, frag, transparent);
+            image.paste_fragment(self.selected_image, pos.x as i32, pos.y as i32, frag, self.display.is_transparent());
             self.image_changed = true;
         }
         self.selection = ImageSelection::None;
     }
 
     pub fn vflip(&mut self, image: &mut ImageAsset, bg_color: u8) {
-        if matches!(self.selection, ImageSelection::Rect(..)) {
+        if matches!(self.selection, ImageSelection::Rect(..) | ImageSelection::Mask(..)) {
             self.lift_selection(image, bg_color);
         }
 
         if let ImageSelection::Fragment(_, frag) = &mut self.selection {
             frag.v_flip(0);
+            frag.mask = Self::transform_frag_mask(frag, |mut mask_frag| { mask_frag.v_flip(0); Some(mask_frag.pixels) });
             frag.changed = true;
         } else {
             image.v_flip(self.selected_image);
@@ -341,12 +962,13 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
     }
 
     pub fn hflip(&mut self, image: &mut ImageAsset, bg_color: u8) {
-        if matches!(self.selection, ImageSelection::Rect(..)) {
+        if matches!(self.selection, ImageSelection::Rect(..) | ImageSelection::Mask(..)) {
             self.lift_selection(image, bg_color);
         }
 
         if let ImageSelection::Fragment(_, frag) = &mut self.selection {
             frag.h_flip(0);
+            frag.mask = Self::transform_frag_mask(frag, |mut mask_frag| { mask_frag.h_flip(0); Some(mask_frag.pixels) });
             frag.changed = true;
         } else {
             image.h_flip(self.selected_image);
@@ -363,13 +985,16 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
                 );
                 self.lift_selection(image, bg_color);
             }
-        } else if matches!(self.selection, ImageSelection::Rect(..)) {
-            self.lift_selection(image, bg_color);  // float selected rectangle to rotate
+        } else if matches!(self.selection, ImageSelection::Rect(..) | ImageSelection::Mask(..)) {
+            self.lift_selection(image, bg_color);  // float selected area to rotate
         }
 
         match &self.selection {
             ImageSelection::Fragment(pos, frag) => {
-                if let Some(rot_frag) = frag.rotate(image.get_asset_id(), 0, rotation) {
+                if let Some(mut rot_frag) = frag.rotate(image.get_asset_id(), 0, rotation) {
+                    rot_frag.mask = Self::transform_frag_mask(frag, |mask_frag| {
+                        mask_frag.rotate(mask_frag.id, 0, rotation).map(|rot_mask| rot_mask.pixels)
+                    });
                     let rot_pos = Pos2::new(
                         pos.x + ((frag.pixels.width as f32 - frag.pixels.height as f32) / 2.0).round_ties_even(),
                         pos.y + ((frag.pixels.height as f32 - frag.pixels.width as f32) / 2.0).round_ties_even(),
@@ -385,17 +1010,47 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
         }
     }
 
+    pub fn transform(&mut self, image: &mut ImageAsset, transform: ImageTransform, bg_color: u8) {
+        if self.selection.is_empty() {  // empty selection: float the whole image
+            self.tool = ImageDrawingTool::Select;
+            self.selection = ImageSelection::Rect(
+                Pos2::ZERO, Pos2::new(image.width() as f32, image.height() as f32)
+            );
+        }
+        self.lift_selection(image, bg_color);
+
+        if let ImageSelection::Fragment(pos, frag) = &self.selection {
+            let empty_color = if self.display.is_transparent() || frag.mask.is_some() { colors::TRANSPARENT } else { bg_color };
+            let pixels = transform.apply(&frag.pixels, empty_color);
+            let (dx, dy) = transform.offset(frag.pixels.width, frag.pixels.height, pixels.width, pixels.height);
+            let pos = Pos2::new(pos.x + dx as f32, pos.y + dy as f32);
+            let mut new_frag = ImageFragment::from_pixels(image.get_asset_id(), pixels);
+            // the mask needs a non-zero color to select the added pixels
+            let mask_transform = match transform {
+                ImageTransform::Outline(_) => ImageTransform::Outline(1),
+                ImageTransform::DropShadow(_) => ImageTransform::DropShadow(1),
+                _ => transform,
+            };
+            new_frag.mask = Self::transform_frag_mask(frag, |mask_frag| Some(mask_transform.apply(&mask_frag.pixels, 0)));
+            self.selection = ImageSelection::Fragment(pos, new_frag);
+        }
+    }
+
     pub fn get_tool(&self) -> ImageDrawingTool {
         self.tool
     }
 
     pub fn set_tool(&mut self, tool: ImageDrawingTool) {
         self.set_tool_without_dropping_selection(tool);
-        self.drop_selection_next_show = true;
+        // keep selected areas for selection tools and for filling inside them
+        if self.selection.is_floating() || ! (tool.is_selection() || tool == ImageDrawingTool::Fill) {
+            self.drop_selection_next_show = true;
+        }
     }
 
     pub fn set_tool_without_dropping_selection(&mut self, tool: ImageDrawingTool) {
         self.tool = tool;
+        self.lasso_points.clear();
     }
 
     pub fn get_selected_image(&self) -> u32 {
@@ -440,14 +1095,22 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
         let mouse_pos = Rect::from_min_size(Pos2::ZERO, image.get_item_size()).clamp(mouse_pos);
         if resp.drag_started() {
             self.drag_mouse_origin = mouse_pos;
+            if ! self.selection.is_floating() && ImageSelectionOp::from_modifiers(resp.ctx.input(|i| i.modifiers)) != ImageSelectionOp::Replace {
+                // add or subtract a rectangle from the current selection
+                self.begin_selection(image, resp);
+                return;
+            }
+            self.selection_base = None;
             match self.selection {
-                ImageSelection::Rect(..) => {
-                    if let Some(sel_rect) = self.selection.get_rect() && sel_rect.contains(orig_mouse_pos) {
-                        // lift fragment for the selected rectangle
+                ImageSelection::Rect(..) | ImageSelection::Mask(..) => {
+                    if self.selection.contains(orig_mouse_pos) {
+                        // lift fragment for the selected area
                         self.lift_selection(image, colors.1);
 
                         // prepare to move it
-                        self.drag_frag_origin = sel_rect.min;
+                        if let Some(sel_rect) = self.selection.get_rect() {
+                            self.drag_frag_origin = sel_rect.min;
+                        }
                     } else {
                         self.selection = ImageSelection::None;
                     }
@@ -469,12 +1132,120 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             self.selection = if let Some((_, frag)) = self.selection.take_fragment() {
                 let pos = (self.drag_frag_origin + (mouse_pos - self.drag_mouse_origin)).round();
                 ImageSelection::Fragment(pos, frag)
+            } else if self.selection_base.is_some() {
+                let rect = Rect::from_two_pos(self.drag_mouse_origin.round(), mouse_pos.round());
+                self.combine_selection(ImageMask::from_rect(image.width(), image.height(), rect))
             } else {
                 ImageSelection::Rect(self.drag_mouse_origin.round(), mouse_pos.round())
             };
         }
     }
 
+    /**
+     * Start a selection that may be combined with the current one,
+     * depending on the keyboard modifiers.
+     */
+    fn begin_selection(&mut self, image: &mut ImageAsset, resp: &egui::Response) {
+        if self.selection.is_floating() {
+            self.drop_selection(image);
+        }
+        self.selection_op = ImageSelectionOp::from_modifiers(resp.ctx.input(|i| i.modifiers));
+        self.selection_base = Some(self.selection.get_mask(image.width(), image.height())
+                                   .unwrap_or_else(|| ImageMask::new(image.width(), image.height())));
+    }
+
+    fn combine_selection(&self, mask: ImageMask) -> ImageSelection {
+        let mask = match (self.selection_op, &self.selection_base) {
+            (ImageSelectionOp::Add, Some(base)) => {
+                let mut base = base.clone();
+                base.add(&mask);
+                base
+            }
+            (ImageSelectionOp::Subtract, Some(base)) => {
+                let mut base = base.clone();
+                base.subtract(&mask);
+                base
+            }
+            _ => mask,
+        };
+        if mask.is_empty() {
+            ImageSelection::None
+        } else {
+            ImageSelection::Mask(mask)
+        }
+    }
+
+    fn handle_magic_wand_mouse(&mut self, x: i32, y: i32, image: &mut ImageAsset, resp: &egui::Response) {
+        if ! resp.drag_started() { return; }
+
+        let contiguous = ! resp.dragged_by(egui::PointerButton::Secondary);
+        self.begin_selection(image, resp);
+        let mask = ImageMask::from_color(image, self.selected_image, (x, y), contiguous, None);
+        self.selection = self.combine_selection(mask);
+    }
+
+    fn handle_lasso_mouse(&mut self, mouse_pos: Pos2, image: &mut ImageAsset, resp: &egui::Response) {
+        let mouse_pos = Rect::from_min_size(Pos2::ZERO, image.get_item_size()).clamp(mouse_pos);
+        if resp.drag_started() {
+            self.begin_selection(image, resp);
+            self.lasso_points.clear();
+        }
+        if resp.dragged_by(egui::PointerButton::Primary) && self.lasso_points.last() != Some(&mouse_pos) {
+            self.lasso_points.push(mouse_pos);
+        }
+    }
+
+    fn handle_polygon_mouse(&mut self, mouse_pos: Pos2, image: &mut ImageAsset, resp: &egui::Response) {
+        if ! resp.drag_started() { return; }
+
+        let mouse_pos = Rect::from_min_size(Pos2::ZERO, image.get_item_size()).clamp(mouse_pos).round();
+        let close_distance = 6.0 / self.last_zoom_level.max(1.0);
+        let closing = resp.dragged_by(egui::PointerButton::Secondary) ||
+            (self.lasso_points.len() >= 3 && self.lasso_points[0].distance(mouse_pos) <= close_distance);
+        if closing {
+            self.finish_lasso(image);
+        } else {
+            if self.lasso_points.is_empty() {
+                self.begin_selection(image, resp);
+            }
+            self.lasso_points.push(mouse_pos);
+        }
+    }
+
+    fn finish_lasso(&mut self, image: &ImageAsset) {
+        if self.lasso_points.len() >= 3 {
+            let mask = ImageMask::from_polygon(image.width(), image.height(), &self.lasso_points);
+            self.selection = self.combine_selection(mask);
+        }
+        self.lasso_points.clear();
+    }
+
+    fn handle_offset_mouse(&mut self, x: i32, y: i32, image: &mut ImageAsset, resp: &egui::Response) {
+        if resp.drag_started() {
+            self.set_undo_target(image);
+            self.offset_last_pos = (x, y);
+        }
+        let (dx, dy) = (x - self.offset_last_pos.0, y - self.offset_last_pos.1);
+        if resp.dragged_by(egui::PointerButton::Primary) && (dx != 0 || dy != 0) {
+            image.shift_pixels(self.selected_image, dx, dy, true, colors::TRANSPARENT);
+            self.offset_last_pos = (x, y);
+            self.image_changed = true;
+        }
+    }
+
+    fn fill_in_selection(&mut self, x: i32, y: i32, image: &mut ImageAsset, color: u8, limit: &ImageMask) -> bool {
+        let fill_mask = ImageMask::from_color(image, self.selected_image, (x, y), true, Some(limit));
+        let mut changed = false;
+        for py in 0..fill_mask.height as i32 {
+            for px in 0..fill_mask.width as i32 {
+                if fill_mask.get(px, py) && image.set_pixel(px, py, self.selected_image, color) {
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
     fn handle_collision_mouse(&mut self, mouse_pos: Pos2, image: &mut ImageAsset, resp: &egui::Response) {
         if ! self.display.has_bits(ImageDisplay::COLLISION) { return; } // don't edit collision while it's not shown
 
@@ -517,6 +1288,108 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
         false
     }
 
+    pub fn capture_brush(&mut self, image: &ImageAsset) -> bool {
+        let pixels = match &self.selection {
+            ImageSelection::Fragment(_, frag) => Some(frag.pixels.clone()),
+            ImageSelection::Rect(..) => self.selection.get_rect().filter(|rect| rect.is_positive()).and_then(|rect| {
+                image.copy_fragment(image.get_asset_id(), self.selected_image, ImageRect::from_rect(rect, image))
+            }).map(|frag| frag.take_pixels()),
+            ImageSelection::Mask(mask) => self.copy_masked_fragment(image, mask).map(|frag| frag.take_pixels()),
+            ImageSelection::None => None,
+        };
+        if let Some(pixels) = pixels {
+            self.brush.custom = Some(pixels);
+            self.set_tool(ImageDrawingTool::Pencil);
+            true
+        } else {
+            false
+        }
+    }
+
+    // If the last 3 points of the stroke make an L, restore the corner pixel.
+    fn remove_stroke_corner(&mut self, image: &mut ImageAsset) {
+        let [.., (ax, ay), (bx, by), (cx, cy)] = self.stroke_points[..] else { return; };
+        let diagonal = (ax - cx).abs() == 1 && (ay - cy).abs() == 1;
+        let corner = (bx == ax || by == ay) && (bx == cx || by == cy);
+        if ! (diagonal && corner) { return; }
+
+        if let Some(undo_target) = self.last_undo_frag() {
+            for (px, py) in self.symmetry.points(bx, by) {
+                let color = undo_target.get_pixel(px, py, 0);
+                image.set_pixel(px, py, self.selected_image, color);
+            }
+            let len = self.stroke_points.len();
+            self.stroke_points.remove(len - 2);
+        }
+    }
+
+    fn pencil_stroke_to(&mut self, x: i32, y: i32, image: &mut ImageAsset, color: u8, brush_colors: bool) {
+        let points = match self.stroke_points.last() {
+            Some(&(last_x, last_y)) if (last_x, last_y) == (x, y) => { return; }
+            Some(&last) => line_points(last, (x, y)).into_iter().skip(1).collect(),
+            None => vec![(x, y)],
+        };
+        let pixel_perfect = self.brush.pixel_perfect && self.brush.is_single_pixel();
+        for (px, py) in points {
+            self.brush.paint(image, self.selected_image, (px, py), color, brush_colors, &self.symmetry);
+            self.stroke_points.push((px, py));
+            if pixel_perfect {
+                self.remove_stroke_corner(image);
+            }
+        }
+        self.image_changed = true;
+    }
+
+    fn constrain_shape_end(&self, (x, y): (i32, i32)) -> (i32, i32) {
+        let (x0, y0) = self.shape_start;
+        let (dx, dy) = (x - x0, y - y0);
+        if self.tool == ImageDrawingTool::Line {
+            // horizontal, vertical or 45°
+            if dx.abs() > 2 * dy.abs() {
+                (x, y0)
+            } else if dy.abs() > 2 * dx.abs() {
+                (x0, y)
+            } else {
+                let len = dx.abs().max(dy.abs());
+                (x0 + len * dx.signum(), y0 + len * dy.signum())
+            }
+        } else {
+            // square or circle
+            let len = dx.abs().max(dy.abs());
+            let sx = if dx < 0 { -1 } else { 1 };
+            let sy = if dy < 0 { -1 } else { 1 };
+            (x0 + len * sx, y0 + len * sy)
+        }
+    }
+
+    fn handle_shape_mouse(&mut self, x: i32, y: i32, image: &mut ImageAsset, resp: &egui::Response, colors: (u8, u8)) {
+        let Some(color) = Self::get_selected_color_for_click(resp, colors) else { return; };
+        if resp.drag_started() {
+            self.set_undo_target(image);
+            self.shape_start = (x, y);
+            self.shape_drawing = true;
+        } else if ! self.shape_drawing {
+            return;  // the drag didn't start a shape (e.g. it started picking a color)
+        } else if let Some(undo_target) = self.last_undo_frag() {
+            // restore the image from before the shape was started to redraw the shape
+            image.paste_fragment(self.selected_image, 0, 0, undo_target, false);
+        }
+
+        let shift_held = resp.ctx.input(|i| i.modifiers.shift);
+        let end = if shift_held { self.constrain_shape_end((x, y)) } else { (x, y) };
+        for (start, end) in self.symmetry.shapes(self.shape_start, end) {
+            match self.tool {
+                ImageDrawingTool::Line => image.draw_line(self.selected_image, start, end, color),
+                ImageDrawingTool::Rect => image.draw_rect(self.selected_image, start, end, false, color),
+                ImageDrawingTool::FilledRect => image.draw_rect(self.selected_image, start, end, true, color),
+                ImageDrawingTool::Ellipse => image.draw_ellipse(self.selected_image, start, end, false, color),
+                ImageDrawingTool::FilledEllipse => image.draw_ellipse(self.selected_image, start, end, true, color),
+                _ => {}
+            }
+        }
+        self.image_changed = true;
+    }
+
     fn handle_mouse(&mut self, mouse_pos: Pos2, image: &mut ImageAsset, resp: &egui::Response, colors: (u8, u8)) {
         let x = mouse_pos.x.floor() as i32;
         let y = mouse_pos.y.floor() as i32;
@@ -528,11 +1401,14 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
                 if ctrl_held {
                     self.pick_color(x, y, image, resp);
                 } else {
-                    if resp.drag_started() { self.set_undo_target(image); }
-                    if let Some(color) = Self::get_selected_color_for_click(resp, colors) &&
-                        image.set_pixel(x, y, self.selected_image, color) {
-                            self.image_changed = true;
-                        }
+                    if resp.drag_started() {
+                        self.set_undo_target(image);
+                        self.stroke_points.clear();
+                    }
+                    if let Some(color) = Self::get_selected_color_for_click(resp, colors) {
+                        let brush_colors = resp.dragged_by(egui::PointerButton::Primary);
+                        self.pencil_stroke_to(x, y, image, color, brush_colors);
+                    }
                 }
             }
 
@@ -541,10 +1417,18 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
                     self.pick_color(x, y, image, resp);
                 } else {
                     if resp.drag_started() { self.set_undo_target(image); }
-                    if let Some(color) = Self::get_selected_color_for_click(resp, colors) &&
-                        image.flood_fill(x, y, self.selected_image, color) {
-                            self.image_changed = true;
+                    if let Some(color) = Self::get_selected_color_for_click(resp, colors) {
+                        let limit = self.selection.get_mask(image.width(), image.height());
+                        for (px, py) in self.symmetry.points(x, y) {
+                            let changed = match &limit {
+                                Some(limit) => self.fill_in_selection(px, py, image, color, limit),
+                                None => image.flood_fill(px, py, self.selected_image, color),
+                            };
+                            if changed {
+                                self.image_changed = true;
+                            }
                         }
+                    }
                 }
             }
 
@@ -552,9 +1436,37 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
                 self.handle_selection_mouse(mouse_pos, image, resp, colors);
             }
 
+            ImageDrawingTool::MagicWand => {
+                self.handle_magic_wand_mouse(x, y, image, resp);
+            }
+
+            ImageDrawingTool::Lasso => {
+                self.handle_lasso_mouse(mouse_pos, image, resp);
+            }
+
+            ImageDrawingTool::Polygon => {
+                self.handle_polygon_mouse(mouse_pos, image, resp);
+            }
+
+            ImageDrawingTool::Offset => {
+                self.handle_offset_mouse(x, y, image, resp);
+            }
+
             ImageDrawingTool::Collision => {
                 self.handle_collision_mouse(mouse_pos, image, resp);
             }
+
+            ImageDrawingTool::Line |
+            ImageDrawingTool::Rect |
+            ImageDrawingTool::FilledRect |
+            ImageDrawingTool::Ellipse |
+            ImageDrawingTool::FilledEllipse => {
+                if ctrl_held {
+                    self.pick_color(x, y, image, resp);
+                } else {
+                    self.handle_shape_mouse(x, y, image, resp, colors);
+                }
+            }
         }
     }
 
@@ -570,6 +1482,11 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
                     wc.image_clipboard = ImageClipboardData::Image(frag.take_pixels());
                 }
             }
+            ImageSelection::Mask(mask) => {
+                if let Some(frag) = self.copy_masked_fragment(image, mask) {
+                    wc.image_clipboard = ImageClipboardData::Image(frag.take_pixels());
+                }
+            }
             ImageSelection::Fragment(_, frag) => {
                 wc.image_clipboard = ImageClipboardData::Image(frag.pixels.clone());
             }
@@ -636,7 +1553,7 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
                 let width = image.width() as f32;
                 let height = image.height() as f32;
                 match self.selection {
-                    ImageSelection::None => {
+                    ImageSelection::None | ImageSelection::Mask(..) => {
                         self.selection = ImageSelection::Rect(Pos2::ZERO, Pos2::new(width, height));
                     }
                     ImageSelection::Rect(origin, end) => {
@@ -652,6 +1569,12 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             }
         }
 
+        let esc = egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Escape);
+        if ! self.lasso_points.is_empty() && ui.input_mut(|i| i.consume_shortcut(&esc)) {
+            self.lasso_points.clear();
+            return ImageEditorAction::None;
+        }
+
         let del = egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Delete);
         if ui.input_mut(|i| i.consume_shortcut(&del)) {
             self.delete_selection(image, fill_color);
@@ -719,6 +1642,21 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
         }
     }
 
+    fn draw_onion_skin(&self, ui: &mut egui::Ui, wc: &mut WindowContext, paint_image_rect: Rect, image: &ImageAsset) {
+        let frames = self.onion_skin.frames_around(self.selected_image, image.num_items());
+        if frames.is_empty() { return; }
+        let slot = image.texture_slot(true, false);
+        let texture = image.texture(wc.tex_man, ui.ctx(), slot);
+        let num_frames = self.onion_skin.num_frames as f32;
+        for (frame, dist) in frames {
+            let color = if dist < 0 { wc.settings.onion_skin_prev_color } else { wc.settings.onion_skin_next_color };
+            let fade = (num_frames + 1.0 - dist.abs() as f32) / num_frames;
+            let alpha = (wc.settings.onion_skin_opacity as f32 / 100.0 * fade * 255.0).clamp(0.0, 255.0) as u8;
+            let tint = egui::Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), alpha);
+            Image::from_texture((texture.id(), image.get_item_size())).uv(image.get_item_uv(frame)).tint(tint).paint_at(ui, paint_image_rect);
+        }
+    }
+
     pub fn set_zoom(&mut self, zoom: f32, old_zoom: f32, canvas_size: Vec2, zoom_center: Vec2, image: &ImageAsset) {
         let zoom = zoom.max(1.0);
         let zoomed_image_size = image.get_item_size() * zoom;
@@ -738,6 +1676,7 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             Self::update_texture(wc, image);
             self.image_changed = false;
         }
+        self.symmetry.update_axis(image.width(), image.height());
         let min_size = Vec2::splat(100.0).max(ui.available_size());
         let (resp, painter) = ui.allocate_painter(min_size, Sense::drag());
         let canvas_rect = resp.rect.expand(-2.0);
@@ -771,6 +1710,7 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
 
         // draw background
         self.draw_background(ui, wc, paint_image_rect, image, zoom);
+        self.reference.paint(ui, paint_image_rect.min, zoom, false);
 
         // draw image
         let slot = image.texture_slot(self.display.is_transparent(), false);
@@ -778,9 +1718,14 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
         let item_uv = image.get_item_uv(self.selected_image);
         Image::from_texture((texture.id(), image_size)).uv(item_uv).paint_at(ui, paint_image_rect);
 
+        // draw onion skin
+        if self.onion_skin.enabled {
+            self.draw_onion_skin(ui, wc, paint_image_rect, image);
+        }
+
         // draw floating selection
         if let ImageSelection::Fragment(pos, frag) = &mut self.selection {
-            let slot = image.texture_slot(self.display.is_transparent(), true);
+            let slot = image.texture_slot(self.display.is_transparent() || frag.mask.is_some(), true);
             let frag_texture = frag.load_texture(wc.tex_man, ui.ctx(), slot, frag.changed);
             if frag.changed { frag.changed = false; }
             let uv = frag.get_item_uv(0);
@@ -789,6 +1734,8 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             Image::from_texture((frag_texture.id(), frag_size)).uv(uv).paint_at(ui, paint_frag_rect);
         }
 
+        self.reference.paint(ui, paint_image_rect.min, zoom, true);
+
         // draw grid and border
         let display_grid = self.display.has_bits(ImageDisplay::GRID) && zoom >= 5.0;
         if display_grid {
@@ -803,6 +1750,11 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             }
         }
 
+        // draw symmetry axes
+        if self.symmetry.mode != ImageSymmetryMode::None {
+            self.symmetry.draw_axes(&painter.with_clip_rect(image_area_rect), canvas_to_image.inverse(), paint_image_rect);
+        }
+
         if self.drop_selection_next_show {
             self.drop_selection_next_show = false;
             self.drop_selection(image);
@@ -813,13 +1765,25 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
 
         let keys_pressed = resp.ctx.input(|i| i.modifiers);
 
+        let hover_axis = match resp.hover_pos() {
+            Some(pos) if self.symmetry.mode != ImageSymmetryMode::None => self.symmetry.axis_at(pos, canvas_to_image.inverse()),
+            _ => None,
+        };
+
         // set cursor
         if resp.contains_pointer() && resp.hovered() {
-            if keys_pressed.alt {
+            if let Some(axis) = self.symmetry.dragging_axis.or(hover_axis) {
+                resp.ctx.set_cursor_icon(match axis {
+                    ImageSymmetryAxis::X => egui::CursorIcon::ResizeHorizontal,
+                    ImageSymmetryAxis::Y => egui::CursorIcon::ResizeVertical,
+                });
+            } else if keys_pressed.alt {
                 resp.ctx.set_cursor_icon(egui::CursorIcon::AllScroll);
             } else if keys_pressed.ctrl {
                 resp.ctx.set_cursor_icon(egui::CursorIcon::ZoomIn);
-            } else if self.tool == ImageDrawingTool::Select {
+            } else if self.tool == ImageDrawingTool::Offset {
+                resp.ctx.set_cursor_icon(egui::CursorIcon::Move);
+            } else if self.tool.is_selection() || ImageDrawingTool::SHAPES.contains(&self.tool) {
                 resp.ctx.set_cursor_icon(egui::CursorIcon::Crosshair)
             }
         }
@@ -839,11 +1803,26 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             self.clip_scroll(canvas_rect.size(), zoomed_image_size);
         }
 
+        // check symmetry axis drag
+        if resp.drag_stopped() {
+            self.symmetry.dragging_axis = None;
+        }
+        if resp.drag_started_by(egui::PointerButton::Primary) && ! keys_pressed.alt && hover_axis.is_some() {
+            self.symmetry.dragging_axis = hover_axis;
+        }
+        if let Some(axis) = self.symmetry.dragging_axis && let Some(pointer_pos) = resp.interact_pointer_pos() {
+            self.symmetry.drag_axis(axis, canvas_to_image * pointer_pos);
+        }
+
         // check click
         if resp.drag_stopped() {
             self.tool_mouse_down = false;
+            self.shape_drawing = false;
+            if self.tool == ImageDrawingTool::Lasso {
+                self.finish_lasso(image);
+            }
         }
-        if let Some(pointer_pos) = resp.interact_pointer_pos() && ! keys_pressed.alt {
+        if let Some(pointer_pos) = resp.interact_pointer_pos() && ! keys_pressed.alt && self.symmetry.dragging_axis.is_none() {
             if resp.drag_started() {
                 self.tool_mouse_down = true;
             }
@@ -853,8 +1832,13 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             }
         }
 
-        // draw selection rectangle
-        if let Some(sel_rect) = self.selection.get_rect() && (sel_rect.width() > 0.0 || sel_rect.height() > 0.0) {
+        // draw selection
+        if let ImageSelection::Mask(mask) = &self.selection {
+            let image_to_canvas = canvas_to_image.inverse();
+            let edges = mask.edges().into_iter().map(|(start, end)| (image_to_canvas * start, image_to_canvas * end)).collect::<Vec<_>>();
+            super::paint_marching_ants_edges(&painter, &edges, wc.settings);
+            wc.request_marching_ants_repaint();
+        } else if let Some(sel_rect) = self.selection.get_rect() && (sel_rect.width() > 0.0 || sel_rect.height() > 0.0) {
             let image_to_canvas = canvas_to_image.inverse();
             let sel_rect = image_to_canvas.transform_rect(sel_rect);
             if sel_rect.is_positive() || resp.dragged_by(egui::PointerButton::Primary) {
@@ -863,6 +1847,17 @@ impl<ImageAsset> ImageEditorWidget<ImageAsset> where ImageAsset: ImageCollection
             }
         }
 
+        // draw lasso or polygon being selected
+        if ! self.lasso_points.is_empty() {
+            let image_to_canvas = canvas_to_image.inverse();
+            let mut points = self.lasso_points.iter().map(|&p| image_to_canvas * p).collect::<Vec<_>>();
+            if self.tool == ImageDrawingTool::Polygon && let Some(hover_pos) = resp.hover_pos() {
+                points.push(hover_pos);
+            }
+            let stroke = egui::Stroke::new(wc.settings.marching_ants_thickness as f32, wc.settings.marching_ants_color1);
+            painter.add(egui::Shape::line(points, stroke));
+        }
+
         // draw collision
         if self.display.has_bits(ImageDisplay::COLLISION) && let Some(col_rect) = self.collision_rect {
             let image_to_canvas = canvas_to_image.inverse();
@@ -13,10 +13,13 @@ use super::super::super::AppSettings;
 pub struct ImagePickerWidget {
     pub allow_empty_selection: bool,
     pub allow_second_selection: bool,
+    pub allow_range_selection: bool,
     pub zoom: f32,
     pub display: ImageDisplay,
+    pub item_labels: Vec<(String, Color32)>,
     selected_image: Option<u32>,
     selected_image_right: Option<u32>,
+    selected_range_end: Option<u32>,
     selected_image_changed: bool,
     selected_image_right_changed: bool,
 }
@@ -28,12 +31,15 @@ impl ImagePickerWidget {
         ImagePickerWidget {
             allow_empty_selection: false,
             allow_second_selection: false,
+            allow_range_selection: false,
             zoom: 1.0,
             selected_image: Some(0),
             selected_image_right: None,
+            selected_range_end: None,
             selected_image_changed: false,
             selected_image_right_changed: false,
             display: ImageDisplay::new(0),
+            item_labels: Vec::new(),
         }
     }
 
@@ -43,6 +49,15 @@ impl ImagePickerWidget {
         self
     }
 
+    /**
+     * Allow selecting a range of images by dragging or shift+clicking
+     * with the left button.
+     */
+    pub fn use_range_selection(mut self, use_range_selection: bool) -> Self {
+        self.allow_range_selection = use_range_selection;
+        self
+    }
+
     pub fn get_selected_image(&self) -> Option<u32> {
         self.selected_image
     }
@@ -51,8 +66,18 @@ impl ImagePickerWidget {
         self.selected_image_right
     }
 
+    /**
+     * Return the first and last images of the selected range, if more
+     * than one image is selected.
+     */
+    pub fn get_selected_range(&self) -> Option<(u32, u32)> {
+        let (Some(start), Some(end)) = (self.selected_image, self.selected_range_end) else { return None; };
+        if start == end { None } else { Some((start.min(end), start.max(end))) }
+    }
+
     pub fn set_selected_image(&mut self, selected_image: Option<u32>) {
         self.selected_image = selected_image;
+        self.selected_range_end = None;
         self.selected_image_changed = true;
     }
 
@@ -82,12 +107,13 @@ impl ImagePickerWidget {
         painter: &egui::Painter,
         canvas_pos: Pos2,
         image_size: Vec2,
-        selected_image: Option<u32>,
+        (selected_image, count): (Option<u32>, u32),
         shrink: f32,
         colors: (Color32, Color32)
     ) {
         let pos = canvas_pos + Vec2::new(shrink, self.selection_to_ui_pos(selected_image) * image_size.y + shrink);
-        let sel_rect = Rect::from_min_size(pos, image_size - Vec2::splat(2.0 * shrink) + Vec2::splat(2.0 * Self::BORDER));
+        let size = Vec2::new(image_size.x, image_size.y * count as f32);
+        let sel_rect = Rect::from_min_size(pos, size - Vec2::splat(2.0 * shrink) + Vec2::splat(2.0 * Self::BORDER));
         let stroke = egui::Stroke::new(3.0, colors.0);
         painter.rect_stroke(sel_rect, egui::CornerRadius::ZERO, stroke, egui::StrokeKind::Inside);
 
@@ -141,12 +167,25 @@ impl ImagePickerWidget {
             // draw items
             egui::Image::from_texture((texture.id(), image_picker_size)).uv(super::FULL_UV).paint_at(ui, images_rect);
 
+            // draw item labels
+            for (index, (label, color)) in self.item_labels.iter().enumerate() {
+                let pos = images_rect.min + Vec2::new(2.0, index as f32 * image_size.y + 2.0);
+                let galley = painter.layout_no_wrap(label.clone(), egui::FontId::monospace(10.0), *color);
+                let label_rect = Rect::from_min_size(pos, galley.size()).expand(1.0);
+                painter.rect_filled(label_rect, egui::CornerRadius::ZERO, Color32::from_black_alpha(180));
+                painter.galley(pos, galley, *color);
+            }
+
             // draw selection rectangles
             if self.allow_second_selection {
-                self.draw_selection_rectangle(&painter, canvas_rect.min, image_size, self.selected_image_right,
+                self.draw_selection_rectangle(&painter, canvas_rect.min, image_size, (self.selected_image_right, 1),
                                               3.0, (Color32::RED, Color32::WHITE));
             }
-            self.draw_selection_rectangle(&painter, canvas_rect.min, image_size, self.selected_image,
+            let left_selection = match self.get_selected_range() {
+                Some((first, last)) => (Some(first), last - first + 1),
+                None => (self.selected_image, 1),
+            };
+            self.draw_selection_rectangle(&painter, canvas_rect.min, image_size, left_selection,
                                           0.0, (Color32::BLUE, Color32::WHITE));
 
             response
@@ -158,7 +197,13 @@ impl ImagePickerWidget {
                 let num_items = image.num_items() as i32 + if self.allow_empty_selection { 1 } else { 0 };
                 let selection = self.ui_pos_to_selection(f32::min((pos.y / frame_size.y).floor(), (num_items - 1) as f32));
                 if resp.inner.dragged_by(egui::PointerButton::Primary) {
-                    self.selected_image = selection;
+                    let shift = ui.input(|i| i.modifiers.shift);
+                    if self.allow_range_selection && selection.is_some() && (shift || ! resp.inner.drag_started()) {
+                        self.selected_range_end = selection;
+                    } else {
+                        self.selected_image = selection;
+                        self.selected_range_end = None;
+                    }
                 } else if resp.inner.dragged_by(egui::PointerButton::Secondary) {
                     self.selected_image_right = selection;
                 }
@@ -16,6 +16,7 @@ use crate::image::{
 };
 use crate::data_asset::{
     MapData,
+    MapEffectType,
     Tileset,
 };
 use crate::misc::STATIC_IMAGES;
@@ -23,13 +24,19 @@ use crate::misc::STATIC_IMAGES;
 use super::{
     TILE_SIZE,
     SCREEN_SIZE,
+    ReferenceImage,
     get_map_layer_tile,
+    map_fx_type_color,
 };
 use super::super::{
     WindowContext,
     KeyboardPressed,
+    paint_terrain,
+    MapStamp,
     MapClipboardData,
     MapUndoData,
+    MapRoomPlacement,
+    EditorAction,
     MapWholeFragment,
     MapLayerFragment,
     MapRect,
@@ -139,6 +146,7 @@ impl MapDisplay {
     pub const PARALLAX: u8    = 1 << 3;
     pub const GRID: u8        = 1 << 4;
     pub const SCREEN: u8      = 1 << 5;
+    pub const ANIMATION: u8   = 1 << 6;
 
     pub fn new(bits: u8) -> Self {
         MapDisplay {
@@ -165,6 +173,10 @@ pub enum MapTool {
     SelectLayer,
     SelectFullLayers,
     SelectAllLayers,
+    Terrain,
+    FloodFill,
+    RectFill,
+    Stamp,
 }
 
 pub struct MapEditorWidget {
@@ -175,6 +187,8 @@ pub struct MapEditorWidget {
     pub tool: MapTool,
     pub left_draw_tile: u8,
     pub right_draw_tile: u8,
+    pub terrain_index: usize,
+    pub stamp: MapStamp,
     pub left_draw_tile_changed: bool,
     pub right_draw_tile_changed: bool,
     pub hover_pos: Vec2,
@@ -182,6 +196,7 @@ pub struct MapEditorWidget {
     pub custom_bg_color: Option<Color32>,
     pub screen_display_pos: Pos2,
     pub selection: MapSelection,
+    pub reference: ReferenceImage,
     drag_mouse_origin: Pos2,
     drag_frag_origin: Pos2,
     tool_changed: bool,
@@ -189,6 +204,8 @@ pub struct MapEditorWidget {
     undo_targets: VecDeque<MapUndoData>,
     redo_targets: VecDeque<MapUndoData>,
     tool_mouse_down: bool,
+    stamp_origin: (i32, i32),
+    stamp_last_pos: Option<(i32, i32)>,
 }
 
 impl MapEditorWidget {
@@ -206,6 +223,8 @@ impl MapEditorWidget {
             display: MapDisplay::new(MapDisplay::FOREGROUND | MapDisplay::BACKGROUND | MapDisplay::GRID),
             left_draw_tile: 0,
             right_draw_tile: MapData::NO_TILE,
+            terrain_index: 0,
+            stamp: MapStamp::new(),
             left_draw_tile_changed: false,
             right_draw_tile_changed: false,
             hover_pos: Vec2::ZERO,
@@ -213,6 +232,7 @@ impl MapEditorWidget {
             custom_bg_color: None,
             screen_display_pos: Pos2::new(TILE_SIZE/zoom, TILE_SIZE/zoom),
             selection: MapSelection::None,
+            reference: ReferenceImage::new(),
             drag_mouse_origin: Pos2::ZERO,
             drag_frag_origin: Pos2::ZERO,
             tool_changed: false,
@@ -220,12 +240,16 @@ impl MapEditorWidget {
             undo_targets: VecDeque::new(),
             redo_targets: VecDeque::new(),
             tool_mouse_down: false,
+            stamp_origin: (0, 0),
+            stamp_last_pos: None,
         }
     }
 
     pub fn get_tile_planes(&mut self) -> Vec<&mut [u8]> {
         let mut ret = Vec::<&mut [u8]>::new();
         self.selection.get_tile_planes(&mut ret);
+        ret.push(&mut self.stamp.block.data);
+        ret.push(&mut self.stamp.random_tiles);
         for undo in self.undo_targets.iter_mut() {
             ret.push(&mut undo.fg_tiles);
             ret.push(&mut undo.bg_tiles);
@@ -247,6 +271,24 @@ impl MapEditorWidget {
         self.undo_targets.push_back(MapUndoData::from_map(map_data));
     }
 
+    /**
+     * Set the room placements to restore when undoing the last change.
+     */
+    pub fn set_undo_room_placements(&mut self, placements: Vec<MapRoomPlacement>) {
+        if let Some(undo) = self.undo_targets.back_mut() {
+            undo.room_placements = Some(placements);
+        }
+    }
+
+    /**
+     * Set the room placements to restore when redoing the last undone change.
+     */
+    pub fn set_redo_room_placements(&mut self, placements: Vec<MapRoomPlacement>) {
+        if let Some(redo) = self.redo_targets.back_mut() {
+            redo.room_placements = Some(placements);
+        }
+    }
+
     pub fn set_edit_layer(&mut self, layer: MapLayer) {
         self.edit_layer = layer;
         self.edit_layer_changed = true;
@@ -487,7 +529,133 @@ impl MapEditorWidget {
         }
     }
 
-    fn handle_mouse(&mut self, pointer_pos: Pos2, response: &egui::Response, map_data: &mut MapData,
+    fn get_layer_tiles(layer: MapLayer, map_data: &mut MapData) -> Option<(&mut [u8], u32, u32)> {
+        match layer {
+            MapLayer::Foreground => Some((&mut map_data.fg_tiles, map_data.width, map_data.height)),
+            MapLayer::Background => Some((&mut map_data.bg_tiles, map_data.width, map_data.height)),
+            MapLayer::Effects => Some((&mut map_data.fx_tiles, map_data.width, map_data.height)),
+            MapLayer::Parallax => Some((&mut map_data.para_tiles, map_data.para_width, map_data.para_height)),
+            _ => None,
+        }
+    }
+
+    fn get_layer_tile_pos(&self, pointer_pos: Pos2, canvas_to_map_full: &emath::RectTransform,
+                          canvas_to_map_para: &emath::RectTransform) -> (i32, i32) {
+        let pos = match self.edit_layer {
+            MapLayer::Parallax => canvas_to_map_para * pointer_pos,
+            _ => canvas_to_map_full * pointer_pos,
+        };
+        (pos.x.floor() as i32, pos.y.floor() as i32)
+    }
+
+    fn handle_flood_fill_mouse(&mut self, pointer_pos: Pos2, response: &egui::Response, map_data: &mut MapData,
+                               canvas_to_map_full: &emath::RectTransform, canvas_to_map_para: &emath::RectTransform) {
+        if ! response.drag_started() { return; }
+        let Some(tile) = self.get_selected_tile_for_click(response) else { return; };
+        let (x, y) = self.get_layer_tile_pos(pointer_pos, canvas_to_map_full, canvas_to_map_para);
+        let Some((tiles, width, height)) = Self::get_layer_tiles(self.edit_layer, map_data) else { return; };
+        if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height { return; }
+        if tiles[(y as u32 * width + x as u32) as usize] == tile { return; }
+
+        self.set_undo_target(map_data);
+        if let Some((tiles, width, height)) = Self::get_layer_tiles(self.edit_layer, map_data) {
+            flood_fill_tiles(tiles, width, height, (x as u32, y as u32), tile);
+        }
+    }
+
+    fn handle_rect_fill_mouse(&mut self, pointer_pos: Pos2, response: &egui::Response, map_data: &mut MapData,
+                              canvas_to_map_full: &emath::RectTransform, canvas_to_map_para: &emath::RectTransform) {
+        let Some(tile) = self.get_selected_tile_for_click(response) else { return; };
+        let (x, y) = self.get_layer_tile_pos(pointer_pos, canvas_to_map_full, canvas_to_map_para);
+        if response.drag_started() {
+            self.set_undo_target(map_data);
+            self.drag_mouse_origin = Pos2::new(x as f32, y as f32);
+        }
+
+        // restore the layer to how it was before the drag started and fill the new rectangle
+        let Some(undo) = self.undo_targets.back() else { return; };
+        let orig_tiles = match self.edit_layer {
+            MapLayer::Foreground => &undo.fg_tiles,
+            MapLayer::Background => &undo.bg_tiles,
+            MapLayer::Effects => &undo.fx_tiles,
+            MapLayer::Parallax => &undo.para_tiles,
+            _ => { return; }
+        };
+        let Some((tiles, width, height)) = Self::get_layer_tiles(self.edit_layer, map_data) else { return; };
+        if orig_tiles.len() != tiles.len() { return; }
+        tiles.copy_from_slice(orig_tiles);
+
+        let (x0, y0) = (self.drag_mouse_origin.x as i32, self.drag_mouse_origin.y as i32);
+        let x_range = x0.min(x).max(0)..=x0.max(x).min(width as i32 - 1);
+        let y_range = y0.min(y).max(0)..=y0.max(y).min(height as i32 - 1);
+        for ty in y_range {
+            for tx in x_range.clone() {
+                tiles[(ty as u32 * width + tx as u32) as usize] = tile;
+            }
+        }
+    }
+
+    fn handle_stamp_mouse(&mut self, pointer_pos: Pos2, response: &egui::Response, map_data: &mut MapData,
+                          canvas_to_map_full: &emath::RectTransform, canvas_to_map_para: &emath::RectTransform) {
+        let paint = if response.dragged_by(egui::PointerButton::Primary) {
+            true
+        } else if response.dragged_by(egui::PointerButton::Secondary) {
+            false
+        } else {
+            return;
+        };
+        if matches!(self.edit_layer, MapLayer::Screen) { return; }
+        let (x, y) = self.get_layer_tile_pos(pointer_pos, canvas_to_map_full, canvas_to_map_para);
+        if response.drag_started() {
+            self.set_undo_target(map_data);
+            self.stamp_origin = (x, y);
+            self.stamp_last_pos = None;
+        }
+
+        // when dragging, place the stamps next to each other
+        let (w, h) = self.stamp.size();
+        let (w, h) = (w.max(1) as i32, h.max(1) as i32);
+        let (ox, oy) = self.stamp_origin;
+        let (x, y) = (ox + (x - ox).div_euclid(w) * w, oy + (y - oy).div_euclid(h) * h);
+        if self.stamp_last_pos == Some((x, y)) { return; }
+        self.stamp_last_pos = Some((x, y));
+
+        // skip stamps that don't overlap the map
+        let Some((_, width, height)) = Self::get_layer_tiles(self.edit_layer, map_data) else { return; };
+        if x + w <= 0 || y + h <= 0 || x >= width as i32 || y >= height as i32 { return; }
+
+        if paint {
+            self.stamp.paint(x, y, map_data, self.edit_layer);
+        } else if let Some((tiles, width, height)) = Self::get_layer_tiles(self.edit_layer, map_data) {
+            for ty in y.max(0)..(y + h).min(height as i32) {
+                for tx in x.max(0)..(x + w).min(width as i32) {
+                    tiles[(ty as u32 * width + tx as u32) as usize] = self.right_draw_tile;
+                }
+            }
+        }
+    }
+
+    fn handle_terrain_mouse(&mut self, pointer_pos: Pos2, response: &egui::Response, map_data: &mut MapData, tileset: &Tileset,
+                            canvas_to_map_full: &emath::RectTransform, canvas_to_map_para: &emath::RectTransform) {
+        let Some(terrain) = tileset.terrains.get(self.terrain_index) else { return; };
+        let paint = if response.dragged_by(egui::PointerButton::Primary) {
+            true
+        } else if response.dragged_by(egui::PointerButton::Secondary) {
+            false
+        } else {
+            return;
+        };
+        if ! matches!(self.edit_layer, MapLayer::Foreground | MapLayer::Background | MapLayer::Parallax) { return; }
+        if response.drag_started() { self.set_undo_target(map_data); }
+        let (tiles, width, height, pos) = match self.edit_layer {
+            MapLayer::Foreground => (&mut map_data.fg_tiles, map_data.width, map_data.height, canvas_to_map_full * pointer_pos),
+            MapLayer::Background => (&mut map_data.bg_tiles, map_data.width, map_data.height, canvas_to_map_full * pointer_pos),
+            _ => (&mut map_data.para_tiles, map_data.para_width, map_data.para_height, canvas_to_map_para * pointer_pos),
+        };
+        paint_terrain(tiles, width, height, (pos.x.floor() as i32, pos.y.floor() as i32), terrain, paint, self.right_draw_tile);
+    }
+
+    fn handle_mouse(&mut self, pointer_pos: Pos2, response: &egui::Response, map_data: &mut MapData, tileset: &Tileset,
                     canvas_to_map_full: &emath::RectTransform, canvas_to_map_para: &emath::RectTransform) {
         if matches!(self.edit_layer, MapLayer::Screen) {
             if ! response.dragged_by(egui::PointerButton::Primary) { return; }
@@ -542,6 +710,22 @@ impl MapEditorWidget {
             MapTool::SelectLayer | MapTool::SelectFullLayers | MapTool::SelectAllLayers => {
                 self.handle_selection_mouse(pointer_pos, response, map_data, canvas_to_map_full, canvas_to_map_para);
             }
+
+            MapTool::Terrain => {
+                self.handle_terrain_mouse(pointer_pos, response, map_data, tileset, canvas_to_map_full, canvas_to_map_para);
+            }
+
+            MapTool::FloodFill => {
+                self.handle_flood_fill_mouse(pointer_pos, response, map_data, canvas_to_map_full, canvas_to_map_para);
+            }
+
+            MapTool::RectFill => {
+                self.handle_rect_fill_mouse(pointer_pos, response, map_data, canvas_to_map_full, canvas_to_map_para);
+            }
+
+            MapTool::Stamp => {
+                self.handle_stamp_mouse(pointer_pos, response, map_data, canvas_to_map_full, canvas_to_map_para);
+            }
         }
     }
 
@@ -558,19 +742,25 @@ impl MapEditorWidget {
         ! self.redo_targets.is_empty()
     }
 
-    pub fn undo(&mut self, map_data: &mut MapData) {
+    pub fn undo(&mut self, wc: &mut WindowContext, map_data: &mut MapData) {
         if let Some(undo_target) = self.undo_targets.pop_back() {
             self.redo_targets.push_back(MapUndoData::from_map(map_data));
             undo_target.to_map(map_data);
             self.selection = MapSelection::None;
+            if let Some(placements) = undo_target.room_placements {
+                wc.add_editor_action(EditorAction::RestoreMapRoomPlacements { map_id: map_data.asset.id, placements, from_undo: true });
+            }
         }
     }
 
-    pub fn redo(&mut self, map_data: &mut MapData) {
+    pub fn redo(&mut self, wc: &mut WindowContext, map_data: &mut MapData) {
         if let Some(redo_target) = self.redo_targets.pop_back() {
             self.undo_targets.push_back(MapUndoData::from_map(map_data));
             redo_target.to_map(map_data);
             self.selection = MapSelection::None;
+            if let Some(placements) = redo_target.room_placements {
+                wc.add_editor_action(EditorAction::RestoreMapRoomPlacements { map_id: map_data.asset.id, placements, from_undo: false });
+            }
         }
     }
 
@@ -661,13 +851,13 @@ impl MapEditorWidget {
     pub fn handle_keyboard(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, map_data: &mut MapData) {
         let ctrl_shift_z = egui::KeyboardShortcut::new(egui::Modifiers::CTRL|egui::Modifiers::SHIFT, egui::Key::Z);
         if ui.input_mut(|i| i.consume_shortcut(&ctrl_shift_z)) {
-            self.redo(map_data);
+            self.redo(wc, map_data);
             return;
         }
 
         let ctrl_z = egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::Z);
         if ui.input_mut(|i| i.consume_shortcut(&ctrl_z)) {
-            self.undo(map_data);
+            self.undo(wc, map_data);
             return;
         }
 
@@ -705,7 +895,8 @@ impl MapEditorWidget {
         Vec2::new(scroll_x, scroll_y)
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, map_data: &mut MapData, tileset: &Tileset) {
+    pub fn show(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext, map_data: &mut MapData, tileset: &Tileset,
+                fx_types: &[MapEffectType]) {
         let min_size = (self.zoom * Vec2::splat(TILE_SIZE)).max(ui.available_size());
         let (response, painter) = ui.allocate_painter(min_size, Sense::drag());
         let response_rect = response.rect;
@@ -751,6 +942,15 @@ impl MapEditorWidget {
         // draw background green
         let bg_color = self.custom_bg_color.unwrap_or(wc.settings.map_bg_color);
         painter.rect_filled(map_area_rect, egui::CornerRadius::ZERO, bg_color);
+        self.reference.paint(ui, canvas_rect.min + self.scroll, self.zoom, false);
+
+        // animated tiles
+        let anim_tiles = if self.display.has_bits(MapDisplay::ANIMATION) && ! tileset.animations.is_empty() {
+            ui.ctx().request_repaint();
+            tileset.animated_tiles((ui.input(|i| i.time) * 1000.0) as u64)
+        } else {
+            std::array::from_fn(|tile| tile as u8)
+        };
 
         // parallax
         if self.display.has_bits(MapDisplay::PARALLAX) && map_data.para_width != 0 && map_data.para_height != 0 {
@@ -758,6 +958,7 @@ impl MapEditorWidget {
                 for x in 0..map_data.para_width {
                     let tile = get_map_layer_tile(map_data, MapLayer::Parallax, x, y);
                     if tile == MapData::NO_TILE { continue; }
+                    let tile = anim_tiles[tile as usize];
                     let (uv, texture) = if tile as u32 >= tileset.num_tiles {
                         (FULL_UV, STATIC_IMAGES.bad_tile().texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent))
                     } else {
@@ -782,6 +983,7 @@ impl MapEditorWidget {
                 for x in 0..map_data.width {
                     let tile = get_map_layer_tile(map_data, MapLayer::Background, x, y);
                     if tile == MapData::NO_TILE { continue; }
+                    let tile = anim_tiles[tile as usize];
                     let (uv, texture) = if tile as u32 >= tileset.num_tiles {
                         (FULL_UV, STATIC_IMAGES.bad_tile().texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent))
                     } else {
@@ -807,6 +1009,7 @@ impl MapEditorWidget {
                 for x in 0..map_data.width {
                     let tile = get_map_layer_tile(map_data, MapLayer::Foreground, x, y);
                     if tile == MapData::NO_TILE { continue; }
+                    let tile = anim_tiles[tile as usize];
                     let (uv, texture) = if tile as u32 >= tileset.num_tiles {
                         (FULL_UV, STATIC_IMAGES.bad_tile().texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent))
                     } else {
@@ -833,12 +1036,16 @@ impl MapEditorWidget {
                 for x in 0..map_data.width {
                     let tile = get_map_layer_tile(map_data, MapLayer::Effects, x, y);
                     if tile == MapData::NO_TILE { continue; }
-                    let (uv, texture) = if tile as u32 >= tileset.num_tiles {
+                    let bad_tile = if fx_types.is_empty() { tile as u32 >= tileset.num_tiles } else { tile as usize >= fx_types.len() };
+                    let (uv, texture) = if bad_tile {
                         (FULL_UV, STATIC_IMAGES.bad_tile().texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent))
                     } else {
                         (fx_tiles.get_item_uv(tile as u32), fx_tiles.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent))
                     };
                     let tile_rect = Self::get_tile_rect(x, y, self.zoom, canvas_rect.min + self.scroll);
+                    if let Some(fx_type) = fx_types.get(tile as usize) {
+                        ui.painter().rect_filled(tile_rect, egui::CornerRadius::ZERO, map_fx_type_color(fx_type).gamma_multiply(0.5));
+                    }
                     Image::from_texture((texture.id(), Vec2::splat(TILE_SIZE))).uv(uv).paint_at(ui, tile_rect);
                 }
             }
@@ -846,6 +1053,8 @@ impl MapEditorWidget {
             self.paint_floating_selection_for_layer(ui, MapLayer::Effects, wc, fx_tiles, TextureSlot::Transparent, canvas_rect);
         }
 
+        self.reference.paint(ui, canvas_rect.min + self.scroll, self.zoom, true);
+
         // grid and border
         let stroke = egui::Stroke::new(1.0, self.custom_grid_color.unwrap_or(wc.settings.map_grid_color));
         if self.display.has_bits(MapDisplay::GRID) {
@@ -886,8 +1095,8 @@ impl MapEditorWidget {
                 response.ctx.set_cursor_icon(egui::CursorIcon::AllScroll);
             } else {
                 match self.tool {
-                    MapTool::Pencil => {}
-                    MapTool::SelectLayer | MapTool::SelectFullLayers | MapTool::SelectAllLayers => {
+                    MapTool::Pencil | MapTool::FloodFill | MapTool::Stamp => {}
+                    MapTool::SelectLayer | MapTool::SelectFullLayers | MapTool::SelectAllLayers | MapTool::Terrain | MapTool::RectFill => {
                         response.ctx.set_cursor_icon(egui::CursorIcon::Crosshair)
                     }
                 }
@@ -918,10 +1127,24 @@ impl MapEditorWidget {
                 self.tool_mouse_down = true;
             }
             if self.tool_mouse_down {
-                self.handle_mouse(pointer_pos, &response, map_data, &canvas_to_map_full, &canvas_to_map_para);
+                self.handle_mouse(pointer_pos, &response, map_data, tileset, &canvas_to_map_full, &canvas_to_map_para);
             }
         }
 
+        // draw stamp outline
+        if self.tool == MapTool::Stamp && self.edit_layer != MapLayer::Screen && ! self.tool_mouse_down &&
+            let Some(hover_pos) = response.hover_pos() {
+            let (x, y) = self.get_layer_tile_pos(hover_pos, &canvas_to_map_full, &canvas_to_map_para);
+            let (w, h) = self.stamp.size();
+            let map_to_canvas = match self.edit_layer {
+                MapLayer::Parallax => canvas_to_map_para.inverse(),
+                _ => canvas_to_map_full.inverse(),
+            };
+            let stamp_rect = Rect::from_min_size(Pos2::new(x as f32, y as f32), Vec2::new(w as f32, h as f32));
+            let stroke = egui::Stroke::new(2.0, Color32::YELLOW);
+            painter.rect_stroke(map_to_canvas.transform_rect(stamp_rect), egui::CornerRadius::ZERO, stroke, egui::StrokeKind::Inside);
+        }
+
         // draw selection rectangle
         if let Some(sel_rect) = self.selection.get_rect() && (sel_rect.width() > 0.0 || sel_rect.height() > 0.0) {
             let map_to_canvas = match self.edit_layer {
@@ -936,3 +1159,22 @@ impl MapEditorWidget {
         }
     }
 }
+
+/**
+ * Replace the tile at `(x, y)` and all tiles with the same value that are
+ * connected to it (horizontally or vertically) with `tile`.
+ */
+fn flood_fill_tiles(tiles: &mut [u8], width: u32, height: u32, (x, y): (u32, u32), tile: u8) {
+    let target = tiles[(y * width + x) as usize];
+    if target == tile { return; }
+    let mut stack = vec![(x, y)];
+    while let Some((x, y)) = stack.pop() {
+        let index = (y * width + x) as usize;
+        if tiles[index] != target { continue; }
+        tiles[index] = tile;
+        if x > 0 { stack.push((x - 1, y)); }
+        if x + 1 < width { stack.push((x + 1, y)); }
+        if y > 0 { stack.push((x, y - 1)); }
+        if y + 1 < height { stack.push((x, y + 1)); }
+    }
+}
@@ -17,6 +17,7 @@ use crate::image::{
 
 use super::{
     TILE_SIZE,
+    SCREEN_SIZE,
     get_map_layer_tile,
 };
 use super::super::{
@@ -32,6 +33,72 @@ impl MapViewWidget {
         Rect::from_min_size(canvas_pos + pos, zoom * Vec2::splat(TILE_SIZE))
     }
 
+    /**
+     * Return the position of the parallax layer for the given camera
+     * position (in pixels), making the parallax layer reach its end when
+     * the camera reaches the end of the map.
+     */
+    pub fn calc_para_camera_pos(map_data: &MapData, camera_pos: Vec2) -> Vec2 {
+        let calc = |camera: f32, size: u32, para_size: u32, screen_size: f32| {
+            let size = size as f32 * TILE_SIZE;
+            let para_size = para_size as f32 * TILE_SIZE;
+            if size <= screen_size || para_size <= screen_size { return 0.0; }
+            camera * (para_size - screen_size) / (size - screen_size)
+        };
+        Vec2::new(
+            calc(camera_pos.x, map_data.width, map_data.para_width, SCREEN_SIZE.x),
+            calc(camera_pos.y, map_data.height, map_data.para_height, SCREEN_SIZE.y),
+        )
+    }
+
+    /**
+     * Return the largest camera position (in pixels) that keeps the
+     * screen inside the map.
+     */
+    pub fn max_camera_pos(map_data: &MapData) -> Vec2 {
+        let map_size = Vec2::new(map_data.width as f32, map_data.height as f32) * TILE_SIZE;
+        (map_size - SCREEN_SIZE).max(Vec2::ZERO)
+    }
+
+    fn paint_screen_layer(ui: &egui::Ui, map_data: &MapData, tileset: &Tileset, texture: &egui::TextureHandle,
+                          layer: MapLayer, layer_pos: Vec2, screen_rect: Rect) {
+        let zoom = screen_rect.width() / SCREEN_SIZE.x;
+        let first = (layer_pos / TILE_SIZE).floor();
+        let last = ((layer_pos + SCREEN_SIZE) / TILE_SIZE).ceil();
+        let origin = screen_rect.min - zoom * layer_pos;
+        for y in first.y.max(0.0) as u32 .. last.y.max(0.0) as u32 {
+            for x in first.x.max(0.0) as u32 .. last.x.max(0.0) as u32 {
+                let tile = get_map_layer_tile(map_data, layer, x, y);
+                if tile == MapData::NO_TILE || tile as u32 >= tileset.num_tiles { continue; }
+                let tile_rect = Self::get_tile_rect(x, y, zoom, origin);
+                Image::from_texture((texture.id(), Vec2::splat(TILE_SIZE))).uv(tileset.get_item_uv(tile as u32)).paint_at(ui, tile_rect);
+            }
+        }
+    }
+
+    /**
+     * Show what the game screen shows with the camera at the given
+     * position (in pixels), with the parallax layer scrolling at its
+     * own speed.
+     */
+    pub fn show_screen(ui: &mut egui::Ui, wc: &mut WindowContext, map_data: &MapData, tileset: &Tileset, camera_pos: Vec2, zoom: f32) {
+        let (response, painter) = ui.allocate_painter(zoom * SCREEN_SIZE, Sense::hover());
+        let screen_rect = response.rect;
+        painter.rect_filled(screen_rect, egui::CornerRadius::ZERO, egui::Color32::from_rgb(0,0,0));
+
+        let mut ui = ui.new_child(egui::UiBuilder::new().max_rect(screen_rect));
+        ui.shrink_clip_rect(screen_rect);
+
+        let texture = tileset.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Opaque);
+        if map_data.para_width != 0 && map_data.para_height != 0 {
+            let para_pos = Self::calc_para_camera_pos(map_data, camera_pos);
+            Self::paint_screen_layer(&ui, map_data, tileset, texture, MapLayer::Parallax, para_pos, screen_rect);
+        }
+        Self::paint_screen_layer(&ui, map_data, tileset, texture, MapLayer::Background, camera_pos, screen_rect);
+        let texture = tileset.texture(wc.tex_man, wc.egui.ctx, TextureSlot::Transparent);
+        Self::paint_screen_layer(&ui, map_data, tileset, texture, MapLayer::Foreground, camera_pos, screen_rect);
+    }
+
     pub fn show(ui: &mut egui::Ui, wc: &mut WindowContext, map_data: &MapData, tileset: &Tileset) {
         let min_size = ui.available_size();
         let (response, painter) = ui.allocate_painter(min_size, Sense::drag());
@@ -3,6 +3,7 @@ mod color_picker_popup;
 mod pal_color_picker;
 mod map_editor;
 mod sfx_editor;
+mod piano;
 mod sprite_frame_list_view;
 mod room_editor;
 mod world_editor;
@@ -15,10 +16,14 @@ mod image_picker;
 mod prop_font_editor;
 mod font_view;
 mod tile_grid_editor;
+mod reference_image;
 
 use egui::{Vec2, Rect, Pos2};
 
-use crate::data_asset::MapData;
+use crate::data_asset::{
+    MapData,
+    MapEffectType,
+};
 use crate::platform::current_time_as_millis;
 
 use super::MapLayer;
@@ -33,6 +38,7 @@ pub use color_picker_popup::{*};
 pub use pal_color_picker::{*};
 pub use map_editor::{*};
 pub use sfx_editor::{*};
+pub use piano::{*};
 pub use sprite_frame_list_view::{*};
 pub use room_editor::{*};
 pub use world_editor::{*};
@@ -45,6 +51,7 @@ pub use image_picker::{*};
 pub use prop_font_editor::{*};
 pub use font_view::{*};
 pub use tile_grid_editor::{*};
+pub use reference_image::{*};
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum WidgetZoom {
@@ -109,6 +116,50 @@ pub fn paint_ants(painter: &egui::Painter, rect: egui::Rect, settings: &AppSetti
     }
 }
 
+/**
+ * Paint marching ants along horizontal and vertical line segments (in
+ * canvas coordinates), like the border of an arbitrary selection.
+ */
+pub fn paint_marching_ants_edges(painter: &egui::Painter, edges: &[(Pos2, Pos2)], settings: &AppSettings) {
+    let delay = settings.marching_ants_delay.max(10) as u64;
+    let t = ((current_time_as_millis() / delay) & (i32::MAX as u64)) as i32;
+    let stroke1 = egui::Stroke::new(settings.marching_ants_thickness as f32, settings.marching_ants_color1);
+    let stroke2 = egui::Stroke::new(settings.marching_ants_thickness as f32, settings.marching_ants_color2);
+    let dash_size = settings.marching_ants_dash_size.clamp(2, 16) as f32;
+    let period = 2.0 * dash_size;
+    let n = (t as f32) % period;
+
+    for &(start, end) in edges {
+        painter.line_segment([start, end], stroke1);
+
+        let horizontal = start.y == end.y;
+        let (from, to, across) = if horizontal {
+            (start.x.min(end.x), start.x.max(end.x), start.y)
+        } else {
+            (start.y.min(end.y), start.y.max(end.y), start.x)
+        };
+        // dashes are aligned to the canvas so they match across segments
+        let mut pos = from - (from + across + n).rem_euclid(period);
+        while pos < to {
+            let dash_start = pos.max(from);
+            let dash_end = (pos + dash_size).min(to);
+            if dash_start < dash_end {
+                if horizontal {
+                    painter.hline(dash_start..=dash_end, across, stroke2);
+                } else {
+                    painter.vline(across, dash_start..=dash_end, stroke2);
+                }
+            }
+            pos += period;
+        }
+    }
+}
+
+pub fn map_fx_type_color(fx_type: &MapEffectType) -> egui::Color32 {
+    let [_, r, g, b] = fx_type.color.to_be_bytes();
+    egui::Color32::from_rgb(r, g, b)
+}
+
 fn get_map_layer_tile(map_data: &MapData, layer: MapLayer, x: u32, y: u32) -> u8 {
     if matches!(layer, MapLayer::Parallax) && (x >= map_data.para_width || y >= map_data.para_height) { return MapData::NO_TILE; }
     if x >= map_data.width || y >= map_data.height { return MapData::NO_TILE; }
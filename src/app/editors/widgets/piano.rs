@@ -0,0 +1,150 @@
+use egui::{
+    Sense,
+    Color32,
+    Pos2,
+    Vec2,
+    Rect,
+};
+
+const NUM_OCTAVES: i32 = 2;
+const WHITE_KEY_SIZE: Vec2 = Vec2::new(18.0, 64.0);
+const BLACK_KEY_SIZE: Vec2 = Vec2::new(12.0, 38.0);
+const WHITE_KEY_NOTES: [i32; 7] = [ 0, 2, 4, 5, 7, 9, 11 ];
+const BLACK_KEY_NOTES: [(i32, usize); 5] = [ (1, 1), (3, 2), (6, 4), (8, 5), (10, 6) ];  // (note, white key to the right)
+const NOTE_NAMES: &[&str] = &[ "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B" ];
+
+// tracker-style layout: lower octave on the bottom row, upper octave on the top row
+const KEYBOARD_NOTES: &[(egui::Key, i32)] = &[
+    (egui::Key::Z,  0), (egui::Key::S,  1), (egui::Key::X,  2), (egui::Key::D,  3), (egui::Key::C,  4),
+    (egui::Key::V,  5), (egui::Key::G,  6), (egui::Key::B,  7), (egui::Key::H,  8), (egui::Key::N,  9),
+    (egui::Key::J, 10), (egui::Key::M, 11),
+    (egui::Key::Q, 12), (egui::Key::Num2, 13), (egui::Key::W, 14), (egui::Key::Num3, 15), (egui::Key::E, 16),
+    (egui::Key::R, 17), (egui::Key::Num5, 18), (egui::Key::T, 19), (egui::Key::Num6, 20), (egui::Key::Y, 21),
+    (egui::Key::Num7, 22), (egui::Key::U, 23),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PianoEvent {
+    NoteOn(i32),
+    NoteOff(i32),
+}
+
+pub struct PianoWidget {
+    pub octave: i32,
+    pressed_note: Option<i32>,
+    mouse_pressed: bool,
+}
+
+impl PianoWidget {
+    pub const MIN_OCTAVE: i32 = -3;
+    pub const MAX_OCTAVE: i32 = 2;
+
+    pub fn new() -> Self {
+        PianoWidget {
+            octave: 0,
+            pressed_note: None,
+            mouse_pressed: false,
+        }
+    }
+
+    pub fn note_name(note: i32) -> String {
+        format!("{}{}", NOTE_NAMES[note.rem_euclid(12) as usize], note.div_euclid(12) + 4)
+    }
+
+    /**
+     * Frequency to play a sample at to hear `note`, where note 0 is the
+     * sample played at `base_freq`.
+     */
+    pub fn note_freq(note: i32, base_freq: f32) -> f32 {
+        base_freq * 2.0f32.powf(note as f32 / 12.0)
+    }
+
+    fn press(&mut self, note: i32) -> PianoEvent {
+        self.pressed_note = Some(note);
+        PianoEvent::NoteOn(note)
+    }
+
+    fn release(&mut self, note: i32) -> Option<PianoEvent> {
+        if self.pressed_note == Some(note) {
+            self.pressed_note = None;
+            Some(PianoEvent::NoteOff(note))
+        } else {
+            None
+        }
+    }
+
+    pub fn handle_keyboard(&mut self, ui: &mut egui::Ui) -> Vec<PianoEvent> {
+        if ui.ctx().egui_wants_keyboard_input() { return Vec::new(); }
+
+        let octave = self.octave;
+        let key_events = ui.input(|i| {
+            i.events.iter().filter_map(|event| match event {
+                egui::Event::Key { key, physical_key, pressed, repeat: false, modifiers, .. } if modifiers.is_none() => {
+                    let key = physical_key.unwrap_or(*key);
+                    KEYBOARD_NOTES.iter().find(|(k, _)| *k == key).map(|&(_, note)| (note + 12 * octave, *pressed))
+                }
+                _ => None,
+            }).collect::<Vec<_>>()
+        });
+
+        key_events.into_iter().filter_map(|(note, pressed)| {
+            if pressed { Some(self.press(note)) } else { self.release(note) }
+        }).collect()
+    }
+
+    fn key_rects(origin: Pos2) -> Vec<(i32, Rect, bool)> {
+        let mut keys = Vec::new();
+        for octave in 0..NUM_OCTAVES {
+            let octave_x = origin.x + (octave * 7) as f32 * WHITE_KEY_SIZE.x;
+            for (i, &note) in WHITE_KEY_NOTES.iter().enumerate() {
+                let min = Pos2::new(octave_x + i as f32 * WHITE_KEY_SIZE.x, origin.y);
+                keys.push((octave * 12 + note, Rect::from_min_size(min, WHITE_KEY_SIZE), false));
+            }
+            for &(note, right_key) in BLACK_KEY_NOTES.iter() {
+                let center_x = octave_x + right_key as f32 * WHITE_KEY_SIZE.x;
+                let min = Pos2::new(center_x - BLACK_KEY_SIZE.x / 2.0, origin.y);
+                keys.push((octave * 12 + note, Rect::from_min_size(min, BLACK_KEY_SIZE), true));
+            }
+        }
+        keys
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Vec<PianoEvent> {
+        let size = Vec2::new((7 * NUM_OCTAVES) as f32 * WHITE_KEY_SIZE.x + 1.0, WHITE_KEY_SIZE.y + 1.0);
+        let (response, painter) = ui.allocate_painter(size, Sense::drag());
+        let keys = Self::key_rects(response.rect.min);
+        let pressed_note = self.pressed_note.map(|note| note - 12 * self.octave);
+
+        let outline = egui::Stroke::new(1.0, Color32::BLACK);
+        let pressed_color = ui.visuals().selection.bg_fill;
+        for &(note, rect, black) in keys.iter().filter(|(_, _, black)| ! *black).chain(keys.iter().filter(|(_, _, black)| *black)) {
+            let color = if pressed_note == Some(note) { pressed_color } else if black { Color32::BLACK } else { Color32::WHITE };
+            painter.rect_filled(rect, egui::CornerRadius::ZERO, color);
+            painter.rect_stroke(rect, egui::CornerRadius::ZERO, outline, egui::StrokeKind::Inside);
+            if note % 12 == 0 {
+                painter.text(rect.center_bottom() - Vec2::new(0.0, 2.0), egui::Align2::CENTER_BOTTOM,
+                             Self::note_name(note + 12 * self.octave), egui::FontId::proportional(9.0), Color32::DARK_GRAY);
+            }
+        }
+
+        // black keys are on top, so check them first
+        let hover_note = response.interact_pointer_pos().and_then(|pos| {
+            keys.iter().filter(|(_, _, black)| *black).chain(keys.iter().filter(|(_, _, black)| ! *black))
+                .find(|(_, rect, _)| rect.contains(pos))
+                .map(|&(note, _, _)| note + 12 * self.octave)
+        });
+
+        let mut events = Vec::new();
+        if self.mouse_pressed && ! response.is_pointer_button_down_on() {
+            self.mouse_pressed = false;
+            if let Some(note) = self.pressed_note {
+                events.extend(self.release(note));
+            }
+        }
+        if response.is_pointer_button_down_on() && let Some(note) = hover_note && self.pressed_note != Some(note) {
+            self.mouse_pressed = true;
+            events.push(self.press(note));
+        }
+        events
+    }
+}
@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use egui::{Vec2, Pos2, Rect, Image};
+
+use crate::app::ReferenceImageSettings;
+use super::super::{
+    WindowContext,
+    SysDialogResponse,
+    SysDialogOpenFile,
+};
+
+/**
+ * An image shown under or over an editor canvas to help trace over
+ * existing artwork. It's never saved in the asset data: its path and
+ * placement are stored in the app settings under the editor key.
+ */
+pub struct ReferenceImage {
+    pub settings: ReferenceImageSettings,
+    key: String,
+    filename: String,
+    texture: Option<egui::TextureHandle>,
+}
+
+impl ReferenceImage {
+    pub fn new() -> Self {
+        ReferenceImage {
+            settings: ReferenceImageSettings::new(),
+            key: String::new(),
+            filename: String::new(),
+            texture: None,
+        }
+    }
+
+    fn sys_dlg_id(&self) -> String {
+        format!("reference_image_{}", self.key)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.texture.is_some()
+    }
+
+    fn load_texture(&mut self, ctx: &egui::Context, file: &SysDialogOpenFile) -> std::io::Result<()> {
+        let data = file.read_data()?;
+        let img = ::image::load_from_memory(&data).map_err(|e| std::io::Error::other(e.to_string()))?.to_rgba8();
+        let size = [img.width() as usize, img.height() as usize];
+        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, img.as_raw());
+        self.texture = Some(ctx.load_texture(self.sys_dlg_id(), color_image, egui::TextureOptions::default()));
+        self.filename = file.filename().to_owned();
+        Ok(())
+    }
+
+    fn save_settings(&self, wc: &mut WindowContext) {
+        wc.settings.reference_images.insert(self.key.clone(), self.settings.clone());
+        wc.settings.save(wc.logger);
+    }
+
+    fn clear(&mut self, wc: &mut WindowContext) {
+        self.texture = None;
+        self.filename.clear();
+        self.settings = ReferenceImageSettings::new();
+        wc.settings.reference_images.remove(&self.key);
+        wc.settings.save(wc.logger);
+    }
+
+    /**
+     * Set the key used to store the settings (normally based on the
+     * asset type and name) and handle the response of the file dialog.
+     * This must be called every frame before showing the menu.
+     */
+    pub fn update(&mut self, wc: &mut WindowContext, key: String) {
+        if key != self.key {
+            let old_key = std::mem::replace(&mut self.key, key);
+            if ! old_key.is_empty() && ! wc.settings.reference_images.contains_key(&self.key) &&
+                let Some(settings) = wc.settings.reference_images.remove(&old_key) {
+                // the asset was renamed
+                wc.settings.reference_images.insert(self.key.clone(), settings);
+            } else {
+                self.texture = None;
+                self.filename.clear();
+                self.settings = wc.settings.reference_images.get(&self.key).cloned().unwrap_or_else(ReferenceImageSettings::new);
+                if ! self.settings.path.is_empty() && let Some(file) = SysDialogOpenFile::create(Path::new(&self.settings.path)) &&
+                    let Err(e) = self.load_texture(wc.egui.ctx, &file) {
+                    wc.logger.log(format!("ERROR loading reference image {}: {}", self.settings.path, e));
+                }
+            }
+        }
+
+        if let Some(SysDialogResponse::File(file)) = wc.sys_dialogs.get_response_for(self.sys_dlg_id()) {
+            match self.load_texture(wc.egui.ctx, &file) {
+                Ok(()) => {
+                    self.settings.path = file.path().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default();
+                    self.settings.visible = true;
+                    self.save_settings(wc);
+                }
+                Err(e) => {
+                    wc.open_message_box("Error Loading Image", format!("Error loading {}:\n{}", file.filename(), e));
+                }
+            }
+        }
+    }
+
+    pub fn show_menu(&mut self, ui: &mut egui::Ui, wc: &mut WindowContext) {
+        let title = if self.is_loaded() && self.settings.visible { "Ref: on" } else { "Ref: off" };
+        ui.menu_button(title, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Load...").clicked() {
+                    wc.sys_dialogs.open_file(
+                        Some(wc.egui.window),
+                        self.sys_dlg_id(),
+                        "reference_image",
+                        "Load Reference Image",
+                        &[
+                            ("Image files (*.png, *.jpg)", &["png", "jpg", "jpeg"]),
+                            ("All files (*.*)", &["*"]),
+                        ]
+                    );
+                    ui.close();
+                }
+                if ui.add_enabled(self.is_loaded(), egui::Button::new("Clear")).clicked() {
+                    self.clear(wc);
+                    ui.close();
+                }
+            });
+            if self.is_loaded() {
+                ui.label(egui::RichText::new(&self.filename).small());
+            }
+            ui.separator();
+
+            let old_settings = self.settings.clone();
+            let mut finished = false;
+            ui.add_enabled_ui(self.is_loaded(), |ui| {
+                finished |= ui.checkbox(&mut self.settings.visible, "Show reference image").changed();
+                ui.horizontal(|ui| {
+                    finished |= ui.radio_value(&mut self.settings.over, false, "Under image").changed();
+                    finished |= ui.radio_value(&mut self.settings.over, true, "Over image").changed();
+                });
+                egui::Grid::new("reference_image_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Opacity:");
+                    finished |= Self::edit_finished(&ui.add(egui::Slider::new(&mut self.settings.opacity, 5..=100).suffix("%")));
+                    ui.end_row();
+
+                    ui.label("Offset:");
+                    ui.horizontal(|ui| {
+                        finished |= Self::edit_finished(&ui.add(egui::DragValue::new(&mut self.settings.offset_x).prefix("x: ")));
+                        finished |= Self::edit_finished(&ui.add(egui::DragValue::new(&mut self.settings.offset_y).prefix("y: ")));
+                    });
+                    ui.end_row();
+
+                    ui.label("Scale:");
+                    finished |= Self::edit_finished(&ui.add(egui::DragValue::new(&mut self.settings.scale).range(1..=1000).suffix("%")));
+                    ui.end_row();
+                });
+            });
+            if finished || (self.settings != old_settings && ! ui.input(|i| i.pointer.any_down())) {
+                self.save_settings(wc);
+            }
+        });
+    }
+
+    fn edit_finished(response: &egui::Response) -> bool {
+        response.drag_stopped() || response.lost_focus()
+    }
+
+    /**
+     * Paint the reference image if it's visible and should be painted
+     * over (or under) the canvas. `origin` is the screen position of
+     * the canvas origin and `zoom` is the size of a canvas pixel.
+     */
+    pub fn paint(&self, ui: &mut egui::Ui, origin: Pos2, zoom: f32, over: bool) {
+        if ! self.settings.visible || self.settings.over != over { return; }
+        let Some(texture) = &self.texture else { return; };
+        let offset = Vec2::new(self.settings.offset_x as f32, self.settings.offset_y as f32);
+        let size = texture.size_vec2() * (self.settings.scale as f32 / 100.0);
+        let rect = Rect::from_min_size(origin + offset * zoom, size * zoom);
+        let tint = egui::Color32::from_white_alpha((self.settings.opacity * 255 / 100) as u8);
+        Image::from_texture((texture.id(), size)).tint(tint).paint_at(ui, rect);
+    }
+}
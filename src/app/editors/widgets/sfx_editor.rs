@@ -6,7 +6,10 @@ use egui::{
     Rect,
 };
 
+use crate::misc::sample_utils;
+
 const MIN_SAMPLES_PER_POINT: f32 = 0.125;
+const ZERO_CROSSING_SNAP_POINTS: f32 = 8.0;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum SfxTool {
@@ -60,6 +63,8 @@ pub struct SfxEditorWidget {
     pub tool: SfxTool,
     pub selection_enabled: bool,
     pub selection: Option<SfxSelection>,
+    pub snap_to_zero_crossing: bool,
+    pub play_position: Option<f32>,
     drag_start_sample_index: u32,
 }
 
@@ -72,6 +77,8 @@ impl SfxEditorWidget {
             tool: SfxTool::Select,
             selection_enabled: true,
             selection: None,
+            snap_to_zero_crossing: false,
+            play_position: None,
             drag_start_sample_index: 0,
         }
     }
@@ -92,6 +99,12 @@ impl SfxEditorWidget {
         (sample_index as f32 - self.first_sample) / self.samples_per_point
     }
 
+    fn snap_sample_index(&self, samples: &[i16], sample_index: u32) -> u32 {
+        if ! self.snap_to_zero_crossing { return sample_index; }
+        let max_dist = (ZERO_CROSSING_SNAP_POINTS * self.samples_per_point).ceil() as u32;
+        sample_utils::find_zero_crossing(samples, sample_index, max_dist)
+    }
+
     fn zoom_by(&mut self, delta: f32, center: f32, canvas_width: f32, num_samples: usize) {
         let delta = if self.samples_per_point / delta < MIN_SAMPLES_PER_POINT {
             MIN_SAMPLES_PER_POINT / self.samples_per_point
@@ -268,6 +281,14 @@ impl SfxEditorWidget {
             }
         }
 
+        // draw play position
+        if let Some(play_position) = self.play_position {
+            let play_pos = self.get_sample_pos(play_position.floor() as u32);
+            if play_pos >= 0.0 && play_pos <= canvas_rect.width() {
+                painter.vline(samples_x_start + play_pos, canvas_rect.y_range(), egui::Stroke::new(1.0, Color32::YELLOW));
+            }
+        }
+
         // ====================================================
         // == handle input
 
@@ -316,7 +337,7 @@ impl SfxEditorWidget {
                     self.tool_mouse_down = true;
                 }
                 let pos = ((pointer_pos.x - canvas_rect.min.x) * self.samples_per_point + self.first_sample).floor();
-                let mouse_sample_index = pos.clamp(0.0, num_samples as f32) as u32;
+                let mouse_sample_index = self.snap_sample_index(samples, pos.clamp(0.0, num_samples as f32) as u32);
                 match self.tool {
                     SfxTool::Select => {
                         if self.selection_enabled {
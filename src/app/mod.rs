@@ -13,6 +13,7 @@ use crate::data_asset::{
     DataAssetType,
     DataAssetId,
     DataAssetStore,
+    MapData,
     StringLogger,
 };
 use crate::misc::asset_defs::{
@@ -20,7 +21,10 @@ use crate::misc::asset_defs::{
     get_asset_type_display_name,
 };
 use crate::misc::IMAGES;
-use crate::image::TextureManager;
+use crate::image::{
+    TextureManager,
+    ImagePixels,
+};
 use crate::sound::SoundPlayer;
 
 use widgets::{
@@ -31,6 +35,9 @@ use widgets::{
 use editors::{
     ImageClipboardData,
     MapClipboardData,
+    MapLayer,
+    slice_level_image,
+    find_duplicate_tiles,
 };
 
 pub use editors::{
@@ -56,7 +63,7 @@ pub use windows::{
     AppWindows,
     AppWindowAction,
 };
-pub use settings::AppSettings;
+pub use settings::{AppSettings, ReferenceImageSettings};
 
 enum ConfirmationDialogAction {
     NewProject,
@@ -106,6 +113,7 @@ impl RavenEditorApp {
     const OPEN_PROJECT_SYS_DLG_ID: &str = "open_project";
     const SAVE_PROJECT_SYS_DLG_ID: &str = "save_project_as";
     const EXPORT_HEADER_SYS_DLG_ID: &str = "export_header";
+    const IMPORT_LEVEL_SYS_DLG_ID: &str = "import_level";
     const ASSET_TREE_PANEL_WIDTH: f32 = 200.0;
 
     pub fn new(cc: &eframe::CreationContext<'_>, is_wasm: bool, logger: StringLogger, settings: AppSettings) -> Self {
@@ -213,6 +221,89 @@ impl RavenEditorApp {
         }
     }
 
+    /**
+     * Create a tileset with the distinct tiles of a level image and a
+     * map that uses them to reproduce the image.
+     */
+    pub fn import_level_image(&mut self, file: SysDialogOpenFile) {
+        let (layer, report_flipped) = self.dialogs.get_import_level_options();
+        let pixels = match file.read_data().and_then(|data| ImagePixels::load_png(&data)) {
+            Ok(pixels) => pixels,
+            Err(e) => {
+                self.logger.log(format!("ERROR reading level image from {}:\n{}", file.filename(), e));
+                self.open_message_box(
+                    "Error Importing Level",
+                    "Error reading level image.\n\nConsult the log window for details."
+                );
+                self.windows.open_log_window();
+                return;
+            }
+        };
+
+        let mut level = slice_level_image(&pixels, layer == MapLayer::Foreground);
+        let num_image_tiles = level.num_tiles;
+        let num_dropped_cells = level.truncate_tiles(MapData::NO_TILE as u32);
+        if level.num_tiles == 0 {
+            self.open_message_box("Empty Image", "The image has no tiles.");
+            return;
+        }
+
+        let tileset_name = self.new_asset_name(DataAssetType::Tileset, None);
+        let Some(tileset_id) = self.store.add_tileset(tileset_name) else { return; };
+        let map_name = self.new_asset_name(DataAssetType::MapData, None);
+        let Some(map_id) = self.store.add_map(map_name, tileset_id) else { return; };
+
+        if let Some(tileset) = self.store.assets.tilesets.get_mut(&tileset_id) {
+            tileset.num_tiles = level.num_tiles;
+            tileset.data = level.tile_data;
+            if report_flipped {
+                let flipped = find_duplicate_tiles(tileset, true);
+                for dup in &flipped {
+                    self.logger.log(format!("  tile {} is {} tile {}", dup.tile, dup.kind.text(), dup.original));
+                }
+                self.logger.log(format!("{} tiles are flipped copies of other tiles (map tiles can't be flipped)", flipped.len()));
+            }
+        }
+        if let Some(map_data) = self.store.assets.maps.get_mut(&map_id) {
+            let size = (level.width * level.height) as usize;
+            let tiles = level.cells.iter().map(|cell| cell.map_or(MapData::NO_TILE, |tile| tile as u8)).collect();
+            map_data.width = level.width;
+            map_data.height = level.height;
+            map_data.fx_tiles = vec![MapData::NO_TILE; size];
+            match layer {
+                MapLayer::Background => {
+                    map_data.fg_tiles = vec![MapData::NO_TILE; size];
+                    map_data.bg_tiles = tiles;
+                }
+                _ => {
+                    map_data.fg_tiles = tiles;
+                    map_data.bg_tiles = vec![MapData::NO_TILE; size];
+                }
+            }
+        }
+
+        let tileset_egui_id = self.editors.add_tileset(tileset_id);
+        self.window_tracker.add_editor(tileset_egui_id, tileset_id);
+        let map_egui_id = self.editors.add_map(map_id);
+        self.window_tracker.add_editor(map_egui_id, map_id);
+        self.logger.log(format!(
+            "Imported level image from {}: {}x{} map with {} unique tiles",
+            file.filename(), level.width, level.height, level.num_tiles
+        ));
+        if num_image_tiles > level.num_tiles {
+            self.logger.log(format!(
+                "WARNING: the image has {} unique tiles, but maps can only use {}; {} map cells using the other tiles were left empty",
+                num_image_tiles, level.num_tiles, num_dropped_cells
+            ));
+            self.open_message_box(
+                "Too Many Tiles",
+                &format!("The image has {} different tiles, but a map can only use {} tiles.\n\n\
+                          Only the first {} tiles were imported, and the {} map cells that use the other tiles were left empty.",
+                         num_image_tiles, level.num_tiles, level.num_tiles, num_dropped_cells)
+            );
+        }
+    }
+
     pub fn open(&mut self, file: SysDialogOpenFile) {
         if let Some(path) = file.path() && let Some(dir) = path.parent() {
             self.sys_dialogs.set_path_for_id("project", dir);
@@ -385,6 +476,7 @@ impl RavenEditorApp {
                     asset.name.replace_range(0..old_name.len(), &new_name);
                 }
             }
+            self.editors.set_image_asset_names_dirty();
         }
     }
 
@@ -541,7 +633,7 @@ impl RavenEditorApp {
         self.text_input_dialog_action = Some(action);
     }
 
-    fn show_dialogs(&mut self, ui: &mut egui::Ui) {
+    fn show_dialogs(&mut self, ui: &mut egui::Ui, window: &eframe::Frame) {
         self.dialogs.show_non_response_dialogs(ui, &mut self.window_tracker, &self.sys_dialogs, &mut self.settings);
 
         // confirmation dialog
@@ -579,12 +671,27 @@ impl RavenEditorApp {
                         if text_input_dialog_result == DialogResult::Yes &&
                             let Some(asset) = self.store.assets.get_asset_mut(*asset_id) {
                                 asset.name = self.dialogs.get_text_input_dialog_input();
+                                self.editors.set_image_asset_names_dirty();
                             }
                     }
                 }
             }
             self.text_input_dialog_action = None;
         }
+
+        // import level dialog
+        if self.dialogs.show_import_level_dialog(ui, &mut self.window_tracker, &self.sys_dialogs) == DialogResult::Yes {
+            self.sys_dialogs.open_file(
+                Some(window),
+                Self::IMPORT_LEVEL_SYS_DLG_ID.to_owned(),
+                "level",
+                "Import Level Image",
+                &[
+                    ("PNG files (*.png)", &["png"]),
+                    ("All files (*.*)", &["*"]),
+                ]
+            );
+        }
     }
 
     fn show_menu_bar(&mut self, ui: &mut egui::Ui, window: &mut eframe::Frame) {
@@ -691,6 +798,9 @@ impl RavenEditorApp {
                             ]
                         );
                     }
+                    if ui.add(menu_item_no_image(" Import level image...")).clicked() {
+                        self.dialogs.open_import_level_dialog(&mut self.window_tracker);
+                    }
                 });
                 ui.menu_button("Help", |ui| {
                     if ui.add(menu_item(IMAGES.info, " Status")).clicked() {
@@ -888,6 +998,7 @@ impl RavenEditorApp {
         };
 
         self.editors.refresh_room_names(&self.store.assets.rooms);
+        self.editors.refresh_image_asset_names(&self.store);
 
         // if some window was closed, focus the window that's closest to the top (if any)
         if self.editors.iter().any(|e| e.closed_last_frame) || self.windows.some_closed_last_frame {
@@ -905,12 +1016,12 @@ impl RavenEditorApp {
 
         for tileset in self.store.assets.tilesets.iter_mut() {
             if let Some(editor) = self.editors.tilesets.get_mut(&tileset.asset.id) {
-                editor.show(&mut win_ctx, tileset);
+                editor.show(&mut win_ctx, tileset, &self.editors.image_asset_names, &self.store.assets.maps);
             }
         }
         for map in self.store.assets.maps.iter_mut() {
             if let Some(editor) = self.editors.maps.get_mut(&map.asset.id) {
-                editor.show(&mut win_ctx, map, &self.store.asset_ids.tilesets, &self.store.assets.tilesets);
+                editor.show(&mut win_ctx, map, &self.store.asset_ids.tilesets, &self.store.assets.tilesets, &self.store.map_fx_types);
             }
         }
         for room in self.store.assets.rooms.iter_mut() {
@@ -935,7 +1046,7 @@ impl RavenEditorApp {
         }
         for sprite in self.store.assets.sprites.iter_mut() {
             if let Some(editor) = self.editors.sprites.get_mut(&sprite.asset.id) {
-                editor.show(&mut win_ctx, sprite);
+                editor.show(&mut win_ctx, sprite, &self.store.assets.animations, &self.editors.image_asset_names);
             }
         }
         for pal_sprite in self.store.assets.pal_sprites.iter_mut() {
@@ -960,7 +1071,7 @@ impl RavenEditorApp {
         }
         for font in self.store.assets.fonts.iter_mut() {
             if let Some(editor) = self.editors.fonts.get_mut(&font.asset.id) {
-                editor.show(&mut win_ctx, font);
+                editor.show(&mut win_ctx, font, &self.editors.image_asset_names);
             }
         }
         for pfont in self.store.assets.prop_fonts.iter_mut() {
@@ -1029,6 +1140,9 @@ impl eframe::App for RavenEditorApp {
         if let Some(SysDialogResponse::File(file)) = self.sys_dialogs.get_response_for(Self::EXPORT_HEADER_SYS_DLG_ID) {
             self.export_header(file);
         }
+        if let Some(SysDialogResponse::File(file)) = self.sys_dialogs.get_response_for(Self::IMPORT_LEVEL_SYS_DLG_ID) {
+            self.import_level_image(file);
+        }
 
         if self.reset_egui_context {
             ui.ctx().memory_mut(|mem| {
@@ -1050,7 +1164,7 @@ impl eframe::App for RavenEditorApp {
         }
 
         self.editors.update_dirty_flags(&self.store);
-        self.show_dialogs(ui);
+        self.show_dialogs(ui, window);
         self.show_menu_bar(ui, window);
         self.show_toolbar(ui, window);
         self.show_footer(ui);
@@ -1,4 +1,5 @@
 use std::io::{Result, Error};
+use std::collections::BTreeMap;
 
 use crate::platform::{
     read_settings_file,
@@ -15,6 +16,31 @@ use crate::data_asset::{
     TokenData
 };
 
+#[derive(Clone, PartialEq)]
+pub struct ReferenceImageSettings {
+    pub path: String,
+    pub visible: bool,
+    pub over: bool,
+    pub opacity: u32,
+    pub offset_x: i32,
+    pub offset_y: i32,
+    pub scale: u32,
+}
+
+impl ReferenceImageSettings {
+    pub fn new() -> Self {
+        ReferenceImageSettings {
+            path: String::new(),
+            visible: true,
+            over: false,
+            opacity: 50,
+            offset_x: 0,
+            offset_y: 0,
+            scale: 100,
+        }
+    }
+}
+
 pub struct AppSettings {
     pub theme: String,
     pub zoom: u32,
@@ -29,7 +55,11 @@ pub struct AppSettings {
     pub marching_ants_thickness: u32,
     pub marching_ants_color1: egui::Color32,
     pub marching_ants_color2: egui::Color32,
+    pub onion_skin_opacity: u32,
+    pub onion_skin_prev_color: egui::Color32,
+    pub onion_skin_next_color: egui::Color32,
     pub colorsets: ColorSetCollection,
+    pub reference_images: BTreeMap<String, ReferenceImageSettings>,
 }
 
 impl AppSettings {
@@ -50,7 +80,11 @@ impl AppSettings {
             marching_ants_thickness: 3,
             marching_ants_color1: egui::Color32::BLACK,
             marching_ants_color2: egui::Color32::WHITE,
+            onion_skin_opacity: 40,
+            onion_skin_prev_color: egui::Color32::from_rgb(0xff, 0x60, 0x60),
+            onion_skin_next_color: egui::Color32::from_rgb(0x60, 0xa0, 0xff),
             colorsets: ColorSetCollection::new(),
+            reference_images: BTreeMap::new(),
         }
     }
 
@@ -72,6 +106,10 @@ impl AppSettings {
         format!("[{},{},{}]", c.r(), c.g(), c.b())
     }
 
+    fn save_string(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
     pub fn cleanup_ident(name: &str) -> String {
         let mut clean = String::new();
         for ch in name.chars() {
@@ -100,6 +138,10 @@ impl AppSettings {
         config.push_str(&format!("marching_ants_colors = [ {}, {} ];\n",
                                  Self::save_color(self.marching_ants_color1),
                                  Self::save_color(self.marching_ants_color2)));
+        config.push_str(&format!("onion_skin_opacity = {};\n", self.onion_skin_opacity));
+        config.push_str(&format!("onion_skin_colors = [ {}, {} ];\n",
+                                 Self::save_color(self.onion_skin_prev_color),
+                                 Self::save_color(self.onion_skin_next_color)));
 
         // colorsets
         config.push_str("colorsets = [\n");
@@ -110,6 +152,21 @@ impl AppSettings {
         }
         config.push_str("];\n");
 
+        // reference images
+        config.push_str("reference_images = [\n");
+        for (key, reference) in self.reference_images.iter() {
+            config.push_str(&format!("  {} = [ {}, {}, {}, {}, {}, {}, {} ],\n",
+                                     Self::save_string(key),
+                                     Self::save_string(&reference.path),
+                                     if reference.visible { 1 } else { 0 },
+                                     if reference.over { 1 } else { 0 },
+                                     reference.opacity,
+                                     reference.offset_x,
+                                     reference.offset_y,
+                                     reference.scale));
+        }
+        config.push_str("];\n");
+
         if let Err(e) = write_settings_file(Self::FILENAME, &config) {
             logger.log(format!("ERROR writing settings: '{}'", e));
         }
@@ -151,6 +208,25 @@ impl<'a> AppSettingsReader<'a> {
         Err(Error::other(format!("expected number, found '{}' at line {}", t, t.pos.line)))
     }
 
+    fn read_signed_number(&mut self) -> Result<i64> {
+        let t = self.tok.read()?;
+        if t.is_punct('-') {
+            return Ok(-(self.read_number()? as i64));
+        }
+        if let Some(n) = t.get_number() {
+            return Ok(n as i64)
+        }
+        Err(Error::other(format!("expected number, found '{}' at line {}", t, t.pos.line)))
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let t = self.tok.read()?;
+        if let Some(s) = t.get_string() {
+            return Ok(s.to_owned());
+        }
+        Err(Error::other(format!("expected string, found '{}' at line {}", t, t.pos.line)))
+    }
+
     fn read_color(&mut self) -> Result<egui::Color32> {
         self.expect_punct('[')?;
         let r = self.read_number()?;
@@ -238,6 +314,58 @@ impl<'a> AppSettingsReader<'a> {
         Ok(colorsets)
     }
 
+    fn read_reference_images_config(&mut self) -> Result<BTreeMap<String, ReferenceImageSettings>> {
+        let mut reference_images = BTreeMap::new();
+
+        self.expect_punct('[')?;
+        loop {
+            let next_key = loop {
+                let t = self.tok.read()?;
+                if t.is_punct(']') { break None; }
+                if t.is_punct(',') { continue; }
+                if let Some(key) = t.get_string() {
+                    break Some(key.to_owned())
+                }
+                return Err(Error::other(format!("expected reference image key string or ']', found '{}' at line {}", t, t.pos.line)));
+            };
+
+            let key = match next_key {
+                Some(key) => { key }
+                None => { break; }
+            };
+            self.expect_punct('=')?;
+            self.expect_punct('[')?;
+            let path = self.read_string()?;
+            self.expect_punct(',')?;
+            let visible = self.read_number()? != 0;
+            self.expect_punct(',')?;
+            let over = self.read_number()? != 0;
+            self.expect_punct(',')?;
+            let opacity = self.read_number()?.min(100) as u32;
+            self.expect_punct(',')?;
+            let offset_x = self.read_signed_number()? as i32;
+            self.expect_punct(',')?;
+            let offset_y = self.read_signed_number()? as i32;
+            self.expect_punct(',')?;
+            let scale = self.read_number()?.max(1) as u32;
+            self.expect_punct(']')?;
+
+            reference_images.insert(key, ReferenceImageSettings {
+                path,
+                visible,
+                over,
+                opacity,
+                offset_x,
+                offset_y,
+                scale,
+            });
+        }
+
+        self.expect_punct(';')?;
+
+        Ok(reference_images)
+    }
+
     fn read(&mut self, settings: &mut AppSettings) -> Result<()> {
         loop {
             let t = self.tok.read()?;
@@ -263,6 +391,13 @@ impl<'a> AppSettingsReader<'a> {
                         settings.marching_ants_color1 = colors[0];
                         settings.marching_ants_color2 = colors[1];
                     }
+                    "onion_skin_opacity" => { settings.onion_skin_opacity = self.read_number_config()?.min(100); }
+                    "onion_skin_colors" => {
+                        let mut colors = [settings.onion_skin_prev_color, settings.onion_skin_next_color];
+                        self.read_color_array_config(&mut colors)?;
+                        settings.onion_skin_prev_color = colors[0];
+                        settings.onion_skin_next_color = colors[1];
+                    }
                     "colorsets" => {
                         let custom_colorsets = self.read_colorsets_config()?;
                         settings.colorsets.clear_custom_colorsets();
@@ -270,6 +405,9 @@ impl<'a> AppSettingsReader<'a> {
                             settings.colorsets.add_custom_colorset(set);
                         }
                     }
+                    "reference_images" => {
+                        settings.reference_images = self.read_reference_images_config()?;
+                    }
                     _ => {
                         self.skip_config_value()?;
                     }
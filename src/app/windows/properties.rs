@@ -1,4 +1,8 @@
 use crate::misc::IMAGES;
+use crate::data_asset::{
+    MapData,
+    MapEffectType,
+};
 
 use super::{
     AppWindowBase,
@@ -11,12 +15,14 @@ use super::super::{
 
 pub struct PropertiesWindow {
     pub base: AppWindowBase,
+    fx_type_name_edit: Option<(usize, String)>,
 }
 
 impl PropertiesWindow {
     pub fn new(base: AppWindowBase) -> Self {
         PropertiesWindow {
             base,
+            fx_type_name_edit: None,
         }
     }
 
@@ -27,7 +33,8 @@ impl PropertiesWindow {
         "0xc0 (11)",
     ];
 
-    fn show_properties_grid(ui: &mut egui::Ui, wc: &mut WindowContext, store: &mut DataAssetStore) {
+    fn show_properties_grid(ui: &mut egui::Ui, wc: &mut WindowContext, store: &mut DataAssetStore,
+                            fx_type_name_edit: &mut Option<(usize, String)>) {
         egui::Grid::new("project_properties_grid")
             .num_columns(2)
             .spacing([8.0, 8.0])
@@ -73,15 +80,101 @@ impl PropertiesWindow {
                 });
                 ui.add(egui::DragValue::new(&mut store.tiles_per_world_block).speed(1.0).range(8..=32));
                 ui.end_row();
+
+                // map effect types
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                    ui.label("Map effect types:");
+                });
+                ui.vertical(|ui| {
+                    Self::show_map_fx_types(ui, store, fx_type_name_edit);
+                });
+                ui.end_row();
+            });
+    }
+
+    fn is_valid_fx_type_name(fx_types: &[MapEffectType], index: usize, name: &str) -> bool {
+        ! name.is_empty() && ! fx_types.iter().enumerate().any(|(i, fx_type)| i != index && fx_type.name == name)
+    }
+
+    fn show_map_fx_types(ui: &mut egui::Ui, store: &mut DataAssetStore, name_edit: &mut Option<(usize, String)>) {
+        // removing a type changes the values of the following ones, so
+        // only types after the last value used in maps can be removed
+        let max_used = store.assets.maps.iter()
+            .flat_map(|map_data| map_data.fx_tiles.iter())
+            .filter(|&&value| value != MapData::NO_TILE)
+            .max()
+            .map(|&value| value as usize);
+        let fx_types = &mut store.map_fx_types;
+        let mut remove_index = None;
+        for index in 0..fx_types.len() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{:3}", index));
+                let fx_type = &mut fx_types[index];
+                let mut rgb = fx_type.color.to_be_bytes();
+                let mut color = [rgb[1], rgb[2], rgb[3]];
+                if egui::color_picker::color_edit_button_srgb(ui, &mut color).changed() {
+                    rgb[1..].copy_from_slice(&color);
+                    fx_type.color = u32::from_be_bytes(rgb);
+                }
+
+                // names are only changed when valid, since they're used for the C enum
+                let mut name = match name_edit {
+                    Some((edit_index, name)) if *edit_index == index => name.clone(),
+                    _ => fx_types[index].name.clone(),
+                };
+                let invalid = name != fx_types[index].name;
+                let mut text_edit = egui::TextEdit::singleline(&mut name).desired_width(150.0);
+                if invalid {
+                    text_edit = text_edit.text_color(ui.visuals().error_fg_color);
+                }
+                let resp = ui.add(text_edit);
+                if resp.changed() {
+                    name = MapEffectType::sanitize_name(&name);
+                    if Self::is_valid_fx_type_name(fx_types, index, &name) {
+                        fx_types[index].name = name;
+                        *name_edit = None;
+                    } else {
+                        *name_edit = Some((index, name));
+                    }
+                } else if resp.lost_focus() && invalid {
+                    *name_edit = None;
+                }
+                if invalid {
+                    resp.on_hover_text("Effect type names must be unique and not empty");
+                }
+
+                let can_remove = max_used.is_none_or(|max_used| max_used < index);
+                let trash = ui.add_enabled(can_remove, egui::Button::image(IMAGES.trash))
+                    .on_disabled_hover_text("Can't remove: this or a later effect type is used in maps");
+                if trash.on_hover_text("Remove effect type").clicked() {
+                    remove_index = Some(index);
+                }
             });
+        }
+        if let Some(index) = remove_index {
+            fx_types.remove(index);
+            *name_edit = None;
+        }
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(fx_types.len() < 255, egui::Button::image_and_text(IMAGES.add, "Add")).clicked() {
+                let name = (fx_types.len()..).map(|n| format!("effect{}", n))
+                    .find(|name| ! fx_types.iter().any(|fx_type| &fx_type.name == name))
+                    .unwrap_or_default();
+                fx_types.push(MapEffectType::new(&name, 0x808080));
+            }
+            if fx_types.is_empty() && ui.button("Add common types").clicked() {
+                *fx_types = MapEffectType::defaults();
+            }
+        });
     }
 
     pub fn show(&mut self, wc: &mut WindowContext, store: &mut DataAssetStore) -> AppWindowAction {
-        let default_rect = self.base.default_rect(wc, 400.0, 150.0);
+        let default_rect = self.base.default_rect(wc, 450.0, 300.0);
         self.base.show_window(wc, default_rect, [], |ui, wc, base| {
             let action = base.show_title_bar(ui, Some(IMAGES.properties), "Project Properties");
             egui::CentralPanel::default().show(ui, |ui| {
-                Self::show_properties_grid(ui, wc, store);
+                Self::show_properties_grid(ui, wc, store, &mut self.fx_type_name_edit);
             });
             action
         })
@@ -152,6 +152,22 @@ impl SettingsWindow {
         });
     }
 
+    fn show_onion_skin_settings(ui: &mut egui::Ui, wc: &mut WindowContext) {
+        egui::CollapsingHeader::new("Onion Skin").default_open(true).show(ui, |ui| {
+            egui::Grid::new("editor_settings_onion_skin")
+                .num_columns(2)
+                .spacing([8.0, 8.0])
+                .show(ui, |ui| {
+                    ui.label("Opacity:");
+                    ui.add(egui::Slider::new(&mut wc.settings.onion_skin_opacity, 5..=100).suffix("%"));
+                    ui.end_row();
+
+                    Self::color_setting(ui, "Previous frames:", &mut [&mut wc.settings.onion_skin_prev_color]);
+                    Self::color_setting(ui, "Next frames:", &mut [&mut wc.settings.onion_skin_next_color]);
+                });
+        });
+    }
+
     pub fn show(&mut self, wc: &mut WindowContext) -> AppWindowAction {
         let default_rect = self.base.default_rect(wc, 400.0, 300.0);
         self.base.show_window(wc, default_rect, [400.0, 200.0], |ui, wc, base| {
@@ -177,6 +193,8 @@ impl SettingsWindow {
                     Self::show_colorset_settings(ui, wc);
                     ui.add_space(5.0);
                     Self::show_marching_ants_settings(ui, wc);
+                    ui.add_space(5.0);
+                    Self::show_onion_skin_settings(ui, wc);
                 });
             });
             action
@@ -68,6 +68,17 @@ struct $<PREFIX>_IMAGE {
 
 #endif /* $<PREFIX>_SKIP_STRUCTS_IMAGE */
 
+#ifndef $<PREFIX>_SKIP_STRUCTS_TILESET_ANIMS
+
+// `data` has, for each animation: the animated tile, the number of
+// frames and the tile and duration (in milliseconds) of each frame
+struct $<PREFIX>_TILESET_ANIMS {
+    int32_t num_anims;
+    const uint16_t *data;
+};
+
+#endif /* $<PREFIX>_SKIP_STRUCTS_TILESET_ANIMS */
+
 #ifndef $<PREFIX>_SKIP_STRUCTS_PAL_SPRITE
 
 struct $<PREFIX>_PAL_SPRITE {
@@ -236,6 +247,7 @@ extern const struct $<PREFIX>_PROP_FONT $<prefix>_prop_fonts[];
 extern const struct $<PREFIX>_MOD_DATA $<prefix>_mods[];
 extern const struct $<PREFIX>_SFX $<prefix>_sfxs[];
 extern const struct $<PREFIX>_IMAGE $<prefix>_tilesets[];
+extern const struct $<PREFIX>_TILESET_ANIMS $<prefix>_tileset_anims[];
 extern const struct $<PREFIX>_IMAGE $<prefix>_sprites[];
 extern const struct $<PREFIX>_MAP $<prefix>_maps[];
 extern const struct $<PREFIX>_SPRITE_ANIMATION $<prefix>_sprite_animations[];
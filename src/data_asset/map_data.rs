@@ -68,3 +68,41 @@ impl super::GenericAsset for MapData {
         header + full_tiles + para_tiles
     }
 }
+
+/**
+Named effect type for the effects (fx) layer of maps: the value of an
+fx tile is the index of its type in the project's list.
+*/
+#[derive(Clone, std::hash::Hash)]
+pub struct MapEffectType {
+    pub name: String,
+    pub color: u32,
+}
+
+impl MapEffectType {
+    pub fn new(name: &str, color: u32) -> Self {
+        MapEffectType {
+            name: Self::sanitize_name(name),
+            color,
+        }
+    }
+
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new("solid", 0x808080),
+            Self::new("one_way_platform", 0x40a0ff),
+            Self::new("ladder", 0xc08040),
+            Self::new("spikes", 0xff4040),
+            Self::new("water", 0x2060ff),
+        ]
+    }
+
+    /**
+     * Convert a name to a form that can be used in a C identifier.
+     */
+    pub fn sanitize_name(name: &str) -> String {
+        name.chars().map(|ch| {
+            if ch.is_ascii_alphanumeric() { ch.to_ascii_lowercase() } else { '_' }
+        }).collect()
+    }
+}
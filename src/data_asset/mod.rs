@@ -22,8 +22,16 @@ pub use reader::tokenizer::{
     TokenData,
 };
 
-pub use tileset::Tileset;
-pub use map_data::MapData;
+pub use tileset::{
+    Tileset,
+    TilesetTerrain,
+    TerrainKind,
+    TileAnimation,
+};
+pub use map_data::{
+    MapData,
+    MapEffectType,
+};
 pub use room::{
     Room,
     RoomMap,
@@ -46,7 +54,11 @@ pub use sprite_animation::{
     SpriteAnimationFrame,
     SpriteAnimationLoop,
 };
-pub use sfx::Sfx;
+pub use sfx::{
+    Sfx,
+    SfxSynthWave,
+    SfxSynthParams,
+};
 pub use mod_data::{
     MOD_PERIOD_TABLE,
     ModData,
@@ -461,14 +473,15 @@ pub struct DataAssetStore {
     pub vga_bits_per_pixel: u8,
     pub vga_sync_bits: u8,
     pub tiles_per_world_block: u32,
+    pub map_fx_types: Vec<MapEffectType>,
     pub project_prefix: String,
     pub assets: AssetCollection,
     pub asset_ids: AssetIdCollection,
 }
 
 impl DataAssetStore {
-    pub const VERSION: u32 = 9;
-    pub const VERSION_DATE: &str = "2026-08-17";
+    pub const VERSION: u32 = 13;
+    pub const VERSION_DATE: &str = "2026-10-18";
 
     pub fn new() -> Self {
         DataAssetStore {
@@ -476,6 +489,7 @@ impl DataAssetStore {
             vga_bits_per_pixel: 8,
             vga_sync_bits: 0xc0,
             tiles_per_world_block: 22,
+            map_fx_types: Vec::new(),
             project_prefix: String::from("PROJECT"),
             assets: AssetCollection::new(),
             asset_ids: AssetIdCollection::new(),
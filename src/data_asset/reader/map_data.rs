@@ -12,6 +12,7 @@ use super::super::{
     DataAssetId,
     DataAssetType,
     MapData,
+    MapEffectType,
 };
 
 pub fn get_asset_def() -> ValueDefStruct
@@ -67,3 +68,12 @@ pub fn create(asset_id: DataAssetId, asset_struct: &ValueStruct, project_data: &
         para_tiles,
     })
 }
+
+pub fn read_fx_types(project_data: &ProjectData) -> Vec<MapEffectType> {
+    let Some(names) = project_data.get_enum_item_names("MAP_FX_TYPES", "MAP_FX_") else { return Vec::new(); };
+    let colors = project_data.get_editor_u32_array("map_fx_colors", "");
+    names.iter().enumerate().map(|(index, name)| {
+        let color = colors.and_then(|colors| colors.get(index).copied()).unwrap_or(0x808080);
+        MapEffectType::new(name, color)
+    }).collect()
+}
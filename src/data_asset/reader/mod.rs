@@ -92,6 +92,7 @@ static GLOBAL_STRUCT_DEFS: LazyLock<HashMap<String,ValueDefStruct>> = LazyLock::
 // other custom global structs (e.g. ROOM_SCRIPT)
 const CUSTOM_GLOBAL_STRUCT_READERS: &[fn(&mut ProjectDataReader, &str) -> Result<bool>] = &[
     room::read_custom_global_struct,
+    tileset::read_custom_global_struct,
 ];
 
 pub struct ProjectData {
@@ -141,6 +142,35 @@ impl ProjectData {
 
     }
 
+    // get optional editor-only array named
+    //    <prefix><array_prefix><asset_name>
+    pub fn get_editor_u32_array(&self, array_prefix: &str, asset_name: &str) -> Option<&Vec<u32>> {
+        match self.arrays.get(&format!("{}{}{}", self.prefix_lower, array_prefix, asset_name)) {
+            Some(Value::U32Array(a)) => Some(&a.values),
+            _ => None,
+        }
+    }
+
+    // get optional array named
+    //    <prefix><array_prefix><asset_name>
+    pub fn get_optional_u16_array(&self, array_prefix: &str, asset_name: &str) -> Option<&Vec<u16>> {
+        match self.arrays.get(&format!("{}{}{}", self.prefix_lower, array_prefix, asset_name)) {
+            Some(Value::U16Array(a)) => Some(&a.values),
+            _ => None,
+        }
+    }
+
+    // get the items of the enum with tag
+    //    <prefix><enum_name>
+    // that are in the form
+    //    <prefix><item_prefix><X>
+    // returning the <X> part of each one
+    pub fn get_enum_item_names(&self, enum_name: &str, item_prefix: &str) -> Option<Vec<String>> {
+        let enum_items = self.enums.get(&format!("{}{}", self.prefix_upper, enum_name))?;
+        let item_prefix = format!("{}{}", self.prefix_upper, item_prefix);
+        Some(enum_items.iter().filter_map(|item| item.strip_prefix(&item_prefix)).map(|name| name.to_lowercase()).collect())
+    }
+
     fn check_name_match(name: &str, parts: &[&str]) -> bool {
         if name.len() != parts.iter().fold(0, |len, p| len + p.len()) {
             return false;
@@ -725,6 +755,7 @@ impl<'a> ProjectDataReader<'a> {
             id_generator,
             assets,
             asset_ids,
+            map_fx_types: map_data::read_fx_types(&self.data),
             project_prefix: self.data.prefix,
             vga_bits_per_pixel: self.data.vga_bits_per_pixel,
             vga_sync_bits: self.data.vga_sync_bits,
@@ -13,6 +13,7 @@ use super::super::{
     DataAssetId,
     DataAssetType,
     Sfx,
+    SfxSynthParams,
 };
 
 pub fn get_asset_def() -> ValueDefStruct
@@ -39,6 +40,7 @@ pub fn create(asset_id: DataAssetId, asset_struct: &ValueStruct, project_data: &
 
     let data = array.get_i8_or_i16_array(project_data)?;
     let name = project_data.extract_asset_name("sfx_samples_", array)?;
+    let synth = project_data.get_editor_u32_array("sfx_synth_", name).and_then(|data| SfxSynthParams::from_data(data));
     Ok(Sfx {
         asset: DataAsset::new(DataAssetType::Sfx, asset_id, DataAsset::identifier_to_name(name)),
         len,
@@ -46,5 +48,6 @@ pub fn create(asset_id: DataAssetId, asset_struct: &ValueStruct, project_data: &
         loop_len,
         bits_per_sample,
         samples: data.take(),
+        synth,
     })
 }
@@ -6,12 +6,15 @@ use super::{
     ValueDefStruct,
     ValueStruct,
     ProjectData,
+    ProjectDataReader,
 };
 use super::super::{
     DataAsset,
     DataAssetId,
     DataAssetType,
     Tileset,
+    TilesetTerrain,
+    TileAnimation,
 };
 
 pub fn get_asset_def() -> ValueDefStruct
@@ -25,6 +28,29 @@ pub fn get_asset_def() -> ValueDefStruct
     ])
 }
 
+pub fn read_custom_global_struct(reader: &mut ProjectDataReader, struct_tag: &str) -> Result<bool> {
+    if struct_tag == "TILESET_ANIMS" {   // ignore tileset animation table (the animations are read from the data arrays)
+        reader.expect_any_ident("tileset animation table identifier")?;
+        reader.expect_punct('[')?;
+        reader.expect_punct(']')?;
+        reader.expect_punct('=')?;
+        reader.expect_punct('{')?;
+        while let Some(t) = reader.read_loop()? {
+            if ! t.is_punct('{') {
+                return error(format!("expected '{{', found '{}'", t), t.pos);
+            }
+            reader.read_number(u32::MAX as u64)?;
+            reader.expect_punct(',')?;
+            reader.expect_any_ident("tileset animation data")?;
+            reader.expect_punct('}')?;
+        }
+        reader.expect_punct(';')?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
 pub fn create(
     asset_id: DataAssetId,
     asset_struct: &ValueStruct,
@@ -39,6 +65,8 @@ pub fn create(
 
     let data = array.get_u32_array(project_data)?;
     let name = project_data.extract_asset_name("tileset_data_", array)?;
+    let animations = project_data.get_optional_u16_array("tileset_anims_", name).and_then(|data| TileAnimation::list_from_data(data));
+    let terrains = project_data.get_editor_u32_array("tileset_terrains_", name).and_then(|data| TilesetTerrain::list_from_data(data));
 
     let want_stride = width.div_ceil(4);
     if stride != want_stride {
@@ -61,5 +89,7 @@ pub fn create(
         height,
         num_tiles,
         data: image_converter.get_image_pixels(data, width, height, num_tiles),
+        terrains: terrains.unwrap_or_default(),
+        animations: animations.unwrap_or_default(),
     })
 }
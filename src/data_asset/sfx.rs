@@ -1,3 +1,170 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum SfxSynthWave {
+    Square,
+    Sawtooth,
+    Triangle,
+    Sine,
+    Noise,
+}
+
+impl SfxSynthWave {
+    pub const ALL: &[SfxSynthWave] = &[
+        SfxSynthWave::Square,
+        SfxSynthWave::Sawtooth,
+        SfxSynthWave::Triangle,
+        SfxSynthWave::Sine,
+        SfxSynthWave::Noise,
+    ];
+
+    const TEXT: &[&str] = &[
+        "Square",
+        "Sawtooth",
+        "Triangle",
+        "Sine",
+        "Noise",
+    ];
+
+    pub fn text(&self) -> &'static str {
+        Self::TEXT[*self as usize]
+    }
+
+    pub fn from_u32(n: u32) -> Option<Self> {
+        Self::ALL.get(n as usize).copied()
+    }
+}
+
+/**
+Parameters used by the sfx generator to render the samples of an
+`Sfx`. They are kept with the sfx so the sound can be tweaked later.
+
+Except for `wave` and `sample_rate`, all parameters are normalized to
+the range 0..1 (or -1..1 for sweeps and slides); see
+`misc::sfx_synth` for how they're interpreted.
+*/
+#[derive(Clone)]
+pub struct SfxSynthParams {
+    pub wave: SfxSynthWave,
+    pub sample_rate: u32,
+
+    // wave shape
+    pub duty: f32,
+    pub duty_sweep: f32,
+    pub noise: f32,
+
+    // envelope
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub sustain_level: f32,
+    pub release: f32,
+
+    // frequency
+    pub base_freq: f32,
+    pub min_freq: f32,
+    pub freq_slide: f32,
+    pub freq_delta_slide: f32,
+
+    // vibrato
+    pub vibrato_depth: f32,
+    pub vibrato_speed: f32,
+
+    // arpeggio
+    pub arp_mult: f32,
+    pub arp_speed: f32,
+
+    // filters
+    pub lp_cutoff: f32,
+    pub lp_sweep: f32,
+    pub lp_resonance: f32,
+    pub hp_cutoff: f32,
+    pub hp_sweep: f32,
+
+    pub volume: f32,
+}
+
+impl SfxSynthParams {
+    const DATA_VERSION: u32 = 1;
+    const NUM_VALUES: usize = 21;
+
+    pub fn new() -> Self {
+        SfxSynthParams {
+            wave: SfxSynthWave::Square,
+            sample_rate: 11025,
+            duty: 0.0,
+            duty_sweep: 0.0,
+            noise: 0.0,
+            attack: 0.0,
+            decay: 0.0,
+            sustain: 0.3,
+            sustain_level: 1.0,
+            release: 0.4,
+            base_freq: 0.3,
+            min_freq: 0.0,
+            freq_slide: 0.0,
+            freq_delta_slide: 0.0,
+            vibrato_depth: 0.0,
+            vibrato_speed: 0.0,
+            arp_mult: 0.0,
+            arp_speed: 0.0,
+            lp_cutoff: 1.0,
+            lp_sweep: 0.0,
+            lp_resonance: 0.0,
+            hp_cutoff: 0.0,
+            hp_sweep: 0.0,
+            volume: 0.5,
+        }
+    }
+
+    fn values(&self) -> [f32; Self::NUM_VALUES] {
+        [
+            self.duty, self.duty_sweep, self.noise,
+            self.attack, self.decay, self.sustain, self.sustain_level, self.release,
+            self.base_freq, self.min_freq, self.freq_slide, self.freq_delta_slide,
+            self.vibrato_depth, self.vibrato_speed,
+            self.arp_mult, self.arp_speed,
+            self.lp_cutoff, self.lp_sweep, self.lp_resonance, self.hp_cutoff, self.hp_sweep,
+        ]
+    }
+
+    fn set_values(&mut self, values: &[f32; Self::NUM_VALUES]) {
+        [
+            self.duty, self.duty_sweep, self.noise,
+            self.attack, self.decay, self.sustain, self.sustain_level, self.release,
+            self.base_freq, self.min_freq, self.freq_slide, self.freq_delta_slide,
+            self.vibrato_depth, self.vibrato_speed,
+            self.arp_mult, self.arp_speed,
+            self.lp_cutoff, self.lp_sweep, self.lp_resonance, self.hp_cutoff, self.hp_sweep,
+        ] = *values;
+    }
+
+    // stored as: version, wave, sample_rate, volume, values (as f32 bits)
+    pub fn to_data(&self) -> Vec<u32> {
+        let mut data = vec![Self::DATA_VERSION, self.wave as u32, self.sample_rate, self.volume.to_bits()];
+        data.extend(self.values().iter().map(|v| v.to_bits()));
+        data
+    }
+
+    pub fn from_data(data: &[u32]) -> Option<Self> {
+        if data.len() != 4 + Self::NUM_VALUES || data[0] != Self::DATA_VERSION { return None; }
+        let mut params = SfxSynthParams::new();
+        params.wave = SfxSynthWave::from_u32(data[1])?;
+        params.sample_rate = data[2];
+        params.volume = f32::from_bits(data[3]);
+        let mut values = [0.0; Self::NUM_VALUES];
+        for (value, &bits) in values.iter_mut().zip(data[4..].iter()) {
+            *value = f32::from_bits(bits);
+        }
+        params.set_values(&values);
+        Some(params)
+    }
+}
+
+impl std::hash::Hash for SfxSynthParams {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.to_data().hash(state);
+    }
+}
+
 #[derive(std::hash::Hash)]
 pub struct Sfx {
     pub asset: super::DataAsset,
@@ -6,6 +173,7 @@ pub struct Sfx {
     pub loop_len: u32,
     pub bits_per_sample: u16,
     pub samples: Vec<i16>,
+    pub synth: Option<SfxSynthParams>,
 }
 
 impl Sfx {
@@ -18,6 +186,7 @@ impl Sfx {
             loop_len: 0,
             bits_per_sample: 16,
             samples: Self::gen_sample_data(len as usize),
+            synth: None,
         }
     }
 
@@ -46,6 +215,7 @@ impl super::DuplicableAsset<Sfx> for Sfx {
             loop_len: self.loop_len,
             bits_per_sample: self.bits_per_sample,
             samples: self.samples.clone(),
+            synth: self.synth.clone(),
         }
     }
 }
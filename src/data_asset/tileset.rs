@@ -1,3 +1,184 @@
+#[derive(Clone, Copy, PartialEq, Eq, std::hash::Hash)]
+pub enum TerrainKind {
+    Blob47,
+    Edge16,
+    Corner16,
+}
+
+impl TerrainKind {
+    pub const ALL: &[TerrainKind] = &[
+        TerrainKind::Blob47,
+        TerrainKind::Edge16,
+        TerrainKind::Corner16,
+    ];
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            TerrainKind::Blob47 => "Blob (47 tiles)",
+            TerrainKind::Edge16 => "Edges (16 tiles)",
+            TerrainKind::Corner16 => "Wang corners (16 tiles)",
+        }
+    }
+
+    pub fn from_u32(n: u32) -> Option<Self> {
+        Self::ALL.get(n as usize).copied()
+    }
+
+    pub fn num_tiles(&self) -> usize {
+        match self {
+            TerrainKind::Blob47 => 47,
+            TerrainKind::Edge16 | TerrainKind::Corner16 => 16,
+        }
+    }
+}
+
+/**
+Auto-tile rules for painting a terrain in maps: `tiles` has one tile
+index for each neighbor mask of the terrain kind (see
+`app::editors::utils::terrain_utils` for how the masks are built).
+Unassigned masks use `MapData::NO_TILE`.
+*/
+#[derive(Clone, std::hash::Hash)]
+pub struct TilesetTerrain {
+    pub name: String,
+    pub kind: TerrainKind,
+    pub tiles: Vec<u8>,
+}
+
+impl TilesetTerrain {
+    const DATA_VERSION: u32 = 1;
+
+    pub fn new(name: String, kind: TerrainKind) -> Self {
+        TilesetTerrain {
+            name,
+            kind,
+            tiles: vec![super::MapData::NO_TILE; kind.num_tiles()],
+        }
+    }
+
+    pub fn set_kind(&mut self, kind: TerrainKind) {
+        self.kind = kind;
+        self.tiles.resize(kind.num_tiles(), super::MapData::NO_TILE);
+    }
+
+    pub fn contains_tile(&self, tile: u8) -> bool {
+        tile != super::MapData::NO_TILE && self.tiles.contains(&tile)
+    }
+
+    pub fn list_to_data(terrains: &[TilesetTerrain]) -> Vec<u32> {
+        let mut data = vec![Self::DATA_VERSION, terrains.len() as u32];
+        for terrain in terrains {
+            let name = terrain.name.as_bytes();
+            data.push(terrain.kind as u32);
+            data.push(name.len() as u32);
+            data.extend(name.chunks(4).map(|chunk| {
+                chunk.iter().enumerate().fold(0, |word, (i, &b)| word | ((b as u32) << (8 * i)))
+            }));
+            data.extend(terrain.tiles.iter().map(|&tile| tile as u32));
+        }
+        data
+    }
+
+    pub fn list_from_data(data: &[u32]) -> Option<Vec<TilesetTerrain>> {
+        if data.len() < 2 || data[0] != Self::DATA_VERSION { return None; }
+        let mut terrains = Vec::new();
+        let mut pos = 2;
+        for _ in 0..data[1] {
+            let kind = TerrainKind::from_u32(*data.get(pos)?)?;
+            let name_len = *data.get(pos + 1)? as usize;
+            pos += 2;
+            let name_words = data.get(pos .. pos + name_len.div_ceil(4))?;
+            let name_bytes = name_words.iter().flat_map(|word| word.to_le_bytes()).take(name_len).collect::<Vec<_>>();
+            pos += name_words.len();
+            let tiles = data.get(pos .. pos + kind.num_tiles())?.iter().map(|&tile| tile as u8).collect();
+            pos += kind.num_tiles();
+            terrains.push(TilesetTerrain {
+                name: String::from_utf8_lossy(&name_bytes).into_owned(),
+                kind,
+                tiles,
+            });
+        }
+        Some(terrains)
+    }
+}
+
+/**
+Animated tile: maps showing `tile` cycle through `frame_tiles`, showing
+each one for the corresponding duration in `frame_durations` (in
+milliseconds).
+*/
+#[derive(Clone, std::hash::Hash)]
+pub struct TileAnimation {
+    pub tile: u8,
+    pub frame_tiles: Vec<u8>,
+    pub frame_durations: Vec<u16>,
+}
+
+impl TileAnimation {
+    pub const DEFAULT_DURATION: u16 = 200;
+
+    pub fn new(tile: u8) -> Self {
+        TileAnimation {
+            tile,
+            frame_tiles: vec![tile],
+            frame_durations: vec![Self::DEFAULT_DURATION],
+        }
+    }
+
+    pub fn total_duration(&self) -> u32 {
+        self.frame_durations.iter().map(|&duration| duration as u32).sum()
+    }
+
+    /**
+     * Return the tile shown at the given time (in milliseconds).
+     */
+    pub fn tile_at(&self, time: u64) -> u8 {
+        let total = self.total_duration();
+        if total == 0 { return self.tile; }
+        let mut time = (time % total as u64) as u32;
+        for (&tile, &duration) in self.frame_tiles.iter().zip(self.frame_durations.iter()) {
+            if time < duration as u32 { return tile; }
+            time -= duration as u32;
+        }
+        self.tile
+    }
+
+    /**
+     * Convert to the format used in the game data: for each animation,
+     * the tile, the number of frames and the (tile, duration) of each
+     * frame.
+     */
+    pub fn list_to_data(animations: &[TileAnimation]) -> Vec<u16> {
+        let mut data = Vec::new();
+        for anim in animations {
+            data.push(anim.tile as u16);
+            data.push(anim.frame_tiles.len() as u16);
+            for (&tile, &duration) in anim.frame_tiles.iter().zip(anim.frame_durations.iter()) {
+                data.push(tile as u16);
+                data.push(duration);
+            }
+        }
+        data
+    }
+
+    pub fn list_from_data(data: &[u16]) -> Option<Vec<TileAnimation>> {
+        let mut animations = Vec::new();
+        let mut pos = 0;
+        while pos < data.len() {
+            let tile = *data.get(pos)? as u8;
+            let num_frames = *data.get(pos + 1)? as usize;
+            let frames = data.get(pos + 2 .. pos + 2 + 2 * num_frames)?;
+            pos += 2 + 2 * num_frames;
+            animations.push(TileAnimation {
+                tile,
+                frame_tiles: frames.chunks_exact(2).map(|frame| frame[0] as u8).collect(),
+                frame_durations: frames.chunks_exact(2).map(|frame| frame[1]).collect(),
+            });
+        }
+        Some(animations)
+    }
+}
+
 #[derive(std::hash::Hash)]
 pub struct Tileset {
     pub asset: super::DataAsset,
@@ -5,6 +186,8 @@ pub struct Tileset {
     pub height: u32,
     pub num_tiles: u32,
     pub data: Vec<u8>,
+    pub terrains: Vec<TilesetTerrain>,
+    pub animations: Vec<TileAnimation>,
 }
 
 impl Tileset {
@@ -18,7 +201,66 @@ impl Tileset {
             height: Self::TILE_SIZE,
             num_tiles: 1,
             data: vec![Self::EMPTY_COLOR; (Self::TILE_SIZE * Self::TILE_SIZE) as usize],
+            terrains: Vec::new(),
+            animations: Vec::new(),
+        }
+    }
+
+    /**
+     * Unassign the given tiles from all terrains (used before the tiles
+     * are removed from the tileset).
+     */
+    pub fn clear_terrain_tiles(&mut self, start: u8, count: u8) {
+        let range = start as u32 .. start as u32 + count as u32;
+        for terrain in self.terrains.iter_mut() {
+            for tile in terrain.tiles.iter_mut() {
+                if range.contains(&(*tile as u32)) {
+                    *tile = super::MapData::NO_TILE;
+                }
+            }
+        }
+    }
+
+    /**
+     * Remove the animations of the given tiles and the frames that use
+     * them (used before the tiles are removed from the tileset).
+     */
+    pub fn clear_animation_tiles(&mut self, start: u8, count: u8) {
+        let range = start as u32 .. start as u32 + count as u32;
+        for anim in self.animations.iter_mut() {
+            for tile in std::iter::once(&mut anim.tile).chain(anim.frame_tiles.iter_mut()) {
+                if range.contains(&(*tile as u32)) {
+                    *tile = super::MapData::NO_TILE;
+                }
+            }
+        }
+        self.remove_empty_animation_tiles();
+    }
+
+    /**
+     * Remove the animation frames with no tile, and the animations with
+     * no tile or no frames.
+     */
+    pub fn remove_empty_animation_tiles(&mut self) {
+        for anim in self.animations.iter_mut() {
+            (anim.frame_tiles, anim.frame_durations) = anim.frame_tiles.iter().zip(anim.frame_durations.iter())
+                .filter(|&(&tile, _)| tile != super::MapData::NO_TILE)
+                .map(|(&tile, &duration)| (tile, duration))
+                .unzip();
+        }
+        self.animations.retain(|anim| anim.tile != super::MapData::NO_TILE && ! anim.frame_tiles.is_empty());
+    }
+
+    /**
+     * Return a table that maps each tile to the tile shown at the given
+     * time (in milliseconds) according to the animations.
+     */
+    pub fn animated_tiles(&self, time: u64) -> [u8; 256] {
+        let mut tiles = std::array::from_fn(|tile| tile as u8);
+        for anim in self.animations.iter() {
+            tiles[anim.tile as usize] = anim.tile_at(time);
         }
+        tiles
     }
 }
 
@@ -30,6 +272,8 @@ impl super::DuplicableAsset<Tileset> for Tileset {
             height: self.height,
             num_tiles: self.num_tiles,
             data: self.data.clone(),
+            terrains: self.terrains.clone(),
+            animations: self.animations.clone(),
         }
     }
 }
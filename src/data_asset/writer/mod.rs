@@ -605,6 +605,46 @@ impl<'a> ProjectDataWriter<'a> {
         }
         self.write("};\n");
         self.write("\n");
+
+        self.write_tileset_animations()?;
+        Ok(())
+    }
+
+    fn write_tileset_animation_data(&self, tileset: &super::Tileset, name_id: &str) {
+        self.write(format!("static const uint16_t {}_tileset_anims_{}[] = {{\n", self.ident.prefix_lower, name_id));
+        for anim in tileset.animations.iter() {
+            self.write(format!("  // tile {}\n", anim.tile));
+            self.write("  ");
+            for value in super::TileAnimation::list_to_data(std::slice::from_ref(anim)) {
+                self.write(format!("{},", value));
+            }
+            self.write("\n");
+        }
+        self.write("};\n");
+        self.write("\n");
+    }
+
+    fn write_tileset_animations(&self) -> Result<()> {
+        for id in self.store.asset_ids.tilesets.iter() {
+            if let Some(tileset) = self.store.assets.tilesets.get(id) && ! tileset.animations.is_empty() {
+                let name_id = self.ident.get_asset_name_id(DataAssetType::Tileset, *id)?;
+                self.write_tileset_animation_data(tileset, name_id);
+            }
+        }
+
+        self.write(format!("const struct {}_TILESET_ANIMS {}_tileset_anims[] = {{\n", self.ident.prefix_upper, self.ident.prefix_lower));
+        for id in self.store.asset_ids.tilesets.iter() {
+            if let Some(tileset) = self.store.assets.tilesets.get(id) {
+                if tileset.animations.is_empty() {
+                    self.write("  { 0, NULL },\n");
+                } else {
+                    let name_id = self.ident.get_asset_name_id(DataAssetType::Tileset, *id)?;
+                    self.write(format!("  {{ {}, {}_tileset_anims_{} }},\n", tileset.animations.len(), self.ident.prefix_lower, name_id));
+                }
+            }
+        }
+        self.write("};\n");
+        self.write("\n");
         Ok(())
     }
 
@@ -887,6 +927,35 @@ impl<'a> ProjectDataWriter<'a> {
         Ok(())
     }
 
+    fn write_map_fx_types(&self) -> Result<()> {
+        if self.store.map_fx_types.is_empty() { return Ok(()); }
+
+        let names = self.store.map_fx_types.iter().map(|fx_type| {
+            super::MapEffectType::sanitize_name(&fx_type.name).to_ascii_uppercase()
+        }).collect::<Vec<_>>();
+        for (index, name) in names.iter().enumerate() {
+            if name.is_empty() {
+                return Err(Error::other(format!("map effect type {} has no name", index)));
+            }
+            if names[..index].contains(name) {
+                return Err(Error::other(format!("duplicate map effect type name '{}'", self.store.map_fx_types[index].name)));
+            }
+        }
+
+        self.write("// ================================================================\n");
+        self.write("// === MAP EFFECT TYPES\n");
+        self.write("// ================================================================\n");
+        self.write("\n");
+
+        self.write(format!("enum {}_MAP_FX_TYPES {{\n", self.ident.prefix_upper));
+        for name_upper in names.iter() {
+            self.write(format!("  {}_MAP_FX_{},\n", self.ident.prefix_upper, name_upper));
+        }
+        self.write("};\n");
+        self.write("\n");
+        Ok(())
+    }
+
     fn write_animation_names(&self) -> Result<()> {
         self.write("// ================================================================\n");
         self.write("// === SPRITE ANIMATION LOOP NAMES\n");
@@ -1209,6 +1278,74 @@ impl<'a> ProjectDataWriter<'a> {
         Ok(())
     }
 
+    // =========================================================================
+    // === EDITOR DATA
+    // =========================================================================
+
+    fn write_sfx_synth_params(&self) -> Result<()> {
+        for id in self.store.asset_ids.sfxs.iter() {
+            if let Some(sfx) = self.store.assets.sfxs.get(id) && let Some(synth) = &sfx.synth {
+                let name_id = self.ident.get_asset_name_id(DataAssetType::Sfx, *id)?;
+                self.write(format!("static const uint32_t {}_sfx_synth_{}[] = {{", self.ident.prefix_lower, name_id));
+                for (i, value) in synth.to_data().iter().enumerate() {
+                    if i.is_multiple_of(8) { self.write("\n  "); }
+                    self.write(format!("{:#010x},", value));
+                }
+                self.write("\n};\n");
+                self.write("\n");
+            }
+        }
+        Ok(())
+    }
+
+    fn write_tileset_terrains(&self) -> Result<()> {
+        for id in self.store.asset_ids.tilesets.iter() {
+            if let Some(tileset) = self.store.assets.tilesets.get(id) && ! tileset.terrains.is_empty() {
+                let name_id = self.ident.get_asset_name_id(DataAssetType::Tileset, *id)?;
+                self.write(format!("static const uint32_t {}_tileset_terrains_{}[] = {{", self.ident.prefix_lower, name_id));
+                for (i, value) in super::TilesetTerrain::list_to_data(&tileset.terrains).iter().enumerate() {
+                    if i.is_multiple_of(8) { self.write("\n  "); }
+                    self.write(format!("{:#010x},", value));
+                }
+                self.write("\n};\n");
+                self.write("\n");
+            }
+        }
+        Ok(())
+    }
+
+    fn write_map_fx_colors(&self) -> Result<()> {
+        if self.store.map_fx_types.is_empty() { return Ok(()); }
+        self.write(format!("static const uint32_t {}_map_fx_colors[] = {{", self.ident.prefix_lower));
+        for (i, fx_type) in self.store.map_fx_types.iter().enumerate() {
+            if i.is_multiple_of(8) { self.write("\n  "); }
+            self.write(format!("{:#010x},", fx_type.color));
+        }
+        self.write("\n};\n");
+        self.write("\n");
+        Ok(())
+    }
+
+    fn write_editor_data(&self) -> Result<()> {
+        if ! self.store.assets.sfxs.iter().any(|sfx| sfx.synth.is_some()) &&
+            ! self.store.assets.tilesets.iter().any(|tileset| ! tileset.terrains.is_empty()) &&
+            self.store.map_fx_types.is_empty() { return Ok(()); }
+
+        self.write("// ================================================================\n");
+        self.write("// === EDITOR DATA (not used by the game)\n");
+        self.write("// ================================================================\n");
+        self.write("\n");
+
+        self.write(format!("#if {}_EDITOR_DATA\n", self.ident.prefix_upper));
+        self.write("\n");
+        self.write_sfx_synth_params()?;
+        self.write_tileset_terrains()?;
+        self.write_map_fx_colors()?;
+        self.write(format!("#endif /* {}_EDITOR_DATA */\n", self.ident.prefix_upper));
+        self.write("\n");
+        Ok(())
+    }
+
     // =========================================================================
     // === SCRIPTS
     // =========================================================================
@@ -1360,7 +1497,9 @@ impl<'a> ProjectDataWriter<'a> {
         self.write_scripts()?;
 
         self.write_data_end()?;
+        self.write_editor_data()?;
 
+        self.write_map_fx_types()?;
         self.write_animation_names()?;
         self.write_room_item_names()?;
         self.write_world_item_names()?;
@@ -0,0 +1,128 @@
+use super::colors;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ColorAdjustment {
+    pub brightness: f32,   // -1.0 ..= 1.0
+    pub contrast: f32,     // -1.0 ..= 1.0
+    pub hue: f32,          // degrees
+    pub saturation: f32,   // -1.0 ..= 1.0
+    pub steps: [i32; 3],   // red, green and blue steps added after the other adjustments
+}
+
+impl ColorAdjustment {
+    pub fn new() -> Self {
+        ColorAdjustment {
+            brightness: 0.0,
+            contrast: 0.0,
+            hue: 0.0,
+            saturation: 0.0,
+            steps: [0; 3],
+        }
+    }
+
+    fn color_to_channels(color: u8, bits_per_pixel: u8) -> [f32; 3] {
+        if bits_per_pixel == 6 {
+            colors::color_to_6bit_rgb(color).map(|c| c as f32 / 3.0)
+        } else {
+            [
+                (color & 0x7) as f32 / 7.0,
+                ((color >> 3) & 0x7) as f32 / 7.0,
+                ((color >> 6) & 0x3) as f32 / 3.0,
+            ]
+        }
+    }
+
+    fn channel_levels(bits_per_pixel: u8) -> [i32; 3] {
+        if bits_per_pixel == 6 { [3, 3, 3] } else { [7, 7, 3] }
+    }
+
+    fn levels_to_color(levels: [i32; 3], bits_per_pixel: u8) -> u8 {
+        let [r, g, b] = levels.map(|c| c as u8);
+        if bits_per_pixel == 6 {
+            colors::color_6bit_rgb_to_color(r, g, b)
+        } else {
+            r | (g << 3) | (b << 6)
+        }
+    }
+
+    fn adjust_channels(&self, [r, g, b]: [f32; 3]) -> [f32; 3] {
+        let (mut h, mut s, v) = rgb_to_hsv(r, g, b);
+        h = (h + self.hue).rem_euclid(360.0);
+        s = if self.saturation < 0.0 { s * (1.0 + self.saturation) } else { s + (1.0 - s) * self.saturation };
+        let (r, g, b) = hsv_to_rgb(h, s, v);
+        let contrast = if self.contrast < 0.0 { 1.0 + self.contrast } else { 1.0 / (1.0 - self.contrast).max(0.01) };
+        [r, g, b].map(|c| ((c - 0.5) * contrast + 0.5 + self.brightness).clamp(0.0, 1.0))
+    }
+
+    /**
+     * Return the adjusted color, snapped to the colors available with the
+     * given bits per pixel (8 or 6).
+     */
+    pub fn adjust(&self, color: u8, bits_per_pixel: u8) -> u8 {
+        let max = Self::channel_levels(bits_per_pixel);
+        let channels = self.adjust_channels(Self::color_to_channels(color, bits_per_pixel));
+        let levels = std::array::from_fn(|i| {
+            ((channels[i] * max[i] as f32).round() as i32 + self.steps[i]).clamp(0, max[i])
+        });
+        Self::levels_to_color(levels, bits_per_pixel)
+    }
+
+    /**
+     * Build a color map for the adjustment. The transparent color is kept,
+     * and no other color is turned into it.
+     */
+    pub fn color_map(&self, bits_per_pixel: u8) -> [u8; 256] {
+        let mut color_map = [0; 256];
+        for (color, new_color) in color_map.iter_mut().enumerate() {
+            let color = color as u8;
+            *new_color = if color == colors::TRANSPARENT {
+                color
+            } else {
+                let adjusted = self.adjust(color, bits_per_pixel);
+                if adjusted == colors::TRANSPARENT {
+                    // use the nearest darker green
+                    let mut levels = Self::channel_levels(bits_per_pixel);
+                    levels[0] = 0;
+                    levels[1] -= 1;
+                    levels[2] = 0;
+                    Self::levels_to_color(levels, bits_per_pixel)
+                } else {
+                    adjusted
+                }
+            };
+        }
+        color_map
+    }
+}
+
+fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+    (h, s, max)
+}
+
+fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    (r + m, g + m, b + m)
+}
@@ -92,6 +92,24 @@ pub trait ImageCollection {
         }
     }
 
+    /**
+     * Replace every pixel color `c` in the item with `color_map[c]`.
+     */
+    fn remap_colors(&mut self, item: u32, color_map: &[u8; 256]) -> bool {
+        if item >= self.num_items() { return false; }
+        let item_size = (self.width() * self.height()) as usize;
+        let start = item as usize * item_size;
+        let mut changed = false;
+        for pixel in self.data_mut()[start..start + item_size].iter_mut() {
+            let color = color_map[*pixel as usize];
+            if *pixel != color {
+                *pixel = color;
+                changed = true;
+            }
+        }
+        changed
+    }
+
     fn shift_pixels(&mut self, item: u32, dx: i32, dy: i32, wrap: bool, fill_color: u8) {
         let width = self.width() as usize;
         let height = self.height() as usize;
@@ -217,6 +235,66 @@ pub trait ImageCollection {
         true
     }
 
+    fn draw_line(&mut self, item: u32, start: (i32, i32), end: (i32, i32), color: u8) {
+        for (x, y) in line_points(start, end) {
+            self.set_pixel(x, y, item, color);
+        }
+    }
+
+    fn draw_rect(&mut self, item: u32, (x0, y0): (i32, i32), (x1, y1): (i32, i32), filled: bool, color: u8) {
+        let (x0, x1) = (x0.min(x1), x0.max(x1));
+        let (y0, y1) = (y0.min(y1), y0.max(y1));
+        for y in y0..=y1 {
+            if filled || y == y0 || y == y1 {
+                for x in x0..=x1 {
+                    self.set_pixel(x, y, item, color);
+                }
+            } else {
+                self.set_pixel(x0, y, item, color);
+                self.set_pixel(x1, y, item, color);
+            }
+        }
+    }
+
+    fn draw_ellipse(&mut self, item: u32, (x0, y0): (i32, i32), (x1, y1): (i32, i32), filled: bool, color: u8) {
+        let (x0, x1) = (x0.min(x1), x0.max(x1));
+        let (y0, y1) = (y0.min(y1), y0.max(y1));
+        let half_w = (x1 - x0 + 1) as f32 / 2.0;
+        let half_h = (y1 - y0 + 1) as f32 / 2.0;
+        let cx = x0 as f32 + half_w;
+        let cy = y0 as f32 + half_h;
+
+        // horizontal span of the ellipse at each row, checking pixel centers
+        let spans = (y0..=y1).map(|y| {
+            let dy = (y as f32 + 0.5 - cy) / half_h;
+            let hw = half_w * (1.0 - dy * dy).max(0.0).sqrt();
+            let left = (cx - hw - 0.5).ceil() as i32;
+            let right = (cx + hw - 0.5).floor() as i32;
+            if left <= right { (left, right) } else { ((cx - 0.5).floor() as i32, (cx - 0.5).ceil() as i32) }
+        }).collect::<Vec<_>>();
+
+        for (i, &(left, right)) in spans.iter().enumerate() {
+            let y = y0 + i as i32;
+            if filled || i == 0 || i == spans.len() - 1 {
+                for x in left..=right {
+                    self.set_pixel(x, y, item, color);
+                }
+            } else {
+                // extend the outline to reach the spans of the rows above and below
+                let (up_left, up_right) = spans[i - 1];
+                let (down_left, down_right) = spans[i + 1];
+                let left_end = (up_left.max(down_left) - 1).clamp(left, right);
+                let right_start = (up_right.min(down_right) + 1).clamp(left, right);
+                for x in left..=left_end {
+                    self.set_pixel(x, y, item, color);
+                }
+                for x in right_start..=right {
+                    self.set_pixel(x, y, item, color);
+                }
+            }
+        }
+    }
+
     fn h_flip(&mut self, item: u32) {
         if item > self.num_items() { return; }
 
@@ -328,7 +406,14 @@ pub trait ImageCollection {
             let src = ((iy + src_y) * frag.width() + src_x) as usize;
             let dest = ((item * self_height + y + iy) * self_width + x) as usize;
             let frag_data = frag.data();
-            if transparent {
+            if let Some(mask) = &frag.mask {
+                for ix in 0..width as usize {
+                    let pixel = frag_data[src+ix];
+                    if mask.get((src_x as usize + ix) as i32, (src_y + iy) as i32) && ! (transparent && pixel == colors::TRANSPARENT) {
+                        data[dest+ix] = pixel;
+                    }
+                }
+            } else if transparent {
                 for ix in 0..width as usize {
                     let pixel = frag_data[src+ix];
                     if pixel != colors::TRANSPARENT {
@@ -342,6 +427,27 @@ pub trait ImageCollection {
     }
 }
 
+/**
+ * Return the points of the line from `start` to `end` (inclusive).
+ */
+pub fn line_points((x0, y0): (i32, i32), (x1, y1): (i32, i32)) -> Vec<(i32, i32)> {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let (mut x, mut y) = (x0, y0);
+    let mut points = Vec::with_capacity((dx - dy + 1) as usize);
+    loop {
+        points.push((x, y));
+        if x == x1 && y == y1 { break; }
+        let e2 = 2 * err;
+        if e2 >= dy { err += dy; x += sx; }
+        if e2 <= dx { err += dx; y += sy; }
+    }
+    points
+}
+
 pub fn default_texture_slot(transparent: bool, float: bool) -> TextureSlot {
     if float {
         if transparent {
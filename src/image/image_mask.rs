@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+
+use egui::{Rect, Pos2, Vec2};
+
+use super::{
+    ImageCollection,
+    ImagePixels,
+};
+
+#[derive(Clone, Debug)]
+pub struct ImageMask {
+    pub width: u32,
+    pub height: u32,
+    bits: Vec<bool>,
+}
+
+impl ImageMask {
+    pub fn new(width: u32, height: u32) -> Self {
+        ImageMask {
+            width,
+            height,
+            bits: vec![false; (width * height) as usize],
+        }
+    }
+
+    pub fn from_rect(width: u32, height: u32, rect: Rect) -> Self {
+        let mut mask = Self::new(width, height);
+        let rect = rect.intersect(Rect::from_min_size(Pos2::ZERO, Vec2::new(width as f32, height as f32)));
+        if rect.is_positive() {
+            for y in rect.min.y as u32 .. rect.max.y as u32 {
+                for x in rect.min.x as u32 .. rect.max.x as u32 {
+                    mask.bits[(y * width + x) as usize] = true;
+                }
+            }
+        }
+        mask
+    }
+
+    /**
+     * Select the pixels whose centers are inside the polygon (using the
+     * even-odd rule).
+     */
+    pub fn from_polygon(width: u32, height: u32, points: &[Pos2]) -> Self {
+        let mut mask = Self::new(width, height);
+        if points.len() < 3 { return mask; }
+
+        let mut crossings = Vec::new();
+        for y in 0..height {
+            let cy = y as f32 + 0.5;
+            crossings.clear();
+            for (i, &p1) in points.iter().enumerate() {
+                let p2 = points[(i + 1) % points.len()];
+                if (p1.y <= cy) != (p2.y <= cy) {
+                    crossings.push(p1.x + (cy - p1.y) / (p2.y - p1.y) * (p2.x - p1.x));
+                }
+            }
+            crossings.sort_by(f32::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                let start = (span[0] - 0.5).ceil().max(0.0) as u32;
+                let end = ((span[1] - 0.5).floor() + 1.0).clamp(0.0, width as f32) as u32;
+                for x in start..end {
+                    mask.bits[(y * width + x) as usize] = true;
+                }
+            }
+        }
+        mask
+    }
+
+    /**
+     * Select the pixels with the same color as the one at (x, y), either
+     * contiguous to it or anywhere in the image. If `limit` is given,
+     * only pixels selected in it are considered.
+     */
+    pub fn from_color(image: &impl ImageCollection, item: u32, (x, y): (i32, i32), contiguous: bool, limit: Option<&ImageMask>) -> Self {
+        let width = image.width();
+        let height = image.height();
+        let mut mask = Self::new(width, height);
+        if x < 0 || y < 0 || x as u32 >= width || y as u32 >= height || item >= image.num_items() { return mask; }
+
+        let allowed = |x: i32, y: i32| limit.is_none_or(|limit| limit.get(x, y));
+        if ! allowed(x, y) { return mask; }
+        let color = image.get_pixel(x, y, item);
+
+        if ! contiguous {
+            for py in 0..height as i32 {
+                for px in 0..width as i32 {
+                    if allowed(px, py) && image.get_pixel(px, py, item) == color {
+                        mask.set(px, py, true);
+                    }
+                }
+            }
+            return mask;
+        }
+
+        let mut work = VecDeque::new();
+        mask.set(x, y, true);
+        work.push_back((x, y));
+        while let Some((x, y)) = work.pop_front() {
+            for (nx, ny) in [(x-1, y), (x+1, y), (x, y-1), (x, y+1)] {
+                if nx < 0 || ny < 0 || nx as u32 >= width || ny as u32 >= height { continue; }
+                if mask.get(nx, ny) || ! allowed(nx, ny) || image.get_pixel(nx, ny, item) != color { continue; }
+                mask.set(nx, ny, true);
+                work.push_back((nx, ny));
+            }
+        }
+        mask
+    }
+
+    /**
+     * Create a mask from pixels, selecting the non-zero ones.
+     */
+    pub fn from_pixels(pixels: &ImagePixels) -> Self {
+        ImageMask {
+            width: pixels.width,
+            height: pixels.height,
+            bits: pixels.data.iter().map(|&pixel| pixel != 0).collect(),
+        }
+    }
+
+    /**
+     * Convert to pixels (1 for selected, 0 otherwise), so the mask can go
+     * through the same transformations as an image.
+     */
+    pub fn to_pixels(&self) -> ImagePixels {
+        ImagePixels::new(self.width, self.height, self.bits.iter().map(|&bit| bit as u8).collect())
+    }
+
+    pub fn sub_mask(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let mut mask = Self::new(width, height);
+        for my in 0..height as i32 {
+            for mx in 0..width as i32 {
+                mask.set(mx, my, self.get(x as i32 + mx, y as i32 + my));
+            }
+        }
+        mask
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height { return false; }
+        self.bits[(y as u32 * self.width + x as u32) as usize]
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, value: bool) {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height { return; }
+        self.bits[(y as u32 * self.width + x as u32) as usize] = value;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        ! self.bits.contains(&true)
+    }
+
+    pub fn add(&mut self, other: &ImageMask) {
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                if other.get(x, y) { self.set(x, y, true); }
+            }
+        }
+    }
+
+    pub fn subtract(&mut self, other: &ImageMask) {
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                if other.get(x, y) { self.set(x, y, false); }
+            }
+        }
+    }
+
+    pub fn bounding_rect(&self) -> Option<Rect> {
+        let mut rect = Rect::NOTHING;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.bits[(y * self.width + x) as usize] {
+                    rect.extend_with(Pos2::new(x as f32, y as f32));
+                    rect.extend_with(Pos2::new((x + 1) as f32, (y + 1) as f32));
+                }
+            }
+        }
+        if rect.is_positive() { Some(rect) } else { None }
+    }
+
+    /**
+     * Return the border of the selected area as horizontal and vertical
+     * line segments in image coordinates.
+     */
+    pub fn edges(&self) -> Vec<(Pos2, Pos2)> {
+        let mut edges = Vec::new();
+        let width = self.width as i32;
+        let height = self.height as i32;
+
+        // horizontal edges
+        for y in 0..=height {
+            let mut start = None;
+            for x in 0..=width {
+                let is_edge = x < width && self.get(x, y-1) != self.get(x, y);
+                match (is_edge, start) {
+                    (true, None) => { start = Some(x); }
+                    (false, Some(sx)) => {
+                        edges.push((Pos2::new(sx as f32, y as f32), Pos2::new(x as f32, y as f32)));
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // vertical edges
+        for x in 0..=width {
+            let mut start = None;
+            for y in 0..=height {
+                let is_edge = y < height && self.get(x-1, y) != self.get(x, y);
+                match (is_edge, start) {
+                    (true, None) => { start = Some(y); }
+                    (false, Some(sy)) => {
+                        edges.push((Pos2::new(x as f32, sy as f32), Pos2::new(x as f32, y as f32)));
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        edges
+    }
+}
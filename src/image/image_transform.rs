@@ -0,0 +1,187 @@
+use super::ImagePixels;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ImageTransform {
+    Scale(f32, f32),     // horizontal and vertical scale factors
+    Rotate(f32),         // clockwise angle in degrees
+    Skew(f32, f32),      // horizontal and vertical skew angles in degrees
+    Outline(u8),         // outline color
+    DropShadow(u8),      // shadow color
+}
+
+impl ImageTransform {
+    /**
+     * Apply the transform to the given pixels, using `empty_color` for
+     * the pixels of the result that don't come from the source.
+     */
+    pub fn apply(&self, pixels: &ImagePixels, empty_color: u8) -> ImagePixels {
+        match *self {
+            ImageTransform::Scale(x, y) => scale(pixels, x, y),
+            ImageTransform::Rotate(angle) => rotsprite(pixels, angle, empty_color),
+            ImageTransform::Skew(x, y) => skew(pixels, x, y, empty_color),
+            ImageTransform::Outline(color) => outline(pixels, color, empty_color),
+            ImageTransform::DropShadow(color) => drop_shadow(pixels, color, empty_color),
+        }
+    }
+
+    /**
+     * Offset of the transformed image relative to the source image, so
+     * that the result stays in place.
+     */
+    pub fn offset(&self, src_width: u32, src_height: u32, width: u32, height: u32) -> (i32, i32) {
+        match self {
+            ImageTransform::DropShadow(..) => (0, 0),
+            _ => (
+                ((src_width as f32 - width as f32) / 2.0).round_ties_even() as i32,
+                ((src_height as f32 - height as f32) / 2.0).round_ties_even() as i32,
+            ),
+        }
+    }
+}
+
+fn scale(pixels: &ImagePixels, scale_x: f32, scale_y: f32) -> ImagePixels {
+    let width = ((pixels.width as f32 * scale_x).round() as u32).max(1);
+    let height = ((pixels.height as f32 * scale_y).round() as u32).max(1);
+    let mut data = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        let src_y = ((y as u64 * pixels.height as u64) / height as u64) as u32;
+        for x in 0..width {
+            let src_x = ((x as u64 * pixels.width as u64) / width as u64) as u32;
+            data.push(pixels.data[(src_y * pixels.width + src_x) as usize]);
+        }
+    }
+    ImagePixels::new(width, height, data)
+}
+
+/**
+ * Scale2x (aka EPX): doubles the image size without adding new colors,
+ * keeping diagonal edges smooth.
+ */
+fn scale2x(pixels: &ImagePixels) -> ImagePixels {
+    let w = pixels.width as i32;
+    let h = pixels.height as i32;
+    let get = |x: i32, y: i32| pixels.data[(y.clamp(0, h-1) * w + x.clamp(0, w-1)) as usize];
+    let width = pixels.width * 2;
+    let mut data = vec![0; (width * pixels.height * 2) as usize];
+    for y in 0..h {
+        for x in 0..w {
+            let p = get(x, y);
+            let a = get(x, y-1);
+            let b = get(x+1, y);
+            let c = get(x-1, y);
+            let d = get(x, y+1);
+            let e0 = if c == a && c != d && a != b { a } else { p };
+            let e1 = if a == b && a != c && b != d { b } else { p };
+            let e2 = if d == c && d != b && c != a { c } else { p };
+            let e3 = if b == d && b != a && d != c { d } else { p };
+            let top = ((2*y) as u32 * width + (2*x) as u32) as usize;
+            let bot = top + width as usize;
+            data[top] = e0;
+            data[top+1] = e1;
+            data[bot] = e2;
+            data[bot+1] = e3;
+        }
+    }
+    ImagePixels::new(width, pixels.height * 2, data)
+}
+
+/**
+ * Rotate an image by an arbitrary angle using the RotSprite algorithm:
+ * upscale 8x with Scale2x, then sample the rotated result at the
+ * original resolution.
+ */
+fn rotsprite(pixels: &ImagePixels, angle: f32, empty_color: u8) -> ImagePixels {
+    const UPSCALE: f32 = 8.0;
+
+    let big = scale2x(&scale2x(&scale2x(pixels)));
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let src_w = pixels.width as f32;
+    let src_h = pixels.height as f32;
+    let width = ((src_w * cos.abs() + src_h * sin.abs()) - 0.001).ceil().max(1.0) as u32;
+    let height = ((src_w * sin.abs() + src_h * cos.abs()) - 0.001).ceil().max(1.0) as u32;
+
+    let mut data = vec![empty_color; (width * height) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - width as f32 / 2.0;
+            let dy = y as f32 + 0.5 - height as f32 / 2.0;
+            let src_x = (dx * cos + dy * sin + src_w / 2.0) * UPSCALE;
+            let src_y = (-dx * sin + dy * cos + src_h / 2.0) * UPSCALE;
+            if src_x < 0.0 || src_y < 0.0 || src_x >= big.width as f32 || src_y >= big.height as f32 { continue; }
+            data[(y * width + x) as usize] = big.data[(src_y as u32 * big.width + src_x as u32) as usize];
+        }
+    }
+    ImagePixels::new(width, height, data)
+}
+
+fn skew(pixels: &ImagePixels, angle_x: f32, angle_y: f32, empty_color: u8) -> ImagePixels {
+    let kx = angle_x.to_radians().tan();
+    let ky = angle_y.to_radians().tan();
+    let src_w = pixels.width as f32;
+    let src_h = pixels.height as f32;
+
+    // x' = x + kx*y, then y' = y + ky*x'
+    let width = (src_w + kx.abs() * src_h).round().max(1.0) as u32;
+    let height = (src_h + ky.abs() * width as f32).round().max(1.0) as u32;
+
+    let mut data = vec![empty_color; (width * height) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - width as f32 / 2.0;
+            let dy = y as f32 + 0.5 - height as f32 / 2.0;
+            let sy = dy - ky * dx;
+            let sx = dx - kx * sy;
+            let src_x = (sx + src_w / 2.0).floor();
+            let src_y = (sy + src_h / 2.0).floor();
+            if src_x < 0.0 || src_y < 0.0 || src_x >= src_w || src_y >= src_h { continue; }
+            data[(y * width + x) as usize] = pixels.data[(src_y as u32 * pixels.width + src_x as u32) as usize];
+        }
+    }
+    ImagePixels::new(width, height, data)
+}
+
+fn outline(pixels: &ImagePixels, color: u8, empty_color: u8) -> ImagePixels {
+    let width = pixels.width + 2;
+    let height = pixels.height + 2;
+    let is_solid = |x: i32, y: i32| {
+        if x < 0 || y < 0 || x >= pixels.width as i32 || y >= pixels.height as i32 { return false; }
+        pixels.data[(y as u32 * pixels.width + x as u32) as usize] != empty_color
+    };
+    let mut data = vec![empty_color; (width * height) as usize];
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            // source coordinates are offset by one pixel
+            let (sx, sy) = (x - 1, y - 1);
+            let pixel = &mut data[(y as u32 * width + x as u32) as usize];
+            if is_solid(sx, sy) {
+                *pixel = pixels.data[(sy as u32 * pixels.width + sx as u32) as usize];
+            } else if is_solid(sx-1, sy) || is_solid(sx+1, sy) || is_solid(sx, sy-1) || is_solid(sx, sy+1) {
+                *pixel = color;
+            }
+        }
+    }
+    ImagePixels::new(width, height, data)
+}
+
+fn drop_shadow(pixels: &ImagePixels, color: u8, empty_color: u8) -> ImagePixels {
+    let width = pixels.width + 1;
+    let height = pixels.height + 1;
+    let get = |x: i32, y: i32| {
+        if x < 0 || y < 0 || x >= pixels.width as i32 || y >= pixels.height as i32 { return empty_color; }
+        pixels.data[(y as u32 * pixels.width + x as u32) as usize]
+    };
+    let mut data = vec![empty_color; (width * height) as usize];
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            let pixel = get(x, y);
+            data[(y as u32 * width + x as u32) as usize] = if pixel != empty_color {
+                pixel
+            } else if get(x-1, y-1) != empty_color {
+                color
+            } else {
+                empty_color
+            };
+        }
+    }
+    ImagePixels::new(width, height, data)
+}
@@ -3,6 +3,9 @@ mod image_collection_io;
 mod texture_manager;
 mod static_image_store;
 mod image_pixels;
+mod image_transform;
+mod image_mask;
+mod color_adjustment;
 mod colorset;
 pub mod colors;
 
@@ -12,6 +15,7 @@ pub use texture_manager::TextureManager;
 pub use static_image_store::StaticImageStore;
 pub use image_collection::{
     default_texture_slot,
+    line_points,
     ImageCollection,
 };
 pub use image_collection_io::{
@@ -23,6 +27,9 @@ pub use image_pixels::{
     ImagePixels,
     ImagePixelsCollection,
 };
+pub use image_transform::ImageTransform;
+pub use image_mask::ImageMask;
+pub use color_adjustment::ColorAdjustment;
 pub use colorset::{
     ColorSet,
     ColorSetCollection,
@@ -31,6 +38,7 @@ pub use colorset::{
 
 use crate::data_asset::DataAssetId;
 
+#[derive(Copy, Clone)]
 pub enum ImageRotation {
     CW90,  // 90 degrees clockwise
     CCW90, // 90 degrees counter-clockwise
@@ -70,6 +78,7 @@ pub struct ImageFragment {
     pub id: DataAssetId,
     pub pixels: ImagePixels,
     pub changed: bool,
+    pub mask: Option<ImageMask>,  // selected pixels of a fragment lifted from a mask selection
 }
 
 impl ImageFragment {
@@ -78,6 +87,7 @@ impl ImageFragment {
             id,
             pixels: ImagePixels::new(width, height, data),
             changed: true,
+            mask: None,
         }
     }
 
@@ -90,6 +100,7 @@ impl ImageFragment {
             id,
             pixels,
             changed: true,
+            mask: None,
         }
     }
 
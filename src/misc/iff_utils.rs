@@ -0,0 +1,247 @@
+use std::io::{Result, Error};
+
+use super::reader::Reader;
+use super::wav_utils::WavFile;
+
+const SVX_COMPRESSION_NONE: u8 = 0;
+const SVX_COMPRESSION_FIB_DELTA: u8 = 1;
+const SVX_CHAN_STEREO: u32 = 6;
+const SVX_FIB_DELTA_TABLE: [i8; 16] = [ -34, -21, -13, -8, -5, -3, -2, -1, 0, 1, 2, 3, 5, 8, 13, 21 ];
+
+const AIFF_LOOP_NONE: u16 = 0;
+
+/**
+ * Read a sample file, detecting whether it's WAV, AIFF or IFF-8SVX.
+ */
+pub fn read_sample_file(data: &[u8]) -> Result<WavFile> {
+    if data.len() >= 12 && &data[0..4] == b"FORM" {
+        match &data[8..12] {
+            b"AIFF" | b"AIFC" => read_aiff(data),
+            b"8SVX" => read_8svx(data),
+            _ => Err(Error::other("unsupported IFF file type")),
+        }
+    } else {
+        WavFile::read(data)
+    }
+}
+
+// Calls `read_chunk` for each chunk of the IFF FORM, with the reader
+// positioned at the start of the chunk data. Chunks are skipped
+// (including padding) after the function returns.
+fn read_iff_chunks(data: &[u8], form_type: &[&[u8; 4]], mut read_chunk: impl FnMut(&[u8; 4], &mut Reader, usize) -> Result<()>) -> Result<()> {
+    let mut r = Reader::new(data);
+    if &r.read_array::<4>()? != b"FORM" { return Err(Error::other("invalid file format")); }
+    let form_size = r.read_u32_be()? as usize;
+    let tag = r.read_array::<4>()?;
+    if ! form_type.contains(&&tag) { return Err(Error::other("invalid IFF form type")); }
+
+    let form_end = form_size.checked_add(8).ok_or_else(|| Error::other("invalid file format"))?.min(data.len());
+    while r.pos() + 8 <= form_end {
+        let tag = r.read_array::<4>()?;
+        let chunk_size = r.read_u32_be()? as usize;
+        let chunk_start = r.pos();
+        if chunk_start.checked_add(chunk_size).is_none_or(|chunk_end| chunk_end > data.len()) {
+            return Err(Error::other(format!("truncated '{}' chunk", String::from_utf8_lossy(&tag))));
+        }
+        read_chunk(&tag, &mut r, chunk_size)?;
+        r.seek((chunk_start + chunk_size + (chunk_size & 1)).min(data.len()))?;
+    }
+    Ok(())
+}
+
+fn split_loop(loop_start: u32, loop_end: u32, len: usize) -> (u32, u32) {
+    let loop_end = loop_end.min(len as u32);
+    if loop_start < loop_end {
+        (loop_start, loop_end - loop_start)
+    } else {
+        (0, 0)
+    }
+}
+
+pub fn read_aiff(data: &[u8]) -> Result<WavFile> {
+    let mut num_channels = 0;
+    let mut num_frames = 0;
+    let mut bits_per_sample = 0;
+    let mut sample_rate = 0.0;
+    let mut little_endian = false;
+    let mut sample_data = None;
+    let mut markers = Vec::new();
+    let mut sustain_loop = None;
+
+    read_iff_chunks(data, &[b"AIFF", b"AIFC"], |tag, r, chunk_size| {
+        match tag {
+            b"COMM" => {
+                num_channels = r.read_u16_be()? as usize;
+                num_frames = r.read_u32_be()? as usize;
+                bits_per_sample = r.read_u16_be()?;
+                sample_rate = r.read_f80_be()?;
+                if chunk_size >= 22 {
+                    // AIFC: compression type
+                    match &r.read_array::<4>()? {
+                        b"NONE" | b"twos" => {}
+                        b"sowt" => { little_endian = true; }
+                        compression => {
+                            return Err(Error::other(format!("unsupported AIFF compression: '{}'", String::from_utf8_lossy(compression))));
+                        }
+                    }
+                }
+            }
+
+            b"SSND" => {
+                let offset = r.read_u32_be()? as usize;
+                r.read_u32_be()?;  // block size
+                r.skip(offset)?;
+                sample_data = Some((r.pos(), chunk_size.saturating_sub(8 + offset)));
+            }
+
+            b"MARK" => {
+                let num_markers = r.read_u16_be()?;
+                for _ in 0..num_markers {
+                    let id = r.read_u16_be()?;
+                    let position = r.read_u32_be()?;
+                    let name_len = r.read_u8()? as usize;
+                    r.skip(name_len + (1 - (name_len & 1)))?;  // name is padded to even length (including its length byte)
+                    markers.push((id, position));
+                }
+            }
+
+            b"INST" => {
+                r.skip(8)?;  // base note, detune, low/high note, low/high velocity, gain
+                let play_mode = r.read_u16_be()?;
+                let begin_marker = r.read_u16_be()?;
+                let end_marker = r.read_u16_be()?;
+                if play_mode != AIFF_LOOP_NONE {
+                    sustain_loop = Some((begin_marker, end_marker));
+                }
+            }
+
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    if num_channels == 0 || bits_per_sample == 0 { return Err(Error::other("invalid AIFF file: no COMM chunk")); }
+    let Some((data_start, data_len)) = sample_data else { return Err(Error::other("invalid AIFF file: no SSND chunk")); };
+    if bits_per_sample > 32 { return Err(Error::other(format!("unsupported AIFF: {} bits per sample", bits_per_sample))); }
+
+    let bytes_per_sample = bits_per_sample.div_ceil(8) as usize;
+    let num_frames = num_frames.min(data_len / (bytes_per_sample * num_channels));
+    let mut channels = vec![Vec::with_capacity(num_frames); num_channels];
+    let mut r = Reader::new(data);
+    r.seek(data_start)?;
+    for _ in 0..num_frames {
+        for ch in channels.iter_mut() {
+            let mut bytes = [0u8; 4];
+            r.read_bytes(&mut bytes[..bytes_per_sample])?;
+            if little_endian { bytes[..bytes_per_sample].reverse(); }
+            // keep the 16 most significant bits
+            let sample = if bytes_per_sample == 1 {
+                (bytes[0] as i8 as i16) << 8
+            } else {
+                i16::from_be_bytes([bytes[0], bytes[1]])
+            };
+            ch.push(sample);
+        }
+    }
+
+    let (loop_start, loop_len) = match sustain_loop {
+        Some((begin_marker, end_marker)) => {
+            let find_marker = |id| markers.iter().find(|(marker_id, _)| *marker_id == id).map(|&(_, position)| position);
+            match (find_marker(begin_marker), find_marker(end_marker)) {
+                (Some(begin), Some(end)) => split_loop(begin, end, num_frames),
+                _ => (0, 0),
+            }
+        }
+        None => (0, 0),
+    };
+
+    Ok(WavFile {
+        channels,
+        bits_per_sample: if bits_per_sample <= 8 { 8 } else { 16 },
+        sample_rate: sample_rate.round() as u32,
+        loop_start,
+        loop_len,
+    })
+}
+
+fn decode_fib_delta(data: &[u8]) -> Vec<i8> {
+    // first byte is padding, second is the initial value
+    let mut samples = Vec::with_capacity(data.len().saturating_sub(2) * 2);
+    let Some(&initial) = data.get(1) else { return samples; };
+    let mut value = initial as i8;
+    for &byte in data.iter().skip(2) {
+        for nibble in [byte >> 4, byte & 0xf] {
+            value = value.wrapping_add(SVX_FIB_DELTA_TABLE[nibble as usize]);
+            samples.push(value);
+        }
+    }
+    samples
+}
+
+pub fn read_8svx(data: &[u8]) -> Result<WavFile> {
+    let mut header = None;
+    let mut body = None;
+    let mut stereo = false;
+
+    read_iff_chunks(data, &[b"8SVX"], |tag, r, chunk_size| {
+        match tag {
+            b"VHDR" => {
+                let one_shot_samples = r.read_u32_be()?;
+                let repeat_samples = r.read_u32_be()?;
+                r.read_u32_be()?;  // samples per high cycle
+                let sample_rate = r.read_u16_be()?;
+                r.read_u8()?;  // number of octaves
+                let compression = r.read_u8()?;
+                header = Some((one_shot_samples, repeat_samples, sample_rate, compression));
+            }
+
+            b"CHAN" => {
+                stereo = r.read_u32_be()? == SVX_CHAN_STEREO;
+            }
+
+            b"BODY" => {
+                body = Some((r.pos(), chunk_size));
+            }
+
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    let Some((one_shot_samples, repeat_samples, sample_rate, compression)) = header else {
+        return Err(Error::other("invalid 8SVX file: no VHDR chunk"));
+    };
+    let Some((body_start, body_len)) = body else { return Err(Error::other("invalid 8SVX file: no BODY chunk")); };
+    let body = &data[body_start..body_start + body_len];
+
+    // stereo files have the left channel data followed by the right channel
+    let channel_bodies = if stereo { vec![&body[..body.len()/2], &body[body.len()/2..]] } else { vec![body] };
+    let mut channels = Vec::new();
+    for channel_body in channel_bodies {
+        let samples = match compression {
+            SVX_COMPRESSION_NONE => channel_body.iter().map(|&b| b as i8).collect(),
+            SVX_COMPRESSION_FIB_DELTA => decode_fib_delta(channel_body),
+            _ => { return Err(Error::other(format!("unsupported 8SVX compression: {}", compression))); }
+        };
+
+        // only keep the first (highest) octave
+        let octave_len = (one_shot_samples + repeat_samples) as usize;
+        let len = if octave_len > 0 { octave_len.min(samples.len()) } else { samples.len() };
+        channels.push(samples[..len].iter().map(|&s| (s as i16) << 8).collect::<Vec<_>>());
+    }
+
+    let len = channels.first().map_or(0, |ch| ch.len());
+    let (loop_start, loop_len) = if repeat_samples > 0 {
+        split_loop(one_shot_samples, one_shot_samples + repeat_samples, len)
+    } else {
+        (0, 0)
+    };
+
+    Ok(WavFile {
+        channels,
+        bits_per_sample: 8,
+        sample_rate: sample_rate as u32,
+        loop_start,
+        loop_len,
+    })
+}
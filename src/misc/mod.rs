@@ -1,6 +1,9 @@
 pub mod asset_defs;
+pub mod iff_utils;
 pub mod image_table;
 pub mod mod_utils;
+pub mod sample_utils;
+pub mod sfx_synth;
 pub mod wav_utils;
 pub mod reader;
 pub mod writer;
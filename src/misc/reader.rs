@@ -14,6 +14,10 @@ impl<'a> Reader<'a> {
         }
     }
 
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
     pub fn seek(&mut self, pos: usize) -> Result<()> {
         if pos > self.data.len() {
             return Err(Error::other("seek past end of buffer"));
@@ -72,6 +76,16 @@ impl<'a> Reader<'a> {
         Ok(f32::from_bits(bits))
     }
 
+    // 80-bit IEEE 754 extended precision, as used by AIFF
+    pub fn read_f80_be(&mut self) -> Result<f64> {
+        let sign_exp = self.read_u16_be()?;
+        let mantissa = ((self.read_u32_be()? as u64) << 32) | self.read_u32_be()? as u64;
+        if sign_exp & 0x7fff == 0 && mantissa == 0 { return Ok(0.0); }
+        let exp = (sign_exp & 0x7fff) as i32 - 16383 - 63;
+        let val = mantissa as f64 * 2f64.powi(exp);
+        Ok(if sign_exp & 0x8000 != 0 { -val } else { val })
+    }
+
     pub fn read_bytes(&mut self, data: &mut [u8]) -> Result<()> {
         let len = data.len();
         if self.pos+len > self.data.len() {
@@ -0,0 +1,74 @@
+const SEAM_FADE_LEN: usize = 64;
+
+fn is_zero_crossing(samples: &[i16], index: usize) -> bool {
+    if index == 0 || index >= samples.len() { return true; }
+    samples[index] == 0 || (samples[index - 1] < 0) != (samples[index] < 0)
+}
+
+/**
+ * Return the zero crossing closest to `index`, looking at most
+ * `max_dist` samples away. Returns `index` if there's none.
+ */
+pub fn find_zero_crossing(samples: &[i16], index: u32, max_dist: u32) -> u32 {
+    let index = (index as usize).min(samples.len());
+    for dist in 0..=max_dist as usize {
+        if index >= dist && is_zero_crossing(samples, index - dist) {
+            return (index - dist) as u32;
+        }
+        if index + dist <= samples.len() && is_zero_crossing(samples, index + dist) {
+            return (index + dist) as u32;
+        }
+    }
+    index as u32
+}
+
+/**
+ * Blend the end of the loop into the samples just before the loop
+ * start, so that jumping from the loop end to the loop start is
+ * seamless. Returns the number of samples actually crossfaded (limited
+ * by the loop length and the number of samples before the loop).
+ */
+pub fn crossfade_loop(samples: &mut [i16], loop_start: u32, loop_end: u32, len: u32) -> u32 {
+    let loop_end = (loop_end as usize).min(samples.len());
+    let loop_start = (loop_start as usize).min(loop_end);
+    let len = (len as usize).min(loop_start).min(loop_end - loop_start);
+    for i in 0..len {
+        let t = (i + 1) as f32 / len as f32;
+        let src = samples[loop_start - len + i] as f32;
+        let dest = &mut samples[loop_end - len + i];
+        *dest = (*dest as f32 * (1.0 - t) + src * t).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+    }
+    len as u32
+}
+
+/**
+ * Build a sound that plays the jump from the loop end to the loop
+ * start `repeats` times, with `seam_len` samples on each side of the
+ * seam, separated by short silences.
+ */
+pub fn loop_seam_preview(samples: &[i16], loop_start: u32, loop_end: u32, seam_len: u32, repeats: usize) -> Vec<i16> {
+    let loop_end = (loop_end as usize).min(samples.len());
+    let loop_start = (loop_start as usize).min(loop_end);
+    let seam_len = (seam_len as usize).min(loop_end - loop_start);
+    if seam_len == 0 { return Vec::new(); }
+
+    let mut seam = Vec::with_capacity(2 * seam_len);
+    seam.extend_from_slice(&samples[loop_end - seam_len..loop_end]);
+    seam.extend_from_slice(&samples[loop_start..loop_start + seam_len]);
+
+    // fade in and out so the only click heard is the seam itself
+    let fade_len = SEAM_FADE_LEN.min(seam_len);
+    let seam_total = seam.len();
+    for i in 0..fade_len {
+        let t = i as f32 / fade_len as f32;
+        seam[i] = (seam[i] as f32 * t) as i16;
+        seam[seam_total - 1 - i] = (seam[seam_total - 1 - i] as f32 * t) as i16;
+    }
+
+    let mut preview = Vec::with_capacity(repeats * 3 * seam_len);
+    for _ in 0..repeats {
+        preview.extend_from_slice(&seam);
+        preview.resize(preview.len() + seam_len, 0);
+    }
+    preview
+}
@@ -0,0 +1,430 @@
+use crate::data_asset::{
+    SfxSynthWave,
+    SfxSynthParams,
+};
+
+// The generator follows the classic sfxr design: the sound is
+// rendered at 44100 "ticks" per second, each tick with 8 sub-samples
+// for the oscillator and filters. The result is then resampled to the
+// requested sample rate.
+const TICK_RATE: f32 = 44100.0;
+const SUPER_SAMPLES: usize = 8;
+const ENV_TICKS_SCALE: f32 = 100000.0;
+const NOISE_BUFFER_LEN: usize = 32;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SfxSynthCategory {
+    Wave,
+    Envelope,
+    Frequency,
+    Vibrato,
+    Arpeggio,
+    Filter,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SfxSynthPreset {
+    Coin,
+    Jump,
+    Laser,
+    Explosion,
+    PowerUp,
+    Hit,
+    Blip,
+}
+
+impl SfxSynthPreset {
+    pub const ALL: &[SfxSynthPreset] = &[
+        SfxSynthPreset::Coin,
+        SfxSynthPreset::Jump,
+        SfxSynthPreset::Laser,
+        SfxSynthPreset::Explosion,
+        SfxSynthPreset::PowerUp,
+        SfxSynthPreset::Hit,
+        SfxSynthPreset::Blip,
+    ];
+
+    const TEXT: &[&str] = &[
+        "Coin",
+        "Jump",
+        "Laser",
+        "Explosion",
+        "Power-up",
+        "Hit",
+        "Blip",
+    ];
+
+    pub fn text(&self) -> &'static str {
+        Self::TEXT[*self as usize]
+    }
+}
+
+/**
+Small xorshift random number generator, so the generator doesn't need
+an external crate (and renders the same noise for the same parameters).
+*/
+pub struct SfxSynthRng {
+    state: u32,
+}
+
+impl SfxSynthRng {
+    pub fn new(seed: u32) -> Self {
+        SfxSynthRng {
+            state: if seed == 0 { 0x2545_f491 } else { seed },
+        }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    // random number in 0..1
+    pub fn unit(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    // random number in -1..1
+    pub fn signed(&mut self) -> f32 {
+        self.unit() * 2.0 - 1.0
+    }
+
+    pub fn chance(&mut self, one_in: u32) -> bool {
+        self.next_u32().is_multiple_of(one_in)
+    }
+}
+
+struct Envelope {
+    stage_len: [u32; 4],
+    sustain_level: f32,
+}
+
+impl Envelope {
+    fn new(params: &SfxSynthParams) -> Self {
+        let ticks = |v: f32| (v * v * ENV_TICKS_SCALE) as u32;
+        Envelope {
+            stage_len: [
+                ticks(params.attack),
+                ticks(params.decay),
+                ticks(params.sustain),
+                ticks(params.release),
+            ],
+            sustain_level: params.sustain_level,
+        }
+    }
+
+    fn total_ticks(&self) -> u32 {
+        self.stage_len.iter().sum()
+    }
+
+    fn volume(&self, tick: u32) -> f32 {
+        let mut t = tick;
+        for (stage, &len) in self.stage_len.iter().enumerate() {
+            if t < len {
+                let pos = t as f32 / len as f32;
+                return match stage {
+                    0 => pos,
+                    1 => 1.0 - pos * (1.0 - self.sustain_level),
+                    2 => self.sustain_level,
+                    _ => self.sustain_level * (1.0 - pos),
+                };
+            }
+            t -= len;
+        }
+        0.0
+    }
+}
+
+fn period_for(freq: f32) -> f64 {
+    100.0 / (freq as f64 * freq as f64 + 0.001)
+}
+
+/**
+Render the sound described by `params`, returning samples in the range
+-1..1 at `params.sample_rate`.
+*/
+pub fn render(params: &SfxSynthParams) -> Vec<f32> {
+    let env = Envelope::new(params);
+    let total_ticks = env.total_ticks();
+    let mut rng = SfxSynthRng::new(0x1234_5678);
+
+    // frequency
+    let mut period = period_for(params.base_freq);
+    let max_period = period_for(params.min_freq);
+    let mut slide = 1.0 - (params.freq_slide as f64).powi(3) * 0.01;
+    let delta_slide = -(params.freq_delta_slide as f64).powi(3) * 0.000001;
+
+    // vibrato
+    let vib_amp = params.vibrato_depth as f64 * 0.5;
+    let vib_speed = (params.vibrato_speed as f64).powi(2) * 0.01;
+    let mut vib_phase = 0.0f64;
+
+    // arpeggio
+    let arp_mult = params.arp_mult as f64;
+    let arp_factor = if arp_mult >= 0.0 { 1.0 - arp_mult * arp_mult * 0.9 } else { 1.0 + arp_mult * arp_mult * 10.0 };
+    let mut arp_limit = if arp_mult != 0.0 {
+        Some(((1.0 - params.arp_speed).powi(2) * 20000.0 + 32.0) as u32)
+    } else {
+        None
+    };
+
+    // square duty
+    let mut duty = 0.5 - params.duty * 0.5;
+    let duty_slide = -params.duty_sweep * 0.00005;
+
+    // filters
+    let lp_enabled = params.lp_cutoff < 1.0;
+    let mut lp_w = params.lp_cutoff.powi(3) * 0.1;
+    let lp_w_d = 1.0 + params.lp_sweep * 0.0001;
+    let lp_damp = (5.0 / (1.0 + params.lp_resonance.powi(2) * 20.0) * (0.01 + lp_w)).min(0.8);
+    let mut lp_pos = 0.0f32;
+    let mut lp_delta = 0.0f32;
+    let mut hp = params.hp_cutoff.powi(2) * 0.1;
+    let hp_d = 1.0 + params.hp_sweep * 0.0003;
+    let mut hp_pos = 0.0f32;
+
+    let mut phase = 0.0f64;
+    let mut noise_buffer = [0.0f32; NOISE_BUFFER_LEN];
+    for v in noise_buffer.iter_mut() { *v = rng.signed(); }
+
+    let mut ticks = Vec::with_capacity(total_ticks as usize);
+    for tick in 0..total_ticks {
+        if let Some(limit) = arp_limit && tick >= limit {
+            arp_limit = None;
+            period *= arp_factor;
+        }
+
+        slide += delta_slide;
+        period *= slide;
+        if period > max_period {
+            period = max_period;
+            if params.min_freq > 0.0 { break; }
+        }
+
+        let mut cur_period = period;
+        if vib_amp > 0.0 {
+            vib_phase += vib_speed;
+            cur_period = period * (1.0 + vib_phase.sin() * vib_amp);
+        }
+        let cur_period = cur_period.max(8.0);
+
+        duty = (duty + duty_slide).clamp(0.0, 0.5);
+        hp = (hp * hp_d).clamp(0.00001, 0.1);
+
+        let env_vol = env.volume(tick);
+        let mut sample_sum = 0.0;
+        for _ in 0..SUPER_SAMPLES {
+            phase += 1.0 / cur_period;
+            if phase >= 1.0 {
+                phase = phase.fract();
+                if params.wave == SfxSynthWave::Noise {
+                    for v in noise_buffer.iter_mut() { *v = rng.signed(); }
+                }
+            }
+            let fp = phase as f32;
+            let mut sample = match params.wave {
+                SfxSynthWave::Square => if fp < duty { 1.0 } else { -1.0 },
+                SfxSynthWave::Sawtooth => 1.0 - fp * 2.0,
+                SfxSynthWave::Triangle => if fp < 0.5 { fp * 4.0 - 1.0 } else { 3.0 - fp * 4.0 },
+                SfxSynthWave::Sine => (fp * std::f32::consts::TAU).sin(),
+                SfxSynthWave::Noise => noise_buffer[((fp * NOISE_BUFFER_LEN as f32) as usize).min(NOISE_BUFFER_LEN - 1)],
+            };
+            if params.noise > 0.0 {
+                sample = sample * (1.0 - params.noise) + rng.signed() * params.noise;
+            }
+
+            // low-pass and high-pass filters
+            let last_lp_pos = lp_pos;
+            lp_w = (lp_w * lp_w_d).clamp(0.0, 0.1);
+            if lp_enabled {
+                lp_delta += (sample - lp_pos) * lp_w;
+                lp_delta -= lp_delta * lp_damp;
+            } else {
+                lp_pos = sample;
+                lp_delta = 0.0;
+            }
+            lp_pos += lp_delta;
+            hp_pos += lp_pos - last_lp_pos;
+            hp_pos -= hp_pos * hp;
+
+            sample_sum += hp_pos * env_vol;
+        }
+        ticks.push(sample_sum / SUPER_SAMPLES as f32 * params.volume);
+    }
+
+    resample(&ticks, params.sample_rate)
+}
+
+// box-filter resample from `TICK_RATE` to the given rate
+fn resample(ticks: &[f32], sample_rate: u32) -> Vec<f32> {
+    let step = sample_rate as f64 / TICK_RATE as f64;
+    let mut samples = Vec::with_capacity((ticks.len() as f64 * step) as usize + 1);
+    let mut sum = 0.0;
+    let mut count = 0;
+    for (i, &tick) in ticks.iter().enumerate() {
+        sum += tick;
+        count += 1;
+        if ((i + 1) as f64 * step) as usize > samples.len() {
+            samples.push(sum / count as f32);
+            sum = 0.0;
+            count = 0;
+        }
+    }
+    if count > 0 {
+        samples.push(sum / count as f32);
+    }
+    samples
+}
+
+/**
+Render the sound described by `params` to samples suitable for an
+`Sfx` with the given bits per sample.
+*/
+pub fn render_samples(params: &SfxSynthParams, bits_per_sample: u16) -> Vec<i16> {
+    render(params).iter().map(|&s| {
+        let s = s.clamp(-1.0, 1.0);
+        if bits_per_sample == 8 {
+            ((s * i8::MAX as f32).round() as i16) << 8
+        } else {
+            (s * i16::MAX as f32).round() as i16
+        }
+    }).collect()
+}
+
+pub fn randomize(params: &mut SfxSynthParams, category: SfxSynthCategory, rng: &mut SfxSynthRng) {
+    match category {
+        SfxSynthCategory::Wave => {
+            params.wave = SfxSynthWave::ALL[(rng.unit() * SfxSynthWave::ALL.len() as f32) as usize % SfxSynthWave::ALL.len()];
+            params.duty = rng.unit();
+            params.duty_sweep = if rng.chance(2) { rng.signed().powi(3) } else { 0.0 };
+            params.noise = if rng.chance(4) { rng.unit() * 0.5 } else { 0.0 };
+        }
+
+        SfxSynthCategory::Envelope => {
+            params.attack = if rng.chance(3) { rng.unit().powi(3) * 0.5 } else { 0.0 };
+            params.decay = rng.unit() * 0.5;
+            params.sustain = 0.1 + rng.unit() * 0.4;
+            params.sustain_level = 0.3 + rng.unit() * 0.7;
+            params.release = 0.1 + rng.unit() * 0.5;
+        }
+
+        SfxSynthCategory::Frequency => {
+            params.base_freq = 0.1 + rng.unit() * 0.7;
+            params.freq_slide = rng.signed().powi(5);
+            params.freq_delta_slide = if rng.chance(3) { rng.signed().powi(3) * 0.5 } else { 0.0 };
+            params.min_freq = if params.freq_slide < 0.0 && rng.chance(2) { rng.unit() * params.base_freq } else { 0.0 };
+        }
+
+        SfxSynthCategory::Vibrato => {
+            params.vibrato_depth = rng.unit().powi(2) * 0.7;
+            params.vibrato_speed = rng.unit();
+        }
+
+        SfxSynthCategory::Arpeggio => {
+            params.arp_mult = rng.signed();
+            params.arp_speed = rng.unit();
+        }
+
+        SfxSynthCategory::Filter => {
+            params.lp_cutoff = if rng.chance(2) { 1.0 } else { 1.0 - rng.unit().powi(3) };
+            params.lp_sweep = rng.signed().powi(3);
+            params.lp_resonance = rng.unit();
+            params.hp_cutoff = if rng.chance(2) { 0.0 } else { rng.unit().powi(3) * 0.5 };
+            params.hp_sweep = rng.signed().powi(3);
+        }
+    }
+}
+
+pub fn preset(preset: SfxSynthPreset, sample_rate: u32, rng: &mut SfxSynthRng) -> SfxSynthParams {
+    let mut params = SfxSynthParams::new();
+    params.sample_rate = sample_rate;
+    params.sustain_level = 1.0;
+
+    match preset {
+        SfxSynthPreset::Coin => {
+            params.base_freq = 0.4 + rng.unit() * 0.5;
+            params.sustain = rng.unit() * 0.1 + 0.05;
+            params.release = 0.1 + rng.unit() * 0.4;
+            if rng.chance(2) {
+                params.arp_speed = 0.5 + rng.unit() * 0.2;
+                params.arp_mult = 0.2 + rng.unit() * 0.4;
+            }
+        }
+
+        SfxSynthPreset::Jump => {
+            params.duty = rng.unit() * 0.6;
+            params.base_freq = 0.3 + rng.unit() * 0.3;
+            params.freq_slide = 0.1 + rng.unit() * 0.2;
+            params.sustain = 0.1 + rng.unit() * 0.3;
+            params.release = 0.1 + rng.unit() * 0.2;
+            if rng.chance(2) { params.hp_cutoff = rng.unit() * 0.3; }
+            if rng.chance(2) { params.lp_cutoff = 1.0 - rng.unit() * 0.6; }
+        }
+
+        SfxSynthPreset::Laser => {
+            params.wave = [SfxSynthWave::Square, SfxSynthWave::Sawtooth, SfxSynthWave::Sine][(rng.unit() * 3.0) as usize % 3];
+            params.base_freq = 0.5 + rng.unit() * 0.5;
+            params.min_freq = (params.base_freq - 0.2 - rng.unit() * 0.6).max(0.2);
+            params.freq_slide = -0.15 - rng.unit() * 0.2;
+            params.duty = rng.unit() * 0.5;
+            params.duty_sweep = rng.unit() * 0.2;
+            params.sustain = 0.1 + rng.unit() * 0.2;
+            params.release = rng.unit() * 0.4;
+            if rng.chance(2) { params.hp_cutoff = rng.unit() * 0.3; }
+        }
+
+        SfxSynthPreset::Explosion => {
+            params.wave = SfxSynthWave::Noise;
+            params.base_freq = (0.1 + rng.unit() * 0.4).powi(2);
+            params.freq_slide = -0.1 + rng.unit() * 0.4;
+            if rng.chance(5) { params.freq_slide = 0.0; }
+            params.sustain = 0.1 + rng.unit() * 0.3;
+            params.release = rng.unit() * 0.5;
+            if rng.chance(2) {
+                params.vibrato_depth = rng.unit() * 0.7;
+                params.vibrato_speed = rng.unit() * 0.6;
+            }
+            if rng.chance(3) {
+                params.arp_speed = 0.6 + rng.unit() * 0.3;
+                params.arp_mult = 0.8 - rng.unit() * 1.6;
+            }
+        }
+
+        SfxSynthPreset::PowerUp => {
+            params.wave = if rng.chance(2) { SfxSynthWave::Sawtooth } else { SfxSynthWave::Square };
+            params.duty = rng.unit() * 0.6;
+            params.base_freq = 0.2 + rng.unit() * 0.3;
+            params.freq_slide = 0.1 + rng.unit() * 0.4;
+            if rng.chance(2) {
+                params.vibrato_depth = rng.unit() * 0.7;
+                params.vibrato_speed = rng.unit() * 0.6;
+            }
+            params.sustain = rng.unit() * 0.4 + 0.1;
+            params.release = 0.1 + rng.unit() * 0.4;
+        }
+
+        SfxSynthPreset::Hit => {
+            params.wave = [SfxSynthWave::Square, SfxSynthWave::Sawtooth, SfxSynthWave::Noise][(rng.unit() * 3.0) as usize % 3];
+            params.duty = rng.unit() * 0.6;
+            params.base_freq = 0.2 + rng.unit() * 0.6;
+            params.freq_slide = -0.3 - rng.unit() * 0.4;
+            params.sustain = rng.unit() * 0.1 + 0.02;
+            params.release = 0.1 + rng.unit() * 0.2;
+            if rng.chance(2) { params.hp_cutoff = rng.unit() * 0.3; }
+        }
+
+        SfxSynthPreset::Blip => {
+            params.wave = if rng.chance(2) { SfxSynthWave::Sine } else { SfxSynthWave::Square };
+            params.duty = rng.unit() * 0.6;
+            params.base_freq = 0.2 + rng.unit() * 0.4;
+            params.sustain = 0.1 + rng.unit() * 0.1;
+            params.release = rng.unit() * 0.2;
+            params.hp_cutoff = 0.1;
+        }
+    }
+    params
+}
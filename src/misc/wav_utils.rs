@@ -2,14 +2,77 @@ use std::io::{Result, Error};
 
 const WAV_FORMAT_PCM: u16 = 1;
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum WavMixdown {
+    Left,
+    Right,
+    Mid,
+}
+
+impl WavMixdown {
+    pub const ALL: &[WavMixdown] = &[
+        WavMixdown::Left,
+        WavMixdown::Right,
+        WavMixdown::Mid,
+    ];
+
+    const TEXT: &[&str] = &[
+        "Left",
+        "Right",
+        "Mid (L+R)",
+    ];
+
+    pub fn text(&self) -> &'static str {
+        Self::TEXT[*self as usize]
+    }
+}
+
+pub struct WavImportOptions {
+    pub mixdown: WavMixdown,
+    pub resample_rate: Option<u32>,
+    pub quantize_8bit: bool,
+    pub dither: bool,
+}
+
 pub struct WavFile {
     pub channels: Vec<Vec<i16>>,
     pub bits_per_sample: u16,
-    #[allow(dead_code)]
     pub sample_rate: u32,
+    pub loop_start: u32,
+    pub loop_len: u32,
 }
 
 impl WavFile {
+    pub fn mix_down(&self, mixdown: WavMixdown) -> Vec<i16> {
+        match mixdown {
+            WavMixdown::Left => self.channels.first().cloned().unwrap_or_default(),
+            WavMixdown::Right => self.channels.get(1).or(self.channels.first()).cloned().unwrap_or_default(),
+            WavMixdown::Mid => {
+                let num_channels = self.channels.len();
+                let len = self.channels.iter().map(|ch| ch.len()).min().unwrap_or(0);
+                (0..len).map(|i| {
+                    let sum = self.channels.iter().fold(0i32, |sum, ch| sum + ch[i] as i32);
+                    (sum / num_channels as i32) as i16
+                }).collect()
+            }
+        }
+    }
+
+    /**
+     * Convert the file to a single channel of samples according to the
+     * options, returning the samples and their bits per sample.
+     */
+    pub fn convert(&self, options: &WavImportOptions) -> (Vec<i16>, u16) {
+        let mut samples = self.mix_down(options.mixdown);
+        if let Some(rate) = options.resample_rate && rate != self.sample_rate && self.sample_rate != 0 {
+            samples = resample(&samples, self.sample_rate, rate);
+        }
+        if options.quantize_8bit {
+            (quantize_8bit(&samples, options.dither), 8)
+        } else {
+            (samples, if self.bits_per_sample == 8 { 8 } else { 16 })
+        }
+    }
 
     fn read_24bit_sample(r: &mut super::reader::Reader) -> Result<i16> {
         r.read_u8()?; // ignore low 8 bits
@@ -81,6 +144,8 @@ impl WavFile {
                         channels,
                         bits_per_sample,
                         sample_rate,
+                        loop_start: 0,
+                        loop_len: 0,
                     });
                 }
 
@@ -129,3 +194,50 @@ impl WavFile {
         Ok(w.take_data())
     }
 }
+
+/**
+ * Resample using a box filter when reducing the sample rate, and
+ * linear interpolation when increasing it.
+ */
+pub fn resample(samples: &[i16], from_rate: u32, to_rate: u32) -> Vec<i16> {
+    if samples.is_empty() || from_rate == to_rate { return samples.to_vec(); }
+
+    let step = from_rate as f64 / to_rate as f64;
+    let out_len = ((samples.len() as f64) / step).floor().max(1.0) as usize;
+    let mut out = Vec::with_capacity(out_len);
+    for i in 0..out_len {
+        let pos = i as f64 * step;
+        if step > 1.0 {
+            let start = pos as usize;
+            let end = (((i + 1) as f64 * step) as usize).clamp(start + 1, samples.len());
+            let sum = samples[start..end].iter().fold(0i64, |sum, &s| sum + s as i64);
+            out.push((sum / (end - start) as i64) as i16);
+        } else {
+            let index = pos as usize;
+            let frac = pos - index as f64;
+            let s1 = samples[index] as f64;
+            let s2 = *samples.get(index + 1).unwrap_or(&samples[index]) as f64;
+            out.push((s1 + (s2 - s1) * frac).round() as i16);
+        }
+    }
+    out
+}
+
+/**
+ * Reduce samples to 8 bits (keeping them in the 16-bit range),
+ * optionally adding triangular dither to mask the quantization noise.
+ */
+pub fn quantize_8bit(samples: &[i16], dither: bool) -> Vec<i16> {
+    let mut seed = 0x1234_5678u32;
+    let mut rand = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        (seed & 0xff) as i32
+    };
+    samples.iter().map(|&s| {
+        let noise = if dither { rand() - rand() } else { 0 };
+        let v = (s as i32 + noise + 0x80).clamp(i16::MIN as i32, i16::MAX as i32);
+        ((v >> 8) << 8) as i16
+    }).collect()
+}
@@ -25,12 +25,14 @@ mod sound_impl {
 
 pub struct SoundPlayer {
     player: Result<sound_impl::SoundPlayerImpl, String>,
+    play_id: u64,
 }
 
 impl SoundPlayer {
     pub fn new() -> Self {
         SoundPlayer {
             player: sound_impl::SoundPlayerImpl::start(),
+            play_id: 0,
         }
     }
 
@@ -45,9 +47,38 @@ impl SoundPlayer {
         self.player.is_ok()
     }
 
-    pub fn play_s16(&mut self, samples: &[i16], freq: f32, volume: f32) {
+    pub fn play_s16(&mut self, samples: &[i16], freq: f32, volume: f32) -> u64 {
+        self.play_s16_loop(samples, freq, volume, 0, 0)
+    }
+
+    /**
+     * Play the samples, looping `loop_len` samples starting at
+     * `loop_start` until stopped (no looping if `loop_len` is 0).
+     * Returns an id that can be used to query the play position.
+     */
+    pub fn play_s16_loop(&mut self, samples: &[i16], freq: f32, volume: f32, loop_start: u32, loop_len: u32) -> u64 {
+        self.play_id += 1;
+        if let Ok(player) = &mut self.player {
+            player.play_s16(samples, freq, volume, loop_start, loop_len);
+        }
+        self.play_id
+    }
+
+    pub fn stop(&mut self) {
         if let Ok(player) = &mut self.player {
-            player.play_s16(samples, freq, volume);
+            player.stop();
+        }
+    }
+
+    /**
+     * Return the sample currently playing, if the sound
+     * started with `play_id` is still playing.
+     */
+    pub fn position(&self, play_id: u64) -> Option<f32> {
+        if play_id != self.play_id { return None; }
+        match &self.player {
+            Ok(player) => player.position(),
+            Err(_) => None,
         }
     }
 }
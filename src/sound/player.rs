@@ -6,6 +6,8 @@ pub struct Player {
     play_pos: f32,
     play_step: f32,
     play_volume: f32,
+    loop_start: usize,
+    loop_end: usize,
     use_filter: bool,
 }
 
@@ -17,6 +19,8 @@ impl Player {
             play_pos: 0.0,
             play_step: 0.0,
             play_volume: 0.0,
+            loop_start: 0,
+            loop_end: 0,
             num_channels,
             sample_rate,
             use_filter,
@@ -24,24 +28,61 @@ impl Player {
     }
 
     pub fn setup(&mut self, data: &[i16], freq: f32, volume: f32) {
+        self.setup_loop(data, freq, volume, 0, 0);
+    }
+
+    pub fn setup_loop(&mut self, data: &[i16], freq: f32, volume: f32, loop_start: u32, loop_len: u32) {
         self.data.clear();
         self.data.extend_from_slice(data);
         self.play_pos = 0.0;
         self.play_step = freq / self.sample_rate;
         self.play_volume = volume;
+        self.loop_end = ((loop_start + loop_len) as usize).min(self.data.len());
+        self.loop_start = (loop_start as usize).min(self.loop_end);
+    }
+
+    pub fn stop(&mut self) {
+        self.play_pos = self.data.len() as f32;
+        self.loop_start = 0;
+        self.loop_end = 0;
+    }
+
+    pub fn position(&self) -> Option<f32> {
+        if self.play_pos < self.data.len() as f32 { Some(self.play_pos) } else { None }
+    }
+
+    fn is_looping(&self) -> bool {
+        self.loop_end > self.loop_start
+    }
+
+    fn wrap_pos(&self, pos: f32) -> f32 {
+        if self.is_looping() && pos >= self.loop_end as f32 {
+            let loop_len = (self.loop_end - self.loop_start) as f32;
+            self.loop_start as f32 + (pos - self.loop_start as f32) % loop_len
+        } else {
+            pos
+        }
+    }
+
+    fn wrap_index(&self, index: usize) -> usize {
+        if self.is_looping() && index >= self.loop_end {
+            self.loop_start + (index - self.loop_start) % (self.loop_end - self.loop_start)
+        } else {
+            index
+        }
     }
 
     fn render_samples_raw(&mut self, data: &mut [i16]) {
         let mut play_pos = self.play_pos;
         let play_step = self.play_step;
         for spl in data.chunks_exact_mut(self.num_channels) {
-            let spl_index = play_pos.round() as usize;
+            let spl_index = self.wrap_index(play_pos.round() as usize);
             let out_spl = if spl_index >= self.data.len() {
                 0
             } else {
                 (self.data[spl_index] as f32 * self.play_volume).clamp(i16::MIN as f32, i16::MAX as f32) as i16
             };
-            play_pos += play_step;
+            play_pos = self.wrap_pos(play_pos + play_step);
             for s in spl.iter_mut().take(self.num_channels) {
                 *s = out_spl;
             }
@@ -62,7 +103,7 @@ impl Player {
             let play_pos_floor = play_pos.floor();
             let play_pos_frac = play_pos - play_pos_floor;
             let last_index = play_pos_floor as usize;
-            let next_index = play_pos.ceil() as usize;
+            let next_index = self.wrap_index(play_pos.ceil() as usize);
             let out_spl = if last_index >= self.data.len() || next_index >= self.data.len() {
                 0
             } else {
@@ -70,7 +111,7 @@ impl Player {
                 let next = (self.data[next_index] as f32 * self.play_volume).clamp(i16::MIN as f32, i16::MAX as f32);
                 (last * (1.0 - play_pos_frac) + next * play_pos_frac) as i16
             };
-            play_pos += play_step;
+            play_pos = self.wrap_pos(play_pos + play_step);
             for s in spl.iter_mut().take(self.num_channels) {
                 *s = out_spl;
             }
@@ -63,11 +63,21 @@ impl SoundPlayerImpl {
         Self::open_sound().map_err(|e| e.to_string())
     }
 
-    pub fn play_s16(&mut self, samples: &[i16], freq: f32, volume: f32) {
+    pub fn play_s16(&mut self, samples: &[i16], freq: f32, volume: f32, loop_start: u32, loop_len: u32) {
         {
             let mut player = self.player.lock().unwrap();
-            player.setup(samples, freq, volume);
+            player.setup_loop(samples, freq, volume, loop_start, loop_len);
         }
         self.stream.play().unwrap_or(());
     }
+
+    pub fn stop(&mut self) {
+        let mut player = self.player.lock().unwrap();
+        player.stop();
+    }
+
+    pub fn position(&self) -> Option<f32> {
+        let player = self.player.lock().unwrap();
+        player.position()
+    }
 }
@@ -9,6 +9,13 @@ impl SoundPlayerImpl {
         Err("sound disabled during compilation".to_owned())
     }
 
-    pub fn play_s16(&mut self, _samples: &[i16], _freq: f32, _volume: f32) {
+    pub fn play_s16(&mut self, _samples: &[i16], _freq: f32, _volume: f32, _loop_start: u32, _loop_len: u32) {
+    }
+
+    pub fn stop(&mut self) {
+    }
+
+    pub fn position(&self) -> Option<f32> {
+        None
     }
 }
@@ -5,6 +5,10 @@ pub struct SoundPlayerImpl {
     pub name: String,
     soloud: soloud::Soloud,
     wav: soloud::audio::Wav,
+    handle: Option<soloud::Handle>,
+    freq: f32,
+    loop_start: u32,
+    loop_end: u32,
 }
 
 impl SoundPlayerImpl {
@@ -15,17 +19,51 @@ impl SoundPlayerImpl {
             name: "soloud".to_string(),
             soloud,
             wav,
+            handle: None,
+            freq: 1.0,
+            loop_start: 0,
+            loop_end: 0,
         })
     }
 
-    pub fn play_s16(&mut self, samples: &[i16], freq: f32, volume: f32) {
+    pub fn play_s16(&mut self, samples: &[i16], freq: f32, volume: f32, loop_start: u32, loop_len: u32) {
         self.wav.stop();
+        self.handle = None;
         self.soloud.set_global_volume(volume);
+
+        // soloud always loops at the end of the sound, so drop everything after the loop
+        let loop_end = ((loop_start + loop_len) as usize).min(samples.len());
+        let looping = loop_len > 0 && (loop_start as usize) < loop_end;
+        let samples = if looping { &samples[..loop_end] } else { samples };
         unsafe {
             if self.wav.load_raw_wav_16_ex(samples, freq, 1).is_err() {
                 return;
             }
         }
-        self.soloud.play(&self.wav);
+        self.wav.set_looping(looping);
+        if looping {
+            self.wav.set_loop_point(loop_start as f64 / freq as f64);
+        }
+        self.freq = freq;
+        self.loop_start = loop_start;
+        self.loop_end = loop_end as u32;
+        self.handle = Some(self.soloud.play(&self.wav));
+    }
+
+    pub fn stop(&mut self) {
+        self.wav.stop();
+        self.handle = None;
+    }
+
+    pub fn position(&self) -> Option<f32> {
+        let handle = self.handle?;
+        if ! self.soloud.is_valid_voice_handle(handle) { return None; }
+        let pos = (self.soloud.stream_position(handle) * self.freq as f64) as f32;
+        if self.loop_end > self.loop_start && pos >= self.loop_end as f32 {
+            let loop_len = (self.loop_end - self.loop_start) as f32;
+            Some(self.loop_start as f32 + (pos - self.loop_start as f32) % loop_len)
+        } else {
+            Some(pos)
+        }
     }
 }